@@ -0,0 +1,973 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AnchorDeserialize};
+use anyhow::{anyhow, Context};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use fusion_swap_client::{
+    fusion_swap, pda, permissions,
+    quote::{quote, FeeAmounts},
+    registry::ResolverPage,
+    whitelist, OrderQuote, Registration, ResolverAccess, Role, WhitelistState,
+};
+use order_file::OrderFile;
+use resolver_tree::ResolverTree;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    account::from_account,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    message::Message,
+    signature::{read_keypair_file, Keypair, Signer},
+    sysvar::{self, clock::Clock},
+    transaction::Transaction,
+};
+
+mod order_file;
+mod resolver_tree;
+
+#[derive(Parser)]
+#[command(about = "Manage Fusion Swap orders and the resolver whitelist")]
+struct Cli {
+    /// Cluster RPC url
+    #[arg(long, env = "CLUSTER_URL", global = true)]
+    url: Option<String>,
+
+    /// Simulate transactions and print the logs instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fusion Swap order operations
+    #[command(subcommand)]
+    Order(OrderCommand),
+    /// Resolver whitelist operations
+    #[command(subcommand)]
+    Whitelist(WhitelistCommand),
+}
+
+#[derive(Args)]
+struct OrderArgs {
+    /// Order config file path, as written by `scripts/fusion-swap/create.ts`
+    #[arg(long)]
+    order: String,
+}
+
+#[derive(Subcommand)]
+enum OrderCommand {
+    /// Create the escrow of the order
+    Create {
+        #[command(flatten)]
+        order: OrderArgs,
+        /// Maker keypair path
+        #[arg(long)]
+        maker_kp: String,
+    },
+    /// Fill the order
+    Fill {
+        #[command(flatten)]
+        order: OrderArgs,
+        /// Maker public key
+        #[arg(long)]
+        maker: Pubkey,
+        /// Taker keypair path
+        #[arg(long)]
+        taker_kp: String,
+        /// Whitelisted resolver the taker signs for as a delegate
+        #[arg(long)]
+        resolver: Option<Pubkey>,
+        /// Resolver tree file to prove the resolver with instead of its resolver access
+        #[arg(long)]
+        resolver_tree: Option<String>,
+        /// Amount of src tokens to fill, in base units
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Cancel the order by its maker
+    Cancel {
+        #[command(flatten)]
+        order: OrderArgs,
+        /// Maker keypair path
+        #[arg(long)]
+        maker_kp: String,
+    },
+    /// Cancel the expired order by a whitelisted resolver
+    CancelByResolver {
+        #[command(flatten)]
+        order: OrderArgs,
+        /// Maker public key
+        #[arg(long)]
+        maker: Pubkey,
+        /// Resolver keypair path
+        #[arg(long)]
+        resolver_kp: String,
+        /// Whitelisted resolver the keypair signs for as a delegate
+        #[arg(long)]
+        resolver: Option<Pubkey>,
+        /// Maximum cancellation premium the resolver takes
+        #[arg(long)]
+        reward_limit: u64,
+    },
+    /// Print the order addresses, escrow balance and a quote at the current cluster time
+    Inspect {
+        #[command(flatten)]
+        order: OrderArgs,
+        /// Maker public key
+        #[arg(long)]
+        maker: Pubkey,
+    },
+    /// Simulate `quote` of a fill at the current cluster time and print the returned amounts
+    Quote {
+        #[command(flatten)]
+        order: OrderArgs,
+        /// Maker public key, also paying for the simulated transaction
+        #[arg(long)]
+        maker: Pubkey,
+        /// Amount of src tokens to fill, in base units
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Print the order hash without connecting to the cluster
+    Hash {
+        #[command(flatten)]
+        order: OrderArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum WhitelistCommand {
+    /// Initialize the whitelist with the authority
+    Init {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+    },
+    /// Migrate the whitelist state created before its layout was versioned
+    MigrateState {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+    },
+    /// Grant a role to an account
+    GrantRole {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+        #[arg(long, value_enum)]
+        role: RoleArg,
+        /// Account public key
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Revoke a role from an account
+    RevokeRole {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+        #[arg(long, value_enum)]
+        role: RoleArg,
+        /// Account public key
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Register a resolver
+    Register {
+        /// Operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+        /// Unix timestamp from which the access is valid
+        #[arg(long, default_value_t = 0)]
+        valid_from: i64,
+        /// Unix timestamp at which the access lapses
+        #[arg(long)]
+        valid_until: i64,
+        /// Instructions the resolver is allowed to call
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "fill,cancel")]
+        permissions: Vec<PermissionArg>,
+        /// Label of the resolver in the registry
+        #[arg(long, default_value = "")]
+        label: String,
+        /// Keypair path of the account paying the rent and the transaction fee,
+        /// the operator by default
+        #[arg(long)]
+        payer_kp: Option<String>,
+    },
+    /// Migrate the access of a resolver registered before its layout was versioned
+    MigrateAccess {
+        /// Operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+        /// Keypair path of the account paying the extra rent and the transaction fee,
+        /// the operator by default
+        #[arg(long)]
+        payer_kp: Option<String>,
+    },
+    /// Replace the permissions of a resolver
+    UpdatePermissions {
+        /// Operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+        /// Instructions the resolver is allowed to call, none to revoke all of them
+        #[arg(long, value_enum, value_delimiter = ',', num_args = 0.., required = true)]
+        permissions: Vec<PermissionArg>,
+    },
+    /// Renew the access of a resolver
+    Renew {
+        /// Operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+        /// Unix timestamp at which the access lapses
+        #[arg(long)]
+        valid_until: i64,
+    },
+    /// Suspend a resolver without closing its access account
+    Suspend {
+        /// Guardian or operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Reinstate a suspended resolver
+    Reinstate {
+        /// Operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Authorize a delegate key to fill and cancel for the resolver
+    AddDelegate {
+        /// Registered resolver keypair path
+        #[arg(long)]
+        resolver_kp: String,
+        /// Delegate keypair path, the delegate signs to accept the delegation
+        #[arg(long)]
+        delegate_kp: String,
+    },
+    /// Revoke a delegate key of the resolver
+    RemoveDelegate {
+        /// Resolver keypair path
+        #[arg(long)]
+        resolver_kp: String,
+        /// Delegate public key
+        #[arg(long)]
+        delegate: Pubkey,
+    },
+    /// Set the resolver root to the root of a resolver tree file
+    SetResolverRoot {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver tree file, omitted to disable filling with a proof
+        #[arg(long)]
+        tree: Option<String>,
+    },
+    /// Set the mint and the minimum amount resolvers bond to fill orders
+    SetBondConfig {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Bond mint, fixed once set
+        #[arg(long)]
+        bond_mint: Pubkey,
+        /// Minimum bonded amount, zero to fill without a bond
+        #[arg(long)]
+        min_bond: u64,
+        /// Seconds between unbonding and withdrawing the bond, at most a year
+        #[arg(long)]
+        unbonding_period: i64,
+    },
+    /// Deposit the bond mint into the bond of the resolver
+    Bond {
+        /// Resolver keypair path
+        #[arg(long)]
+        resolver_kp: String,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Start unbonding part of the bond of the resolver
+    Unbond {
+        /// Resolver keypair path
+        #[arg(long)]
+        resolver_kp: String,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Withdraw the unbonded amount once the unbonding period has passed
+    WithdrawBond {
+        /// Resolver keypair path
+        #[arg(long)]
+        resolver_kp: String,
+    },
+    /// Slash the bond of a resolver
+    Slash {
+        /// Authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public key
+        #[arg(long)]
+        user: Pubkey,
+        /// Token account receiving the slashed amount
+        #[arg(long)]
+        dst_ata: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Deregister a resolver
+    Deregister {
+        /// Operator keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// Resolver public keys, deregistered in one instruction
+        #[arg(long, value_delimiter = ',', required = true)]
+        user: Vec<Pubkey>,
+    },
+    /// Propose a new authority, which takes over once it accepts the transfer
+    ProposeAuthority {
+        /// Current authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+        /// New authority public key
+        #[arg(long)]
+        new_authority: Pubkey,
+    },
+    /// Accept the pending authority transfer
+    AcceptAuthority {
+        /// Proposed authority keypair path
+        #[arg(long)]
+        new_authority_kp: String,
+    },
+    /// Cancel the pending authority transfer
+    CancelAuthorityTransfer {
+        /// Current authority keypair path
+        #[arg(long)]
+        authority_kp: String,
+    },
+    /// Print the whitelist authority, role members and the resolver access accounts
+    List,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RoleArg {
+    Operator,
+    Guardian,
+}
+
+impl From<RoleArg> for Role {
+    fn from(role: RoleArg) -> Self {
+        match role {
+            RoleArg::Operator => Role::Operator,
+            RoleArg::Guardian => Role::Guardian,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PermissionArg {
+    Fill,
+    Cancel,
+}
+
+fn permission_bits(permissions: &[PermissionArg]) -> u8 {
+    permissions
+        .iter()
+        .map(|permission| match permission {
+            PermissionArg::Fill => permissions::FILL,
+            PermissionArg::Cancel => permissions::CANCEL,
+        })
+        .fold(0, |bits, permission| bits | permission)
+}
+
+struct CliContext {
+    rpc: Option<RpcClient>,
+    dry_run: bool,
+}
+
+impl CliContext {
+    fn rpc(&self) -> anyhow::Result<&RpcClient> {
+        self.rpc
+            .as_ref()
+            .ok_or_else(|| anyhow!("cluster url is not set, pass --url or set CLUSTER_URL"))
+    }
+
+    fn whitelist_state(&self) -> anyhow::Result<WhitelistState> {
+        let account = self.rpc()?.get_account(&pda::whitelist_state())?;
+        Ok(WhitelistState::try_deserialize(&mut &account.data[..])?)
+    }
+
+    fn bond_mint(&self) -> anyhow::Result<Pubkey> {
+        self.whitelist_state()?
+            .bond_mint
+            .ok_or_else(|| anyhow!("bond mint is not set"))
+    }
+
+    fn token_program(&self, mint: &Pubkey) -> anyhow::Result<Pubkey> {
+        Ok(self
+            .rpc()?
+            .get_account(mint)
+            .with_context(|| format!("mint {mint} not found"))?
+            .owner)
+    }
+
+    fn order(&self, file: &OrderFile, maker: Pubkey) -> anyhow::Result<fusion_swap::Order> {
+        Ok(fusion_swap::Order {
+            config: file.config(),
+            maker,
+            maker_receiver: file.receiver,
+            src_mint: file.src_mint,
+            dst_mint: file.dst_mint,
+            protocol_dst_acc: file.fee.protocol_dst_acc,
+            integrator_dst_acc: file.fee.integrator_dst_acc,
+            src_token_program: self.token_program(&file.src_mint)?,
+            dst_token_program: self.token_program(&file.dst_mint)?,
+        })
+    }
+
+    fn send(&self, instruction: Instruction, signer: &Keypair) -> anyhow::Result<()> {
+        self.send_signed(instruction, &[signer])
+    }
+
+    /// Sends `instruction` signed by `signers`, the first of which pays the transaction fee
+    fn send_signed(&self, instruction: Instruction, signers: &[&Keypair]) -> anyhow::Result<()> {
+        let rpc = self.rpc()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signers[0].pubkey()),
+            signers,
+            rpc.get_latest_blockhash()?,
+        );
+
+        if self.dry_run {
+            let result = rpc.simulate_transaction(&transaction)?.value;
+            for log in result.logs.unwrap_or_default() {
+                println!("{log}");
+            }
+            match result.err {
+                Some(err) => println!("Simulation failed: {err}"),
+                None => println!(
+                    "Simulation succeeded, {} compute units consumed",
+                    result.units_consumed.unwrap_or_default()
+                ),
+            }
+        } else {
+            let signature = rpc.send_and_confirm_transaction(&transaction)?;
+            println!("Transaction signature {signature}");
+        }
+        Ok(())
+    }
+}
+
+fn keypair(path: &str) -> anyhow::Result<Keypair> {
+    read_keypair_file(path).map_err(|e| anyhow!("failed to read keypair {path}: {e}"))
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let ctx = CliContext {
+        rpc: cli
+            .url
+            .map(|url| RpcClient::new_with_commitment(url, CommitmentConfig::confirmed())),
+        dry_run: cli.dry_run,
+    };
+
+    match cli.command {
+        Command::Order(command) => run_order(&ctx, command),
+        Command::Whitelist(command) => run_whitelist(&ctx, command),
+    }
+}
+
+fn run_order(ctx: &CliContext, command: OrderCommand) -> anyhow::Result<()> {
+    match command {
+        OrderCommand::Create { order, maker_kp } => {
+            let maker = keypair(&maker_kp)?;
+            let order = ctx.order(&OrderFile::read(&order.order)?, maker.pubkey())?;
+            println!("Escrow: {}", order.escrow()?);
+            ctx.send(fusion_swap::create(order)?, &maker)
+        }
+        OrderCommand::Fill {
+            order,
+            maker,
+            taker_kp,
+            resolver,
+            resolver_tree,
+            amount,
+        } => {
+            let taker = keypair(&taker_kp)?;
+            let order = ctx.order(&OrderFile::read(&order.order)?, maker)?;
+            let resolver = resolver.unwrap_or(taker.pubkey());
+            let instruction = match resolver_tree {
+                Some(tree) => {
+                    let proof = ResolverTree::read(&tree)?.proof(&resolver)?;
+                    fusion_swap::delegated_fill_with_proof(
+                        order,
+                        taker.pubkey(),
+                        resolver,
+                        amount,
+                        proof,
+                    )?
+                }
+                None => fusion_swap::delegated_fill(order, taker.pubkey(), resolver, amount)?,
+            };
+            ctx.send(instruction, &taker)
+        }
+        OrderCommand::Cancel { order, maker_kp } => {
+            let maker = keypair(&maker_kp)?;
+            let order = ctx.order(&OrderFile::read(&order.order)?, maker.pubkey())?;
+            ctx.send(fusion_swap::cancel(&order)?, &maker)
+        }
+        OrderCommand::CancelByResolver {
+            order,
+            maker,
+            resolver_kp,
+            resolver,
+            reward_limit,
+        } => {
+            let signer = keypair(&resolver_kp)?;
+            let order = ctx.order(&OrderFile::read(&order.order)?, maker)?;
+            let instruction = match resolver {
+                Some(resolver) => fusion_swap::delegated_cancel_by_resolver(
+                    order,
+                    signer.pubkey(),
+                    resolver,
+                    reward_limit,
+                )?,
+                None => fusion_swap::cancel_by_resolver(order, signer.pubkey(), reward_limit)?,
+            };
+            ctx.send(instruction, &signer)
+        }
+        OrderCommand::Inspect { order, maker } => {
+            let rpc = ctx.rpc()?;
+            let order = ctx.order(&OrderFile::read(&order.order)?, maker)?;
+            let escrow_src_ata = order.escrow_src_ata()?;
+            println!("Order hash: {}", hex(&order.hash()?));
+            println!("Escrow: {}", order.escrow()?);
+            println!("Escrow src ata: {escrow_src_ata}");
+
+            let Some(account) = rpc
+                .get_account_with_commitment(&escrow_src_ata, rpc.commitment())?
+                .value
+            else {
+                println!("Escrow does not exist");
+                return Ok(());
+            };
+            // Amount follows the mint and the owner in both Token and Token-2022 accounts
+            let remaining_src_amount = u64::from_le_bytes(
+                account
+                    .data
+                    .get(64..72)
+                    .ok_or_else(|| anyhow!("escrow src ata is not a token account"))?
+                    .try_into()?,
+            );
+            println!("Remaining src amount: {remaining_src_amount}");
+
+            let clock: Clock = from_account(&rpc.get_account(&sysvar::clock::ID)?)
+                .ok_or_else(|| anyhow!("failed to decode the clock sysvar"))?;
+            let quote = quote(
+                &order.config,
+                clock.unix_timestamp as u64,
+                remaining_src_amount,
+            )?;
+            print_quote(quote.rate_bump, quote.dst_amount, &quote.fee_amounts);
+            Ok(())
+        }
+        OrderCommand::Quote {
+            order,
+            maker,
+            amount,
+        } => {
+            let rpc = ctx.rpc()?;
+            let order = ctx.order(&OrderFile::read(&order.order)?, maker)?;
+            let transaction = Transaction::new_unsigned(Message::new(
+                &[fusion_swap::quote(order, amount)?],
+                Some(&maker),
+            ));
+            let result = rpc
+                .simulate_transaction_with_config(
+                    &transaction,
+                    RpcSimulateTransactionConfig {
+                        replace_recent_blockhash: true,
+                        ..Default::default()
+                    },
+                )?
+                .value;
+            if let Some(err) = result.err {
+                for log in result.logs.unwrap_or_default() {
+                    println!("{log}");
+                }
+                return Err(anyhow!("quote simulation failed: {err}"));
+            }
+
+            // Return data is always base64 encoded
+            let (data, _) = result
+                .return_data
+                .ok_or_else(|| anyhow!("quote returned no data"))?
+                .data;
+            let quote = OrderQuote::try_from_slice(&STANDARD.decode(data)?)?;
+            println!("Order hash: {}", hex(&quote.order_hash));
+            print_quote(quote.rate_bump, quote.dst_amount, &quote.fee_amounts);
+            Ok(())
+        }
+        OrderCommand::Hash { order } => {
+            let file = OrderFile::read(&order.order)?;
+            let hash = fusion_swap_client::order_hash(
+                &file.config(),
+                file.fee.protocol_dst_acc,
+                file.fee.integrator_dst_acc,
+                file.src_mint,
+                file.dst_mint,
+                file.receiver,
+            )?;
+            println!("{}", hex(&hash));
+            Ok(())
+        }
+    }
+}
+
+fn run_whitelist(ctx: &CliContext, command: WhitelistCommand) -> anyhow::Result<()> {
+    match command {
+        WhitelistCommand::Init { authority_kp } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(whitelist::initialize(authority.pubkey()), &authority)
+        }
+        WhitelistCommand::MigrateState { authority_kp } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::migrate_whitelist_state(authority.pubkey()),
+                &authority,
+            )
+        }
+        WhitelistCommand::GrantRole {
+            authority_kp,
+            role,
+            user,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::grant_role(authority.pubkey(), role.into(), user),
+                &authority,
+            )
+        }
+        WhitelistCommand::RevokeRole {
+            authority_kp,
+            role,
+            user,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::revoke_role(authority.pubkey(), role.into(), user),
+                &authority,
+            )
+        }
+        WhitelistCommand::Register {
+            authority_kp,
+            user,
+            valid_from,
+            valid_until,
+            permissions,
+            label,
+            payer_kp,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            let payer = payer_kp.as_deref().map(keypair).transpose()?;
+            let current_page = ctx.whitelist_state()?.current_page;
+            let instruction = whitelist::register(
+                authority.pubkey(),
+                payer.as_ref().unwrap_or(&authority).pubkey(),
+                Registration {
+                    user,
+                    valid_from,
+                    valid_until,
+                    permissions: permission_bits(&permissions),
+                    label,
+                },
+                current_page,
+            );
+            match &payer {
+                Some(payer) => ctx.send_signed(instruction, &[payer, &authority]),
+                None => ctx.send(instruction, &authority),
+            }
+        }
+        WhitelistCommand::MigrateAccess {
+            authority_kp,
+            user,
+            payer_kp,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            let payer = payer_kp.as_deref().map(keypair).transpose()?;
+            let current_page = ctx.whitelist_state()?.current_page;
+            let instruction = whitelist::migrate_resolver_access(
+                authority.pubkey(),
+                payer.as_ref().unwrap_or(&authority).pubkey(),
+                user,
+                current_page,
+            );
+            match &payer {
+                Some(payer) => ctx.send_signed(instruction, &[payer, &authority]),
+                None => ctx.send(instruction, &authority),
+            }
+        }
+        WhitelistCommand::UpdatePermissions {
+            authority_kp,
+            user,
+            permissions,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::update_permissions(
+                    authority.pubkey(),
+                    user,
+                    permission_bits(&permissions),
+                ),
+                &authority,
+            )
+        }
+        WhitelistCommand::Renew {
+            authority_kp,
+            user,
+            valid_until,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::renew(authority.pubkey(), user, valid_until),
+                &authority,
+            )
+        }
+        WhitelistCommand::Suspend { authority_kp, user } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(whitelist::suspend(authority.pubkey(), user), &authority)
+        }
+        WhitelistCommand::Reinstate { authority_kp, user } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(whitelist::reinstate(authority.pubkey(), user), &authority)
+        }
+        WhitelistCommand::AddDelegate {
+            resolver_kp,
+            delegate_kp,
+        } => {
+            let resolver = keypair(&resolver_kp)?;
+            let delegate = keypair(&delegate_kp)?;
+            ctx.send_signed(
+                whitelist::add_delegate(resolver.pubkey(), delegate.pubkey()),
+                &[&resolver, &delegate],
+            )
+        }
+        WhitelistCommand::RemoveDelegate {
+            resolver_kp,
+            delegate,
+        } => {
+            let resolver = keypair(&resolver_kp)?;
+            ctx.send(
+                whitelist::remove_delegate(resolver.pubkey(), delegate),
+                &resolver,
+            )
+        }
+        WhitelistCommand::SetResolverRoot { authority_kp, tree } => {
+            let authority = keypair(&authority_kp)?;
+            let root = tree
+                .map(|tree| ResolverTree::read(&tree))
+                .transpose()?
+                .and_then(|tree| tree.root());
+            ctx.send(
+                whitelist::set_resolver_root(authority.pubkey(), root),
+                &authority,
+            )
+        }
+        WhitelistCommand::SetBondConfig {
+            authority_kp,
+            bond_mint,
+            min_bond,
+            unbonding_period,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::set_bond_config(
+                    authority.pubkey(),
+                    bond_mint,
+                    min_bond,
+                    unbonding_period,
+                ),
+                &authority,
+            )
+        }
+        WhitelistCommand::Bond {
+            resolver_kp,
+            amount,
+        } => {
+            let resolver = keypair(&resolver_kp)?;
+            let bond_mint = ctx.bond_mint()?;
+            ctx.send(
+                whitelist::bond(
+                    resolver.pubkey(),
+                    bond_mint,
+                    ctx.token_program(&bond_mint)?,
+                    amount,
+                ),
+                &resolver,
+            )
+        }
+        WhitelistCommand::Unbond {
+            resolver_kp,
+            amount,
+        } => {
+            let resolver = keypair(&resolver_kp)?;
+            ctx.send(whitelist::unbond(resolver.pubkey(), amount), &resolver)
+        }
+        WhitelistCommand::WithdrawBond { resolver_kp } => {
+            let resolver = keypair(&resolver_kp)?;
+            let bond_mint = ctx.bond_mint()?;
+            ctx.send(
+                whitelist::withdraw_bond(
+                    resolver.pubkey(),
+                    bond_mint,
+                    ctx.token_program(&bond_mint)?,
+                ),
+                &resolver,
+            )
+        }
+        WhitelistCommand::Slash {
+            authority_kp,
+            user,
+            dst_ata,
+            amount,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            let bond_mint = ctx.bond_mint()?;
+            ctx.send(
+                whitelist::slash(
+                    authority.pubkey(),
+                    user,
+                    bond_mint,
+                    ctx.token_program(&bond_mint)?,
+                    dst_ata,
+                    amount,
+                ),
+                &authority,
+            )
+        }
+        WhitelistCommand::Deregister { authority_kp, user } => {
+            let authority = keypair(&authority_kp)?;
+            let accesses = user
+                .iter()
+                .map(|user| {
+                    let account = ctx.rpc()?.get_account(&pda::resolver_access(user))?;
+                    Ok(ResolverAccess::try_deserialize(&mut &account.data[..])?)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let instruction = match accesses.as_slice() {
+                [access] => whitelist::deregister(
+                    authority.pubkey(),
+                    access.payer,
+                    access.resolver,
+                    access.registry_page,
+                ),
+                accesses => whitelist::deregister_many(authority.pubkey(), accesses),
+            };
+            ctx.send(instruction, &authority)
+        }
+        WhitelistCommand::ProposeAuthority {
+            authority_kp,
+            new_authority,
+        } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::propose_authority(authority.pubkey(), new_authority),
+                &authority,
+            )
+        }
+        WhitelistCommand::AcceptAuthority { new_authority_kp } => {
+            let new_authority = keypair(&new_authority_kp)?;
+            ctx.send(
+                whitelist::accept_authority(new_authority.pubkey()),
+                &new_authority,
+            )
+        }
+        WhitelistCommand::CancelAuthorityTransfer { authority_kp } => {
+            let authority = keypair(&authority_kp)?;
+            ctx.send(
+                whitelist::cancel_authority_transfer(authority.pubkey()),
+                &authority,
+            )
+        }
+        WhitelistCommand::List => {
+            let rpc = ctx.rpc()?;
+            let state = ctx.whitelist_state()?;
+            println!("Authority: {}", state.authority);
+            if let Some(pending_authority) = state.pending_authority {
+                println!("Pending authority: {pending_authority}");
+            }
+            for operator in &state.operators {
+                println!("Operator: {operator}");
+            }
+            for guardian in &state.guardians {
+                println!("Guardian: {guardian}");
+            }
+            if let Some(root) = state.resolver_root {
+                println!("Resolver root: {}", hex(&root));
+            }
+            if let Some(bond_mint) = state.bond_mint {
+                println!(
+                    "Bond: {} of {bond_mint}, unbonding period {}s",
+                    state.min_bond, state.unbonding_period
+                );
+            }
+
+            println!("Resolvers: {}", state.resolver_count);
+
+            let pages: Vec<_> = (0..=state.current_page).map(pda::resolver_page).collect();
+            for page in rpc.get_multiple_accounts(&pages)?.into_iter().flatten() {
+                let page = ResolverPage::try_deserialize(&mut &page.data[..])?;
+                let accesses: Vec<_> = page.resolvers.iter().map(pda::resolver_access).collect();
+                for access in rpc.get_multiple_accounts(&accesses)?.into_iter().flatten() {
+                    let access = ResolverAccess::try_deserialize(&mut &access.data[..])?;
+                    println!(
+                        "Resolver: {} {:?}, registered at {}, {:?}, permissions {:#04b}, valid from {} until {}",
+                        access.resolver,
+                        access.label,
+                        access.registered_at,
+                        access.status,
+                        access.permissions,
+                        access.valid_from,
+                        access.valid_until
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_quote(rate_bump: u64, dst_amount: u64, fee_amounts: &FeeAmounts) {
+    println!("Rate bump: {rate_bump}");
+    println!("Dst amount: {dst_amount}");
+    println!("Maker amount: {}", fee_amounts.maker_amount);
+    println!("Protocol fee: {}", fee_amounts.protocol_fee);
+    println!("Integrator fee: {}", fee_amounts.integrator_fee);
+    println!(
+        "Integrator fee shares: {:?}",
+        fee_amounts.integrator_fee_shares
+    );
+    println!("Resolver surplus: {}", fee_amounts.resolver_surplus);
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,457 @@
+//! Order config file as written by `scripts/fusion-swap/create.ts`: camelCase fields,
+//! `BN` amounts as hex strings and public keys in base58
+
+use anchor_lang::prelude::Pubkey;
+use fusion_swap_client::{
+    auction::{
+        AuctionData, ExponentialDecayCurve, LinearWithPlateauCurve, PiecewiseLinearCurve,
+        PointAndTimeDelta, StepCurve,
+    },
+    FeeConfig, IntegratorFeeShare, OrderConfig,
+};
+use serde::{de::Error, Deserialize, Deserializer};
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderFile {
+    pub id: u32,
+    #[serde(deserialize_with = "bn")]
+    pub src_amount: u64,
+    #[serde(deserialize_with = "bn")]
+    pub min_dst_amount: u64,
+    #[serde(deserialize_with = "bn")]
+    pub estimated_dst_amount: u64,
+    pub expiration_time: u32,
+    pub src_asset_is_native: bool,
+    pub dst_asset_is_native: bool,
+    pub fee: FeeFile,
+    pub dutch_auction_data: AuctionFile,
+    pub cancellation_auction_duration: u32,
+    #[serde(deserialize_with = "pubkey")]
+    pub src_mint: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub dst_mint: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub receiver: Pubkey,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeFile {
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    pub protocol_dst_acc: Option<Pubkey>,
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    pub integrator_dst_acc: Option<Pubkey>,
+    pub protocol_fee: u16,
+    pub integrator_fee: u16,
+    #[serde(default)]
+    pub integrator_fee_shares: Vec<IntegratorFeeShareFile>,
+    pub surplus_percentage: u8,
+    #[serde(default)]
+    pub integrator_surplus_percentage: u8,
+    #[serde(default)]
+    pub resolver_surplus_percentage: u8,
+    #[serde(deserialize_with = "bn")]
+    pub max_cancellation_premium: u64,
+    #[serde(default, deserialize_with = "bn")]
+    pub max_src_cancellation_premium: u64,
+}
+
+#[derive(Deserialize)]
+pub struct IntegratorFeeShareFile {
+    #[serde(deserialize_with = "pubkey")]
+    pub recipient: Pubkey,
+    pub fee: u16,
+}
+
+/// Anchor encodes tuple enum variants as `{ "variant": { "0": value } }`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuctionFile {
+    PiecewiseLinear(Variant<PointsCurveFile>),
+    ExponentialDecay(Variant<ExponentialDecayCurveFile>),
+    Step(Variant<PointsCurveFile>),
+    LinearWithPlateau(Variant<LinearWithPlateauCurveFile>),
+}
+
+#[derive(Deserialize)]
+pub struct Variant<T> {
+    #[serde(rename = "0")]
+    pub value: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointsCurveFile {
+    pub start_time: u32,
+    pub duration: u32,
+    pub initial_rate_bump: u16,
+    pub points_and_time_deltas: Vec<PointFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointFile {
+    pub rate_bump: u16,
+    pub time_delta: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExponentialDecayCurveFile {
+    pub start_time: u32,
+    pub duration: u32,
+    pub initial_rate_bump: u16,
+    pub half_life: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearWithPlateauCurveFile {
+    pub start_time: u32,
+    pub duration: u32,
+    pub initial_rate_bump: u16,
+    pub plateau_duration: u32,
+}
+
+impl OrderFile {
+    pub fn read(path: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn config(&self) -> OrderConfig {
+        OrderConfig {
+            id: self.id,
+            src_amount: self.src_amount,
+            min_dst_amount: self.min_dst_amount,
+            estimated_dst_amount: self.estimated_dst_amount,
+            expiration_time: self.expiration_time,
+            src_asset_is_native: self.src_asset_is_native,
+            dst_asset_is_native: self.dst_asset_is_native,
+            fee: FeeConfig {
+                protocol_fee: self.fee.protocol_fee,
+                integrator_fee: self.fee.integrator_fee,
+                integrator_fee_shares: self
+                    .fee
+                    .integrator_fee_shares
+                    .iter()
+                    .map(|share| IntegratorFeeShare {
+                        recipient: share.recipient,
+                        fee: share.fee,
+                    })
+                    .collect(),
+                surplus_percentage: self.fee.surplus_percentage,
+                integrator_surplus_percentage: self.fee.integrator_surplus_percentage,
+                resolver_surplus_percentage: self.fee.resolver_surplus_percentage,
+                max_cancellation_premium: self.fee.max_cancellation_premium,
+                max_src_cancellation_premium: self.fee.max_src_cancellation_premium,
+            },
+            dutch_auction_data: self.dutch_auction_data.auction_data(),
+            cancellation_auction_duration: self.cancellation_auction_duration,
+        }
+    }
+}
+
+impl AuctionFile {
+    fn auction_data(&self) -> AuctionData {
+        let points = |curve: &PointsCurveFile| {
+            curve
+                .points_and_time_deltas
+                .iter()
+                .map(|point| PointAndTimeDelta {
+                    rate_bump: point.rate_bump,
+                    time_delta: point.time_delta,
+                })
+                .collect()
+        };
+
+        match self {
+            AuctionFile::PiecewiseLinear(Variant { value }) => {
+                AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                    start_time: value.start_time,
+                    duration: value.duration,
+                    initial_rate_bump: value.initial_rate_bump,
+                    points_and_time_deltas: points(value),
+                })
+            }
+            AuctionFile::ExponentialDecay(Variant { value }) => {
+                AuctionData::ExponentialDecay(ExponentialDecayCurve {
+                    start_time: value.start_time,
+                    duration: value.duration,
+                    initial_rate_bump: value.initial_rate_bump,
+                    half_life: value.half_life,
+                })
+            }
+            AuctionFile::Step(Variant { value }) => AuctionData::Step(StepCurve {
+                start_time: value.start_time,
+                duration: value.duration,
+                initial_rate_bump: value.initial_rate_bump,
+                points_and_time_deltas: points(value),
+            }),
+            AuctionFile::LinearWithPlateau(Variant { value }) => {
+                AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                    start_time: value.start_time,
+                    duration: value.duration,
+                    initial_rate_bump: value.initial_rate_bump,
+                    plateau_duration: value.plateau_duration,
+                })
+            }
+        }
+    }
+}
+
+/// `BN` is serialized by `JSON.stringify` as a hex string, plain numbers are accepted as well
+fn bn<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Bn {
+        Number(u64),
+        Hex(String),
+    }
+
+    match Bn::deserialize(deserializer)? {
+        Bn::Number(value) => Ok(value),
+        Bn::Hex(value) => u64::from_str_radix(value.trim_start_matches("0x"), 16)
+            .map_err(|e| D::Error::custom(format!("invalid hex amount {value}: {e}"))),
+    }
+}
+
+pub fn pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Pubkey::from_str(&value).map_err(|e| D::Error::custom(format!("invalid pubkey {value}: {e}")))
+}
+
+fn optional_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| {
+            Pubkey::from_str(&value)
+                .map_err(|e| D::Error::custom(format!("invalid pubkey {value}: {e}")))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+    use serde_json::{json, Value};
+
+    /// `BN` as written by `JSON.stringify`
+    fn bn(value: u64) -> Value {
+        json!(format!("{value:x}"))
+    }
+
+    fn points(points: &[PointAndTimeDelta]) -> Value {
+        points
+            .iter()
+            .map(|point| json!({ "rateBump": point.rate_bump, "timeDelta": point.time_delta }))
+            .collect()
+    }
+
+    /// Order file of `config` as written by `scripts/fusion-swap/create.ts`
+    fn order_json(config: &OrderConfig, mint: &Pubkey, fee_dst_acc: Option<&Pubkey>) -> Value {
+        let curve = match &config.dutch_auction_data {
+            AuctionData::PiecewiseLinear(curve) => json!({ "piecewiseLinear": { "0": {
+                "startTime": curve.start_time,
+                "duration": curve.duration,
+                "initialRateBump": curve.initial_rate_bump,
+                "pointsAndTimeDeltas": points(&curve.points_and_time_deltas),
+            }}}),
+            AuctionData::ExponentialDecay(curve) => json!({ "exponentialDecay": { "0": {
+                "startTime": curve.start_time,
+                "duration": curve.duration,
+                "initialRateBump": curve.initial_rate_bump,
+                "halfLife": curve.half_life,
+            }}}),
+            AuctionData::Step(curve) => json!({ "step": { "0": {
+                "startTime": curve.start_time,
+                "duration": curve.duration,
+                "initialRateBump": curve.initial_rate_bump,
+                "pointsAndTimeDeltas": points(&curve.points_and_time_deltas),
+            }}}),
+            AuctionData::LinearWithPlateau(curve) => json!({ "linearWithPlateau": { "0": {
+                "startTime": curve.start_time,
+                "duration": curve.duration,
+                "initialRateBump": curve.initial_rate_bump,
+                "plateauDuration": curve.plateau_duration,
+            }}}),
+        };
+        let fee = &config.fee;
+        json!({
+            "id": config.id,
+            "srcAmount": bn(config.src_amount),
+            "minDstAmount": bn(config.min_dst_amount),
+            "estimatedDstAmount": bn(config.estimated_dst_amount),
+            "expirationTime": config.expiration_time,
+            "srcAssetIsNative": config.src_asset_is_native,
+            "dstAssetIsNative": config.dst_asset_is_native,
+            "fee": {
+                "protocolDstAcc": fee_dst_acc.map(Pubkey::to_string),
+                "integratorDstAcc": fee_dst_acc.map(Pubkey::to_string),
+                "protocolFee": fee.protocol_fee,
+                "integratorFee": fee.integrator_fee,
+                "integratorFeeShares": fee.integrator_fee_shares.iter().map(|share| json!({
+                    "recipient": share.recipient.to_string(),
+                    "fee": share.fee,
+                })).collect::<Value>(),
+                "surplusPercentage": fee.surplus_percentage,
+                "integratorSurplusPercentage": fee.integrator_surplus_percentage,
+                "resolverSurplusPercentage": fee.resolver_surplus_percentage,
+                "maxCancellationPremium": bn(fee.max_cancellation_premium),
+                "maxSrcCancellationPremium": bn(fee.max_src_cancellation_premium),
+            },
+            "dutchAuctionData": curve,
+            "cancellationAuctionDuration": config.cancellation_auction_duration,
+            "srcMint": mint.to_string(),
+            "dstMint": mint.to_string(),
+            "receiver": mint.to_string(),
+        })
+    }
+
+    fn config(dutch_auction_data: AuctionData) -> OrderConfig {
+        OrderConfig {
+            id: 7,
+            src_amount: u64::MAX,
+            min_dst_amount: 1_000_000,
+            estimated_dst_amount: 1_100_000,
+            expiration_time: 1_700_000_000,
+            src_asset_is_native: true,
+            dst_asset_is_native: false,
+            fee: FeeConfig {
+                protocol_fee: 100,
+                integrator_fee: 200,
+                integrator_fee_shares: vec![IntegratorFeeShare {
+                    recipient: Pubkey::new_unique(),
+                    fee: 50,
+                }],
+                surplus_percentage: 50,
+                integrator_surplus_percentage: 20,
+                resolver_surplus_percentage: 10,
+                max_cancellation_premium: 5_000,
+                max_src_cancellation_premium: 255,
+            },
+            dutch_auction_data,
+            cancellation_auction_duration: 60,
+        }
+    }
+
+    #[test]
+    fn round_trips_order_config() {
+        let points = vec![
+            PointAndTimeDelta {
+                rate_bump: 500,
+                time_delta: 10,
+            },
+            PointAndTimeDelta {
+                rate_bump: 100,
+                time_delta: 20,
+            },
+        ];
+        let curves = [
+            AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                start_time: 1,
+                duration: 100,
+                initial_rate_bump: 1_000,
+                points_and_time_deltas: points.clone(),
+            }),
+            AuctionData::ExponentialDecay(ExponentialDecayCurve {
+                start_time: 2,
+                duration: 200,
+                initial_rate_bump: 2_000,
+                half_life: 30,
+            }),
+            AuctionData::Step(StepCurve {
+                start_time: 3,
+                duration: 300,
+                initial_rate_bump: 3_000,
+                points_and_time_deltas: points,
+            }),
+            AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                start_time: 4,
+                duration: 400,
+                initial_rate_bump: 4_000,
+                plateau_duration: 40,
+            }),
+        ];
+        let mint = Pubkey::new_unique();
+        let fee_dst_acc = Pubkey::new_unique();
+
+        for curve in curves {
+            let config = config(curve);
+            let file: OrderFile =
+                serde_json::from_value(order_json(&config, &mint, Some(&fee_dst_acc))).unwrap();
+            assert_eq!(
+                file.config().try_to_vec().unwrap(),
+                config.try_to_vec().unwrap()
+            );
+            assert_eq!(file.src_mint, mint);
+            assert_eq!(file.fee.protocol_dst_acc, Some(fee_dst_acc));
+            assert_eq!(file.fee.integrator_dst_acc, Some(fee_dst_acc));
+        }
+    }
+
+    #[test]
+    fn parses_optional_fields_and_plain_amounts() {
+        let mint = Pubkey::new_unique();
+        let mut order = order_json(
+            &config(AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                start_time: 0,
+                duration: 0,
+                initial_rate_bump: 0,
+                plateau_duration: 0,
+            })),
+            &mint,
+            None,
+        );
+        let fee = order["fee"].as_object_mut().unwrap();
+        for field in [
+            "integratorFeeShares",
+            "integratorSurplusPercentage",
+            "resolverSurplusPercentage",
+            "maxSrcCancellationPremium",
+        ] {
+            fee.remove(field);
+        }
+        order["minDstAmount"] = json!(1_000_000);
+        order["srcAmount"] = json!("0xff");
+
+        let file: OrderFile = serde_json::from_value(order).unwrap();
+        let config = file.config();
+        assert_eq!(config.src_amount, 255);
+        assert_eq!(config.min_dst_amount, 1_000_000);
+        assert!(config.fee.integrator_fee_shares.is_empty());
+        assert_eq!(config.fee.integrator_surplus_percentage, 0);
+        assert_eq!(config.fee.max_src_cancellation_premium, 0);
+        assert_eq!(file.fee.protocol_dst_acc, None);
+    }
+
+    #[test]
+    fn rejects_invalid_amounts_and_pubkeys() {
+        let mint = Pubkey::new_unique();
+        let order = order_json(
+            &config(AuctionData::ExponentialDecay(ExponentialDecayCurve {
+                start_time: 0,
+                duration: 0,
+                initial_rate_bump: 0,
+                half_life: 1,
+            })),
+            &mint,
+            None,
+        );
+
+        let mut invalid_amount = order.clone();
+        invalid_amount["srcAmount"] = json!("xyz");
+        let error = serde_json::from_value::<OrderFile>(invalid_amount)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("invalid hex amount xyz"));
+
+        let mut invalid_pubkey = order;
+        invalid_pubkey["receiver"] = json!("receiver");
+        let error = serde_json::from_value::<OrderFile>(invalid_pubkey)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("invalid pubkey receiver"));
+    }
+}
@@ -0,0 +1,124 @@
+//! Resolver tree file behind the whitelist resolver root: a JSON array of
+//! `{ "resolver": base58, "validUntil": unix timestamp }` leaves, `validUntil` is omitted for
+//! access without expiry
+
+use crate::order_file::pubkey;
+use anchor_lang::prelude::Pubkey;
+use anyhow::anyhow;
+use fusion_swap_client::merkle::{self, ResolverProof};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolverLeafFile {
+    #[serde(deserialize_with = "pubkey")]
+    pub resolver: Pubkey,
+    #[serde(default = "no_expiry")]
+    pub valid_until: i64,
+}
+
+fn no_expiry() -> i64 {
+    i64::MAX
+}
+
+pub struct ResolverTree {
+    leaves: Vec<ResolverLeafFile>,
+}
+
+impl ResolverTree {
+    pub fn read(path: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            leaves: serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        })
+    }
+
+    fn hashes(&self) -> Vec<[u8; 32]> {
+        self.leaves
+            .iter()
+            .map(|leaf| merkle::leaf(&leaf.resolver, leaf.valid_until))
+            .collect()
+    }
+
+    /// Root of the tree, `None` if there are no resolvers
+    pub fn root(&self) -> Option<[u8; 32]> {
+        merkle::root(&self.hashes())
+    }
+
+    /// Proof of the first leaf of `resolver`
+    pub fn proof(&self, resolver: &Pubkey) -> anyhow::Result<ResolverProof> {
+        let index = self
+            .leaves
+            .iter()
+            .position(|leaf| leaf.resolver == *resolver)
+            .ok_or_else(|| anyhow!("{resolver} is not in the resolver tree"))?;
+        Ok(ResolverProof {
+            valid_until: self.leaves[index].valid_until,
+            proof: merkle::proof(&self.hashes(), index),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tree(leaves: serde_json::Value) -> ResolverTree {
+        ResolverTree {
+            leaves: serde_json::from_value(leaves).unwrap(),
+        }
+    }
+
+    #[test]
+    fn proofs_verify_against_root() {
+        let resolvers: Vec<_> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let tree = tree(
+            resolvers
+                .iter()
+                .enumerate()
+                .map(|(i, resolver)| match i {
+                    // Access without expiry
+                    0 => json!({ "resolver": resolver.to_string() }),
+                    _ => json!({ "resolver": resolver.to_string(), "validUntil": 1_000 * i }),
+                })
+                .collect(),
+        );
+        let root = tree.root().unwrap();
+
+        for (i, resolver) in resolvers.iter().enumerate() {
+            let proof = tree.proof(resolver).unwrap();
+            let valid_until = if i == 0 { i64::MAX } else { 1_000 * i as i64 };
+            assert_eq!(proof.valid_until, valid_until);
+            assert!(proof.verify(&root, resolver), "resolver {i}");
+            assert!(!proof.verify(&root, &Pubkey::new_unique()));
+        }
+    }
+
+    #[test]
+    fn root_of_single_resolver_is_its_leaf() {
+        let resolver = Pubkey::new_unique();
+        let tree = tree(json!([{ "resolver": resolver.to_string(), "validUntil": 42 }]));
+        assert_eq!(tree.root(), Some(merkle::leaf(&resolver, 42)));
+        let proof = tree.proof(&resolver).unwrap();
+        assert!(proof.proof.is_empty());
+        assert!(proof.verify(&tree.root().unwrap(), &resolver));
+    }
+
+    #[test]
+    fn empty_tree_has_no_root() {
+        let tree = tree(json!([]));
+        assert_eq!(tree.root(), None);
+        assert!(tree.proof(&Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn rejects_proof_of_unknown_resolver() {
+        let tree = tree(json!([{ "resolver": Pubkey::new_unique().to_string() }]));
+        let resolver = Pubkey::new_unique();
+        let error = tree.proof(&resolver).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!("{resolver} is not in the resolver tree")
+        );
+    }
+}
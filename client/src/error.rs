@@ -0,0 +1,128 @@
+pub use ::fusion_swap::error::FusionError;
+pub use ::whitelist::error::WhitelistError;
+use anchor_lang::prelude::Pubkey;
+
+/// Custom error of one of the programs, decoded from its error code
+#[derive(Debug, Clone, Copy)]
+pub enum ProgramError {
+    Fusion(FusionError),
+    Whitelist(WhitelistError),
+}
+
+const FUSION_ERRORS: &[FusionError] = &[
+    FusionError::InconsistentNativeSrcTrait,
+    FusionError::InconsistentNativeDstTrait,
+    FusionError::InvalidAmount,
+    FusionError::MissingMakerDstAta,
+    FusionError::NotEnoughTokensInEscrow,
+    FusionError::OrderExpired,
+    FusionError::InvalidEstimatedTakingAmount,
+    FusionError::InvalidProtocolSurplusFee,
+    FusionError::InconsistentProtocolFeeConfig,
+    FusionError::InconsistentIntegratorFeeConfig,
+    FusionError::OrderNotExpired,
+    FusionError::InvalidCancellationFee,
+    FusionError::CancelOrderByResolverIsForbidden,
+    FusionError::MissingTakerDstAta,
+    FusionError::MissingMakerSrcAta,
+    FusionError::TooManyIntegratorFeeShares,
+    FusionError::InvalidIntegratorFeeShare,
+    FusionError::InconsistentIntegratorFeeShares,
+    FusionError::InvalidTotalFee,
+    FusionError::NoFeesToClaim,
+    FusionError::InvalidSurplusSplit,
+    FusionError::MissingResolverSrcAta,
+    FusionError::InconsistentBatchAccounts,
+    FusionError::TooManyAuctionPoints,
+    FusionError::IncreasingAuctionRateBump,
+    FusionError::AuctionPointsExceedDuration,
+    FusionError::AuctionStartsAfterExpiration,
+    FusionError::ResolverAccessNotValid,
+    FusionError::ResolverSuspended,
+    FusionError::MissingResolverPermission,
+    FusionError::MissingResolverAccess,
+    FusionError::MissingWhitelistState,
+    FusionError::ResolverRootNotSet,
+    FusionError::InvalidResolverProof,
+    FusionError::InsufficientResolverBond,
+    FusionError::InvalidResolverDelegate,
+];
+
+const WHITELIST_ERRORS: &[WhitelistError] = &[
+    WhitelistError::Unauthorized,
+    WhitelistError::NoPendingAuthority,
+    WhitelistError::RoleAlreadyGranted,
+    WhitelistError::RoleNotGranted,
+    WhitelistError::TooManyRoleMembers,
+    WhitelistError::InvalidAccessPeriod,
+    WhitelistError::ResolverAlreadySuspended,
+    WhitelistError::ResolverNotSuspended,
+    WhitelistError::InvalidPermissions,
+    WhitelistError::LabelTooLong,
+    WhitelistError::InvalidPayer,
+    WhitelistError::InconsistentBatchAccounts,
+    WhitelistError::InvalidUnbondingPeriod,
+    WhitelistError::BondMintChanged,
+    WhitelistError::InvalidBondMint,
+    WhitelistError::InvalidBondAmount,
+    WhitelistError::InsufficientBond,
+    WhitelistError::UnbondingNotElapsed,
+    WhitelistError::AlreadyMigrated,
+];
+
+/// Decodes the custom error `code` returned by `program_id`.
+/// Returns `None` for unknown programs and for codes that are not program errors,
+/// e.g. Anchor framework errors
+pub fn decode_error(program_id: &Pubkey, code: u32) -> Option<ProgramError> {
+    if *program_id == ::fusion_swap::ID {
+        FUSION_ERRORS
+            .iter()
+            .find(|error| u32::from(**error) == code)
+            .map(|error| ProgramError::Fusion(*error))
+    } else if *program_id == ::whitelist::ID {
+        WHITELIST_ERRORS
+            .iter()
+            .find(|error| u32::from(**error) == code)
+            .map(|error| ProgramError::Whitelist(*error))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every error declared in the checked-in IDL must decode to the variant of the same name
+    fn assert_decodes_idl_errors(program_id: &Pubkey, idl: &str) {
+        let idl: serde_json::Value = serde_json::from_str(idl).unwrap();
+        let errors = idl["errors"].as_array().unwrap();
+        for error in errors {
+            let code = error["code"].as_u64().unwrap() as u32;
+            let name = match decode_error(program_id, code).unwrap() {
+                ProgramError::Fusion(error) => error.name(),
+                ProgramError::Whitelist(error) => error.name(),
+            };
+            assert_eq!(name, error["name"].as_str().unwrap());
+        }
+    }
+
+    #[test]
+    fn decodes_all_fusion_swap_errors() {
+        assert_decodes_idl_errors(
+            &::fusion_swap::ID,
+            include_str!("../../idl/fusion_swap.json"),
+        );
+    }
+
+    #[test]
+    fn decodes_all_whitelist_errors() {
+        assert_decodes_idl_errors(&::whitelist::ID, include_str!("../../idl/whitelist.json"));
+    }
+
+    #[test]
+    fn does_not_decode_foreign_codes() {
+        assert!(decode_error(&::fusion_swap::ID, 3012).is_none());
+        assert!(decode_error(&Pubkey::default(), 6000).is_none());
+    }
+}
@@ -0,0 +1,410 @@
+//! Instruction builders for the Fusion Swap program. Accounts derivable from the order
+//! (escrow, ATAs, resolver access) are filled in by the builders.
+
+use crate::pda;
+use ::fusion_swap::{accounts, instruction, order_hash, OrderConfig, ID};
+use ::whitelist::merkle::ResolverProof;
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+    system_program, InstructionData, Result, ToAccountMetas,
+};
+use anchor_spl::associated_token;
+
+/// Order together with the accounts it is bound to by the order hash
+#[derive(Clone)]
+pub struct Order {
+    pub config: OrderConfig,
+    pub maker: Pubkey,
+    pub maker_receiver: Pubkey,
+    pub src_mint: Pubkey,
+    pub dst_mint: Pubkey,
+    pub protocol_dst_acc: Option<Pubkey>,
+    pub integrator_dst_acc: Option<Pubkey>,
+    pub src_token_program: Pubkey,
+    pub dst_token_program: Pubkey,
+}
+
+impl Order {
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        order_hash(
+            &self.config,
+            self.protocol_dst_acc,
+            self.integrator_dst_acc,
+            self.src_mint,
+            self.dst_mint,
+            self.maker_receiver,
+        )
+    }
+
+    pub fn escrow(&self) -> Result<Pubkey> {
+        Ok(pda::escrow(&self.maker, &self.hash()?))
+    }
+
+    pub fn escrow_src_ata(&self) -> Result<Pubkey> {
+        Ok(pda::ata(
+            &self.escrow()?,
+            &self.src_mint,
+            &self.src_token_program,
+        ))
+    }
+
+    /// Maker's ATA of the src mint, absent for orders of native SOL
+    pub fn maker_src_ata(&self) -> Option<Pubkey> {
+        (!self.config.src_asset_is_native)
+            .then(|| pda::ata(&self.maker, &self.src_mint, &self.src_token_program))
+    }
+}
+
+/// Builds `create`, escrowing the maker's src tokens
+pub fn create(order: Order) -> Result<Instruction> {
+    let escrow = order.escrow()?;
+    let accounts = accounts::Create {
+        system_program: system_program::ID,
+        escrow,
+        src_mint: order.src_mint,
+        src_token_program: order.src_token_program,
+        escrow_src_ata: pda::ata(&escrow, &order.src_mint, &order.src_token_program),
+        maker: order.maker,
+        maker_src_ata: order.maker_src_ata(),
+        dst_mint: order.dst_mint,
+        maker_receiver: order.maker_receiver,
+        associated_token_program: associated_token::ID,
+        protocol_dst_acc: order.protocol_dst_acc,
+        integrator_dst_acc: order.integrator_dst_acc,
+    };
+
+    Ok(Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::Create {
+            order: order.config,
+        }
+        .data(),
+    })
+}
+
+/// Builds `fill` of `amount` src tokens by `taker`, using the taker's ATAs.
+/// Integrator fee share recipients are appended as remaining accounts
+pub fn fill(order: Order, taker: Pubkey, amount: u64) -> Result<Instruction> {
+    fill_for(order, taker, taker, amount)
+}
+
+/// Builds `fill` signed by `delegate` on behalf of `resolver`, using the delegate's ATAs
+pub fn delegated_fill(
+    order: Order,
+    delegate: Pubkey,
+    resolver: Pubkey,
+    amount: u64,
+) -> Result<Instruction> {
+    fill_for(order, delegate, resolver, amount)
+}
+
+/// Delegate PDA of `signer` if it signs for another `resolver`
+fn resolver_delegate(signer: &Pubkey, resolver: &Pubkey) -> Option<Pubkey> {
+    (signer != resolver).then(|| pda::resolver_delegate(signer))
+}
+
+fn fill_for(order: Order, taker: Pubkey, resolver: Pubkey, amount: u64) -> Result<Instruction> {
+    Ok(Instruction {
+        program_id: ID,
+        accounts: fill_accounts(&order, taker, resolver, false)?,
+        data: instruction::Fill {
+            order: order.config,
+            amount,
+        }
+        .data(),
+    })
+}
+
+/// Builds `fill_with_proof` of `amount` src tokens by `taker`, proving that the taker is in the
+/// resolver tree of the whitelist instead of passing its resolver access
+pub fn fill_with_proof(
+    order: Order,
+    taker: Pubkey,
+    amount: u64,
+    proof: ResolverProof,
+) -> Result<Instruction> {
+    fill_with_proof_for(order, taker, taker, amount, proof)
+}
+
+/// Builds `fill_with_proof` signed by `delegate` on behalf of `resolver` in the resolver tree,
+/// passing the resolver access the delegate was added under
+pub fn delegated_fill_with_proof(
+    order: Order,
+    delegate: Pubkey,
+    resolver: Pubkey,
+    amount: u64,
+    proof: ResolverProof,
+) -> Result<Instruction> {
+    fill_with_proof_for(order, delegate, resolver, amount, proof)
+}
+
+fn fill_with_proof_for(
+    order: Order,
+    taker: Pubkey,
+    resolver: Pubkey,
+    amount: u64,
+    proof: ResolverProof,
+) -> Result<Instruction> {
+    Ok(Instruction {
+        program_id: ID,
+        accounts: fill_accounts(&order, taker, resolver, true)?,
+        data: instruction::FillWithProof {
+            order: order.config,
+            amount,
+            proof,
+        }
+        .data(),
+    })
+}
+
+/// Accounts of `fill` and `fill_with_proof`, the resolver access is left out when the resolver
+/// is proven by a Merkle proof and signs itself
+fn fill_accounts(
+    order: &Order,
+    taker: Pubkey,
+    resolver: Pubkey,
+    with_proof: bool,
+) -> Result<Vec<AccountMeta>> {
+    let escrow = order.escrow()?;
+    let dst_is_native = order.config.dst_asset_is_native;
+    let mut accounts = accounts::Fill {
+        taker,
+        resolver_delegate: resolver_delegate(&taker, &resolver),
+        resolver_access: (!with_proof || taker != resolver)
+            .then(|| pda::resolver_access(&resolver)),
+        whitelist_state: Some(pda::whitelist_state()),
+        resolver_bond: pda::resolver_bond(&resolver),
+        maker: order.maker,
+        maker_receiver: order.maker_receiver,
+        src_mint: order.src_mint,
+        dst_mint: order.dst_mint,
+        escrow,
+        escrow_src_ata: pda::ata(&escrow, &order.src_mint, &order.src_token_program),
+        taker_src_ata: pda::ata(&taker, &order.src_mint, &order.src_token_program),
+        src_token_program: order.src_token_program,
+        dst_token_program: order.dst_token_program,
+        system_program: system_program::ID,
+        associated_token_program: associated_token::ID,
+        maker_dst_ata: (!dst_is_native).then(|| {
+            pda::ata(
+                &order.maker_receiver,
+                &order.dst_mint,
+                &order.dst_token_program,
+            )
+        }),
+        taker_dst_ata: (!dst_is_native)
+            .then(|| pda::ata(&taker, &order.dst_mint, &order.dst_token_program)),
+        protocol_dst_acc: order.protocol_dst_acc,
+        integrator_dst_acc: order.integrator_dst_acc,
+    }
+    .to_account_metas(None);
+    accounts.extend(
+        order
+            .config
+            .fee
+            .integrator_fee_shares
+            .iter()
+            .map(|share| AccountMeta::new(share.recipient, false)),
+    );
+    Ok(accounts)
+}
+
+/// Builds `quote` of a fill of `amount` src tokens. The instruction only reads accounts and
+/// returns an `OrderQuote`, it is meant to be simulated
+pub fn quote(order: Order, amount: u64) -> Result<Instruction> {
+    let escrow = order.escrow()?;
+    let accounts = accounts::QuoteOrder {
+        maker: order.maker,
+        maker_receiver: order.maker_receiver,
+        src_mint: order.src_mint,
+        dst_mint: order.dst_mint,
+        escrow,
+        escrow_src_ata: pda::ata(&escrow, &order.src_mint, &order.src_token_program),
+        src_token_program: order.src_token_program,
+        protocol_dst_acc: order.protocol_dst_acc,
+        integrator_dst_acc: order.integrator_dst_acc,
+    };
+
+    Ok(Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::Quote {
+            order: order.config,
+            amount,
+        }
+        .data(),
+    })
+}
+
+/// Builds `cancel` of the order by its maker
+pub fn cancel(order: &Order) -> Result<Instruction> {
+    let order_hash = order.hash()?;
+    let escrow = pda::escrow(&order.maker, &order_hash);
+    let accounts = accounts::Cancel {
+        maker: order.maker,
+        src_mint: order.src_mint,
+        escrow,
+        escrow_src_ata: pda::ata(&escrow, &order.src_mint, &order.src_token_program),
+        maker_src_ata: order.maker_src_ata(),
+        src_token_program: order.src_token_program,
+    };
+
+    Ok(Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::Cancel {
+            order_hash,
+            order_src_asset_is_native: order.config.src_asset_is_native,
+        }
+        .data(),
+    })
+}
+
+fn cancel_by_resolver_accounts(
+    order: &Order,
+    signer: Pubkey,
+    resolver: Pubkey,
+    resolver_src_ata: Option<Pubkey>,
+) -> Result<Vec<AccountMeta>> {
+    let escrow = order.escrow()?;
+    Ok(accounts::CancelByResolver {
+        resolver: signer,
+        resolver_delegate: resolver_delegate(&signer, &resolver),
+        resolver_access: pda::resolver_access(&resolver),
+        maker: order.maker,
+        maker_receiver: order.maker_receiver,
+        src_mint: order.src_mint,
+        dst_mint: order.dst_mint,
+        escrow,
+        escrow_src_ata: pda::ata(&escrow, &order.src_mint, &order.src_token_program),
+        maker_src_ata: order.maker_src_ata(),
+        resolver_src_ata,
+        src_token_program: order.src_token_program,
+        system_program: system_program::ID,
+        protocol_dst_acc: order.protocol_dst_acc,
+        integrator_dst_acc: order.integrator_dst_acc,
+    }
+    .to_account_metas(None))
+}
+
+/// Resolver's ATA of the src mint if the order pays the cancellation premium in src tokens
+fn resolver_src_ata(order: &Order, resolver: &Pubkey) -> Option<Pubkey> {
+    (order.config.fee.max_src_cancellation_premium > 0)
+        .then(|| pda::ata(resolver, &order.src_mint, &order.src_token_program))
+}
+
+/// Builds `cancel_by_resolver` of an expired order
+pub fn cancel_by_resolver(
+    order: Order,
+    resolver: Pubkey,
+    reward_limit: u64,
+) -> Result<Instruction> {
+    cancel_by_resolver_for(order, resolver, resolver, reward_limit)
+}
+
+/// Builds `cancel_by_resolver` signed by `delegate` on behalf of `resolver`.
+/// The cancellation premium goes to the delegate
+pub fn delegated_cancel_by_resolver(
+    order: Order,
+    delegate: Pubkey,
+    resolver: Pubkey,
+    reward_limit: u64,
+) -> Result<Instruction> {
+    cancel_by_resolver_for(order, delegate, resolver, reward_limit)
+}
+
+fn cancel_by_resolver_for(
+    order: Order,
+    signer: Pubkey,
+    resolver: Pubkey,
+    reward_limit: u64,
+) -> Result<Instruction> {
+    let accounts =
+        cancel_by_resolver_accounts(&order, signer, resolver, resolver_src_ata(&order, &signer))?;
+
+    Ok(Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction::CancelByResolver {
+            order: order.config,
+            reward_limit,
+        }
+        .data(),
+    })
+}
+
+/// Builds `cancel_by_resolver_batch` of several expired orders
+pub fn cancel_by_resolver_batch(
+    orders: Vec<Order>,
+    resolver: Pubkey,
+    reward_limit: u64,
+) -> Result<Instruction> {
+    cancel_by_resolver_batch_for(orders, resolver, resolver, reward_limit)
+}
+
+/// Builds `cancel_by_resolver_batch` signed by `delegate` on behalf of `resolver`
+pub fn delegated_cancel_by_resolver_batch(
+    orders: Vec<Order>,
+    delegate: Pubkey,
+    resolver: Pubkey,
+    reward_limit: u64,
+) -> Result<Instruction> {
+    cancel_by_resolver_batch_for(orders, delegate, resolver, reward_limit)
+}
+
+fn cancel_by_resolver_batch_for(
+    orders: Vec<Order>,
+    signer: Pubkey,
+    resolver: Pubkey,
+    reward_limit: u64,
+) -> Result<Instruction> {
+    let mut accounts = accounts::CancelByResolverBatch {
+        resolver: signer,
+        resolver_delegate: resolver_delegate(&signer, &resolver),
+        resolver_access: pda::resolver_access(&resolver),
+    }
+    .to_account_metas(None);
+    for order in orders.iter() {
+        accounts.extend(cancel_by_resolver_accounts(
+            order,
+            signer,
+            resolver,
+            resolver_src_ata(order, &signer),
+        )?);
+    }
+
+    Ok(Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction::CancelByResolverBatch {
+            orders: orders.into_iter().map(|order| order.config).collect(),
+            reward_limit,
+        }
+        .data(),
+    })
+}
+
+/// Builds `claim_fees` sweeping the beneficiary's fee vault of `mint` into `beneficiary_dst_acc`
+pub fn claim_fees(
+    beneficiary: Pubkey,
+    mint: Pubkey,
+    beneficiary_dst_acc: Pubkey,
+    token_program: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ClaimFees {
+        beneficiary,
+        fee_vault_authority: pda::fee_vault_authority(&beneficiary),
+        mint,
+        fee_vault: pda::fee_vault(&beneficiary, &mint, &token_program),
+        beneficiary_dst_acc,
+        token_program,
+    };
+
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ClaimFees {}.data(),
+    }
+}
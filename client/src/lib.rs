@@ -0,0 +1,16 @@
+//! Rust client for the Fusion Swap and Whitelist programs: PDA derivation, typed
+//! instruction builders and decoding of program error codes.
+
+pub mod error;
+pub mod fusion_swap;
+pub mod pda;
+pub mod whitelist;
+
+pub use ::fusion_swap::{
+    auction, order_hash, quote, FeeConfig, IntegratorFeeShare, OrderConfig, OrderQuote,
+    ID as FUSION_SWAP_ID,
+};
+pub use ::whitelist::{
+    merkle, permissions, registry, role::Role, Registration, ResolverAccess, ResolverStatus,
+    WhitelistState, ID as WHITELIST_ID,
+};
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+/// Escrow PDA of the order, acting as the authority for the escrow ATA
+pub fn escrow(maker: &Pubkey, order_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow", maker.as_ref(), order_hash], &::fusion_swap::ID).0
+}
+
+/// Associated token account of `mint` owned by `owner`
+pub fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// ATA of the escrow holding the maker's src tokens
+pub fn escrow_src_ata(
+    maker: &Pubkey,
+    order_hash: &[u8; 32],
+    src_mint: &Pubkey,
+    src_token_program: &Pubkey,
+) -> Pubkey {
+    ata(&escrow(maker, order_hash), src_mint, src_token_program)
+}
+
+/// Whitelist state PDA holding the whitelist authority
+pub fn whitelist_state() -> Pubkey {
+    Pubkey::find_program_address(&[::whitelist::WHITELIST_STATE_SEED], &::whitelist::ID).0
+}
+
+/// Delegate PDA of a key signing on behalf of a whitelisted resolver
+pub fn resolver_delegate(delegate: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[::whitelist::RESOLVER_DELEGATE_SEED, delegate.as_ref()],
+        &::whitelist::ID,
+    )
+    .0
+}
+
+/// Resolver access PDA of a whitelisted resolver
+pub fn resolver_access(resolver: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[::whitelist::RESOLVER_ACCESS_SEED, resolver.as_ref()],
+        &::whitelist::ID,
+    )
+    .0
+}
+
+/// Page of the resolver registry
+pub fn resolver_page(page: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[::whitelist::RESOLVER_PAGE_SEED, &page.to_le_bytes()],
+        &::whitelist::ID,
+    )
+    .0
+}
+
+/// Bond PDA of a resolver, the authority of its bond vault
+pub fn resolver_bond(resolver: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[::whitelist::RESOLVER_BOND_SEED, resolver.as_ref()],
+        &::whitelist::ID,
+    )
+    .0
+}
+
+/// Vault of the resolver bond holding the bonded `mint`
+pub fn bond_vault(resolver: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    ata(&resolver_bond(resolver), mint, token_program)
+}
+
+/// Fee vault authority PDA of a beneficiary
+pub fn fee_vault_authority(beneficiary: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[::fusion_swap::FEE_VAULT_SEED, beneficiary.as_ref()],
+        &::fusion_swap::ID,
+    )
+    .0
+}
+
+/// Fee vault of a beneficiary for `mint`, usable as a fee destination in orders
+pub fn fee_vault(beneficiary: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    ata(&fee_vault_authority(beneficiary), mint, token_program)
+}
@@ -0,0 +1,459 @@
+//! Instruction builders for the Whitelist program
+
+use crate::pda;
+use ::whitelist::{
+    accounts, instruction, registry::RESOLVER_PAGE_SIZE, role::Role, Registration, ResolverAccess,
+    ID,
+};
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token;
+
+/// Builds `initialize` of the whitelist with `authority`
+pub fn initialize(authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Initialize {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::Initialize {}.data(),
+    }
+}
+
+/// Builds `migrate_whitelist_state` of the whitelist state created before versioning by its
+/// `authority`
+pub fn migrate_whitelist_state(authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::MigrateWhitelistState {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::MigrateWhitelistState {}.data(),
+    }
+}
+
+/// Builds `grant_role` of `role` to `user`
+pub fn grant_role(authority: Pubkey, role: Role, user: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ManageRole {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+        }
+        .to_account_metas(None),
+        data: instruction::GrantRole { role, user }.data(),
+    }
+}
+
+/// Builds `revoke_role` of `role` from `user`
+pub fn revoke_role(authority: Pubkey, role: Role, user: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ManageRole {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeRole { role, user }.data(),
+    }
+}
+
+/// Builds `register` of `registration.user` as a resolver by an operator, with the rent of the
+/// access paid by `payer`. `current_page` is `WhitelistState::current_page`, the registry page
+/// the user is added to
+pub fn register(
+    authority: Pubkey,
+    payer: Pubkey,
+    registration: Registration,
+    current_page: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: register_accounts(authority, payer, &registration.user, current_page),
+        data: instruction::Register {
+            user: registration.user,
+            valid_from: registration.valid_from,
+            valid_until: registration.valid_until,
+            permissions: registration.permissions,
+            label: registration.label,
+        }
+        .data(),
+    }
+}
+
+/// Builds `register_many` of `registrations` by an operator, with the rents paid by `payer`.
+/// `current_page` is `WhitelistState::current_page` and `current_page_len` the number of
+/// resolvers already in that page, the users are added to it and the following pages in order
+pub fn register_many(
+    authority: Pubkey,
+    payer: Pubkey,
+    registrations: Vec<Registration>,
+    current_page: u32,
+    current_page_len: usize,
+) -> Instruction {
+    let mut accounts = accounts::RegisterMany { authority }.to_account_metas(None);
+    for (i, registration) in registrations.iter().enumerate() {
+        let page = current_page + ((current_page_len + i) / RESOLVER_PAGE_SIZE) as u32;
+        accounts.extend(register_accounts(
+            authority,
+            payer,
+            &registration.user,
+            page,
+        ));
+    }
+
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction::RegisterMany { registrations }.data(),
+    }
+}
+
+fn register_accounts(
+    authority: Pubkey,
+    payer: Pubkey,
+    user: &Pubkey,
+    page: u32,
+) -> Vec<AccountMeta> {
+    accounts::Register {
+        authority,
+        payer,
+        whitelist_state: pda::whitelist_state(),
+        resolver_access: pda::resolver_access(user),
+        resolver_page: pda::resolver_page(page),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None)
+}
+
+/// Builds `renew` of the resolver `user` access until `valid_until` by an operator
+pub fn renew(authority: Pubkey, user: Pubkey, valid_until: i64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Renew {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            resolver_access: pda::resolver_access(&user),
+        }
+        .to_account_metas(None),
+        data: instruction::Renew { user, valid_until }.data(),
+    }
+}
+
+/// Builds `migrate_resolver_access` of the access of `user` registered before versioning by an
+/// operator, with the extra rent paid by `payer`. `current_page` is
+/// `WhitelistState::current_page`, the registry page the user is added to
+pub fn migrate_resolver_access(
+    authority: Pubkey,
+    payer: Pubkey,
+    user: Pubkey,
+    current_page: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::MigrateResolverAccess {
+            authority,
+            payer,
+            whitelist_state: pda::whitelist_state(),
+            resolver_access: pda::resolver_access(&user),
+            resolver_page: pda::resolver_page(current_page),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::MigrateResolverAccess { user }.data(),
+    }
+}
+
+/// Builds `update_permissions` of the resolver `user` by an operator
+pub fn update_permissions(authority: Pubkey, user: Pubkey, permissions: u8) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::UpdatePermissions {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            resolver_access: pda::resolver_access(&user),
+        }
+        .to_account_metas(None),
+        data: instruction::UpdatePermissions { user, permissions }.data(),
+    }
+}
+
+/// Builds `suspend` of the resolver `user` by a guardian or an operator
+pub fn suspend(authority: Pubkey, user: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Suspend {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            resolver_access: pda::resolver_access(&user),
+        }
+        .to_account_metas(None),
+        data: instruction::Suspend { user }.data(),
+    }
+}
+
+/// Builds `reinstate` of the suspended resolver `user` by an operator
+pub fn reinstate(authority: Pubkey, user: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Reinstate {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            resolver_access: pda::resolver_access(&user),
+        }
+        .to_account_metas(None),
+        data: instruction::Reinstate { user }.data(),
+    }
+}
+
+/// Builds `deregister` of the resolver `user` by an operator, refunding the rent to `payer`.
+/// `payer` and `registry_page` are `ResolverAccess::payer` and `ResolverAccess::registry_page`
+/// of the user
+pub fn deregister(
+    authority: Pubkey,
+    payer: Pubkey,
+    user: Pubkey,
+    registry_page: u32,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: deregister_accounts(authority, payer, &user, registry_page),
+        data: instruction::Deregister { user }.data(),
+    }
+}
+
+/// Builds `deregister_many` of the resolvers with `accesses` by an operator
+pub fn deregister_many(authority: Pubkey, accesses: &[ResolverAccess]) -> Instruction {
+    let mut accounts = accounts::DeregisterMany { authority }.to_account_metas(None);
+    for access in accesses {
+        accounts.extend(deregister_accounts(
+            authority,
+            access.payer,
+            &access.resolver,
+            access.registry_page,
+        ));
+    }
+
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction::DeregisterMany {
+            users: accesses.iter().map(|access| access.resolver).collect(),
+        }
+        .data(),
+    }
+}
+
+fn deregister_accounts(
+    authority: Pubkey,
+    payer: Pubkey,
+    user: &Pubkey,
+    registry_page: u32,
+) -> Vec<AccountMeta> {
+    accounts::Deregister {
+        authority,
+        payer,
+        whitelist_state: pda::whitelist_state(),
+        resolver_access: pda::resolver_access(user),
+        resolver_page: pda::resolver_page(registry_page),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None)
+}
+
+/// Builds `add_delegate` authorizing `delegate` to sign for the registered `resolver`,
+/// signed by both of them
+pub fn add_delegate(resolver: Pubkey, delegate: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::AddDelegate {
+            resolver,
+            delegate,
+            resolver_access: pda::resolver_access(&resolver),
+            resolver_delegate: pda::resolver_delegate(&delegate),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::AddDelegate {}.data(),
+    }
+}
+
+/// Builds `remove_delegate` revoking `delegate` of `resolver`
+pub fn remove_delegate(resolver: Pubkey, delegate: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::RemoveDelegate {
+            resolver,
+            resolver_delegate: pda::resolver_delegate(&delegate),
+        }
+        .to_account_metas(None),
+        data: instruction::RemoveDelegate { delegate }.data(),
+    }
+}
+
+/// Builds `set_resolver_root` replacing the Merkle root of the resolver tree
+pub fn set_resolver_root(authority: Pubkey, root: Option<[u8; 32]>) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::SetResolverRoot {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetResolverRoot { root }.data(),
+    }
+}
+
+/// Builds `set_bond_config` requiring a bond of at least `min_bond` of `bond_mint` to fill,
+/// withdrawable `unbonding_period` seconds after `unbond`
+pub fn set_bond_config(
+    authority: Pubkey,
+    bond_mint: Pubkey,
+    min_bond: u64,
+    unbonding_period: i64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::SetBondConfig {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            bond_mint,
+        }
+        .to_account_metas(None),
+        data: instruction::SetBondConfig {
+            min_bond,
+            unbonding_period,
+        }
+        .data(),
+    }
+}
+
+/// Builds `bond` of `amount` of `bond_mint` from the resolver's ATA
+pub fn bond(
+    resolver: Pubkey,
+    bond_mint: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Bond {
+            resolver,
+            whitelist_state: pda::whitelist_state(),
+            resolver_bond: pda::resolver_bond(&resolver),
+            bond_mint,
+            resolver_src_ata: pda::ata(&resolver, &bond_mint, &token_program),
+            bond_vault: pda::bond_vault(&resolver, &bond_mint, &token_program),
+            token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::Bond { amount }.data(),
+    }
+}
+
+/// Builds `unbond` of `amount` of the resolver's bond
+pub fn unbond(resolver: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Unbond {
+            resolver,
+            whitelist_state: pda::whitelist_state(),
+            resolver_bond: pda::resolver_bond(&resolver),
+        }
+        .to_account_metas(None),
+        data: instruction::Unbond { amount }.data(),
+    }
+}
+
+/// Builds `withdraw_bond` of the unbonded amount to the resolver's ATA
+pub fn withdraw_bond(resolver: Pubkey, bond_mint: Pubkey, token_program: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::WithdrawBond {
+            resolver,
+            whitelist_state: pda::whitelist_state(),
+            resolver_bond: pda::resolver_bond(&resolver),
+            bond_mint,
+            bond_vault: pda::bond_vault(&resolver, &bond_mint, &token_program),
+            resolver_dst_ata: pda::ata(&resolver, &bond_mint, &token_program),
+            token_program,
+        }
+        .to_account_metas(None),
+        data: instruction::WithdrawBond {}.data(),
+    }
+}
+
+/// Builds `slash` of `amount` of the bond of `resolver` to `slash_dst_ata`
+pub fn slash(
+    authority: Pubkey,
+    resolver: Pubkey,
+    bond_mint: Pubkey,
+    token_program: Pubkey,
+    slash_dst_ata: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::Slash {
+            authority,
+            whitelist_state: pda::whitelist_state(),
+            resolver_bond: pda::resolver_bond(&resolver),
+            bond_mint,
+            bond_vault: pda::bond_vault(&resolver, &bond_mint, &token_program),
+            slash_dst_ata,
+            token_program,
+        }
+        .to_account_metas(None),
+        data: instruction::Slash { resolver, amount }.data(),
+    }
+}
+
+/// Builds `propose_authority` of `new_authority` as the next whitelist authority
+pub fn propose_authority(current_authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::ProposeAuthority {
+            current_authority,
+            whitelist_state: pda::whitelist_state(),
+        }
+        .to_account_metas(None),
+        data: instruction::ProposeAuthority { new_authority }.data(),
+    }
+}
+
+/// Builds `accept_authority` of the pending transfer by `pending_authority`
+pub fn accept_authority(pending_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::AcceptAuthority {
+            pending_authority,
+            whitelist_state: pda::whitelist_state(),
+        }
+        .to_account_metas(None),
+        data: instruction::AcceptAuthority {}.data(),
+    }
+}
+
+/// Builds `cancel_authority_transfer` of the pending transfer
+pub fn cancel_authority_transfer(current_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts::CancelAuthorityTransfer {
+            current_authority,
+            whitelist_state: pda::whitelist_state(),
+        }
+        .to_account_metas(None),
+        data: instruction::CancelAuthorityTransfer {}.data(),
+    }
+}
@@ -0,0 +1,211 @@
+//! Proptest strategies for the Fusion Swap order parameters. Curves and fee configs are
+//! generated either arbitrarily or only among the values `create` accepts.
+
+use anchor_lang::prelude::Pubkey;
+use common::constants::{BASE_1E2, BASE_1E5};
+use fusion_swap::{
+    auction::{
+        AuctionData, ExponentialDecayCurve, LinearWithPlateauCurve, PiecewiseLinearCurve,
+        PointAndTimeDelta, StepCurve, MAX_AUCTION_POINTS,
+    },
+    FeeConfig, IntegratorFeeShare, OrderConfig, MAX_INTEGRATOR_FEE_SHARES,
+};
+use proptest::{collection::vec, prelude::*};
+
+fn points(max_len: usize) -> impl Strategy<Value = Vec<PointAndTimeDelta>> {
+    vec((any::<u16>(), any::<u16>()), 0..=max_len).prop_map(|points| {
+        points
+            .into_iter()
+            .map(|(rate_bump, time_delta)| PointAndTimeDelta {
+                rate_bump,
+                time_delta,
+            })
+            .collect()
+    })
+}
+
+/// Any curve, including the ones rejected by `AuctionData::validate`
+pub fn arbitrary_auction_data() -> impl Strategy<Value = AuctionData> {
+    prop_oneof![
+        (
+            any::<u32>(),
+            any::<u32>(),
+            any::<u16>(),
+            points(2 * MAX_AUCTION_POINTS)
+        )
+            .prop_map(
+                |(start_time, duration, initial_rate_bump, points_and_time_deltas)| {
+                    AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                        start_time,
+                        duration,
+                        initial_rate_bump,
+                        points_and_time_deltas,
+                    })
+                }
+            ),
+        (any::<u32>(), any::<u32>(), any::<u16>(), any::<u32>()).prop_map(
+            |(start_time, duration, initial_rate_bump, half_life)| {
+                AuctionData::ExponentialDecay(ExponentialDecayCurve {
+                    start_time,
+                    duration,
+                    initial_rate_bump,
+                    half_life,
+                })
+            }
+        ),
+        (
+            any::<u32>(),
+            any::<u32>(),
+            any::<u16>(),
+            points(2 * MAX_AUCTION_POINTS)
+        )
+            .prop_map(
+                |(start_time, duration, initial_rate_bump, points_and_time_deltas)| {
+                    AuctionData::Step(StepCurve {
+                        start_time,
+                        duration,
+                        initial_rate_bump,
+                        points_and_time_deltas,
+                    })
+                }
+            ),
+        (any::<u32>(), any::<u32>(), any::<u16>(), any::<u32>()).prop_map(
+            |(start_time, duration, initial_rate_bump, plateau_duration)| {
+                AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                    start_time,
+                    duration,
+                    initial_rate_bump,
+                    plateau_duration,
+                })
+            }
+        ),
+    ]
+}
+
+/// Curve accepted by `AuctionData::validate`: points do not increase the rate bump and fit into
+/// the duration, the plateau fits into the duration
+pub fn valid_auction_data() -> impl Strategy<Value = AuctionData> {
+    (arbitrary_auction_data(), any::<u32>()).prop_map(|(mut auction, extra_duration)| {
+        match &mut auction {
+            AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                duration,
+                initial_rate_bump,
+                points_and_time_deltas,
+                ..
+            })
+            | AuctionData::Step(StepCurve {
+                duration,
+                initial_rate_bump,
+                points_and_time_deltas,
+                ..
+            }) => {
+                points_and_time_deltas.truncate(MAX_AUCTION_POINTS);
+                points_and_time_deltas.sort_by_key(|point| std::cmp::Reverse(point.rate_bump));
+                let mut current_rate_bump = *initial_rate_bump;
+                for point in points_and_time_deltas.iter_mut() {
+                    point.rate_bump = point.rate_bump.min(current_rate_bump);
+                    current_rate_bump = point.rate_bump;
+                }
+                let total_time_delta: u32 = points_and_time_deltas
+                    .iter()
+                    .map(|point| point.time_delta as u32)
+                    .sum();
+                *duration = total_time_delta.saturating_add(extra_duration);
+            }
+            AuctionData::LinearWithPlateau(curve) => {
+                curve.plateau_duration = curve.plateau_duration.min(curve.duration);
+            }
+            AuctionData::ExponentialDecay(_) => {}
+        }
+        auction
+    })
+}
+
+/// Fee config accepted by `create`: fees sum up to at most `BASE_1E5`, shares are positive
+/// and surplus percentages sum up to at most `BASE_1E2`. Every next fee is capped by what is
+/// left of the total, so configs of exactly 100% are generated often
+pub fn fee_config() -> impl Strategy<Value = FeeConfig> {
+    (
+        any::<u16>(),
+        any::<u16>(),
+        vec(1..=u16::MAX, 0..=MAX_INTEGRATOR_FEE_SHARES),
+        [0..=BASE_1E2 as u8, 0..=BASE_1E2 as u8, 0..=BASE_1E2 as u8],
+        any::<u64>(),
+        any::<u64>(),
+    )
+        .prop_map(
+            |(
+                protocol_fee,
+                integrator_fee,
+                shares,
+                [surplus, integrator_surplus, resolver_surplus],
+                max_cancellation_premium,
+                max_src_cancellation_premium,
+            )| {
+                let mut fee_left = BASE_1E5;
+                let mut take_fee = |fee: u16| {
+                    let fee = (fee as u64).min(fee_left);
+                    fee_left -= fee;
+                    fee as u16
+                };
+                let protocol_fee = take_fee(protocol_fee);
+                let integrator_fee = take_fee(integrator_fee);
+                let integrator_fee_shares = shares
+                    .into_iter()
+                    .map(&mut take_fee)
+                    .filter(|&fee| fee > 0)
+                    .map(|fee| IntegratorFeeShare {
+                        recipient: Pubkey::new_unique(),
+                        fee,
+                    })
+                    .collect();
+
+                let mut surplus_left = BASE_1E2 as u8;
+                let mut take_surplus = |percentage: u8| {
+                    let percentage = percentage.min(surplus_left);
+                    surplus_left -= percentage;
+                    percentage
+                };
+
+                FeeConfig {
+                    protocol_fee,
+                    integrator_fee,
+                    integrator_fee_shares,
+                    surplus_percentage: take_surplus(surplus),
+                    integrator_surplus_percentage: take_surplus(integrator_surplus),
+                    resolver_surplus_percentage: take_surplus(resolver_surplus),
+                    max_cancellation_premium,
+                    max_src_cancellation_premium,
+                }
+            },
+        )
+}
+
+/// Valid order with amounts up to `max_amount`
+pub fn order_config(max_amount: u64) -> impl Strategy<Value = OrderConfig> {
+    (
+        1..=max_amount,
+        1..=max_amount,
+        0..=max_amount,
+        fee_config(),
+        valid_auction_data(),
+    )
+        .prop_map(
+            move |(src_amount, min_dst_amount, estimated_surplus, fee, dutch_auction_data)| {
+                OrderConfig {
+                    id: 0,
+                    src_amount,
+                    min_dst_amount,
+                    estimated_dst_amount: min_dst_amount
+                        .saturating_add(estimated_surplus)
+                        .min(max_amount),
+                    expiration_time: u32::MAX,
+                    src_asset_is_native: false,
+                    dst_asset_is_native: false,
+                    fee,
+                    dutch_auction_data,
+                    cancellation_auction_duration: 0,
+                }
+            },
+        )
+}
@@ -0,0 +1,655 @@
+//! Stateful fuzzing of the Fusion Swap and Whitelist programs: random sequences of `create`,
+//! `fill`, `cancel`, `cancel_by_resolver`, `register` and `deregister`, including calls with
+//! accounts of other users and orders, run on the in-process SVM of the integration tests.
+//! After every step the token balances, the escrows and the outcome of the call are checked
+//! against a model of the programs.
+
+use fusion_integration_tests::{fusion_swap_instruction, registration, TestEnv, START_TIME};
+use fusion_swap::{
+    auction::{calculate_premium, AuctionData, PiecewiseLinearCurve},
+    quote::quote,
+};
+use fusion_swap_client::{
+    fusion_swap::{self as fusion_swap_ix, Order},
+    whitelist as whitelist_ix,
+};
+use proptest::{collection::vec, prelude::*, sample::Index};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// Every case starts a new SVM, so the default number of cases is kept low
+const DEFAULT_CASES: u32 = 16;
+
+const USERS: usize = 4;
+const INITIAL_BALANCE: u64 = 1_000_000_000;
+const MAX_SRC_AMOUNT: u64 = 1_000_000;
+/// Below the rent of the escrow ATA, as required by `create`
+const MAX_CANCELLATION_PREMIUM: u64 = 1_000_000;
+/// Orders live up to a few warps, so that both fills and cancellations by resolvers happen
+const MAX_LIFETIME: u32 = 600;
+
+#[derive(Clone, Debug)]
+enum Premium {
+    None,
+    Lamports(u64),
+    /// Fraction of the src amount in `u16` units
+    Src(u16),
+}
+
+/// Account of a `fill` replaced by an attacker's one
+#[derive(Clone, Debug)]
+enum Redirect {
+    /// Maker receiver and its dst ATA, taking the maker's proceeds
+    MakerReceiver(usize),
+    /// Protocol fee account
+    ProtocolFee(usize),
+    /// Escrow and escrow ATA of another order
+    Escrow(Index),
+}
+
+#[derive(Clone, Debug)]
+enum Step {
+    Create {
+        maker: usize,
+        src_amount: u64,
+        min_dst_amount: u64,
+        protocol_fee: u16,
+        initial_rate_bump: u16,
+        premium: Premium,
+        cancellation_auction_duration: u32,
+        lifetime: u32,
+    },
+    /// `fill` of an amount between zero and one more than what is left in the escrow
+    Fill {
+        taker: usize,
+        order: Index,
+        amount: Index,
+    },
+    RedirectedFill {
+        taker: usize,
+        order: Index,
+        amount: u64,
+        redirect: Redirect,
+    },
+    /// `cancel` signed by `signer`, which passes its own src ATA as the maker's one
+    Cancel {
+        signer: usize,
+        order: Index,
+    },
+    CancelByResolver {
+        resolver: usize,
+        order: Index,
+        reward_limit: u64,
+    },
+    /// `register` signed by `signer`, by the whitelist authority if `None`
+    Register {
+        signer: Option<usize>,
+        user: usize,
+    },
+    Deregister {
+        signer: Option<usize>,
+        user: usize,
+    },
+    Warp {
+        seconds: u32,
+    },
+}
+
+fn user() -> impl Strategy<Value = usize> {
+    0..USERS
+}
+
+fn premium() -> impl Strategy<Value = Premium> {
+    prop_oneof![
+        Just(Premium::None),
+        (1..=MAX_CANCELLATION_PREMIUM).prop_map(Premium::Lamports),
+        any::<u16>().prop_map(Premium::Src),
+    ]
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    prop_oneof![
+        3 => (
+            user(),
+            1..=MAX_SRC_AMOUNT,
+            1..=MAX_SRC_AMOUNT,
+            prop_oneof![Just(0), 1..=10_000u16],
+            0..=10_000u16,
+            premium(),
+            0..=MAX_LIFETIME,
+            1..=MAX_LIFETIME,
+        )
+            .prop_map(
+                |(
+                    maker,
+                    src_amount,
+                    min_dst_amount,
+                    protocol_fee,
+                    initial_rate_bump,
+                    premium,
+                    cancellation_auction_duration,
+                    lifetime,
+                )| Step::Create {
+                    maker,
+                    src_amount,
+                    min_dst_amount,
+                    protocol_fee,
+                    initial_rate_bump,
+                    premium,
+                    cancellation_auction_duration,
+                    lifetime,
+                }
+            ),
+        4 => (user(), any::<Index>(), any::<Index>())
+            .prop_map(|(taker, order, amount)| Step::Fill { taker, order, amount }),
+        2 => (
+            user(),
+            any::<Index>(),
+            1..=MAX_SRC_AMOUNT,
+            prop_oneof![
+                user().prop_map(Redirect::MakerReceiver),
+                user().prop_map(Redirect::ProtocolFee),
+                any::<Index>().prop_map(Redirect::Escrow),
+            ],
+        )
+            .prop_map(|(taker, order, amount, redirect)| Step::RedirectedFill {
+                taker,
+                order,
+                amount,
+                redirect,
+            }),
+        2 => (user(), any::<Index>()).prop_map(|(signer, order)| Step::Cancel { signer, order }),
+        2 => (user(), any::<Index>(), any::<u64>()).prop_map(
+            |(resolver, order, reward_limit)| Step::CancelByResolver {
+                resolver,
+                order,
+                reward_limit,
+            }
+        ),
+        1 => (prop::option::weighted(0.2, user()), user())
+            .prop_map(|(signer, user)| Step::Register { signer, user }),
+        1 => (prop::option::weighted(0.2, user()), user())
+            .prop_map(|(signer, user)| Step::Deregister { signer, user }),
+        3 => (1..=MAX_LIFETIME / 2).prop_map(|seconds| Step::Warp { seconds }),
+    ]
+}
+
+struct ModelOrder {
+    order: Order,
+    /// Src tokens left in the escrow, the escrow ATA is closed once it is `None`
+    remaining: Option<u64>,
+}
+
+/// Expected state of the programs: token balances of the users of the src (`0`) and dst (`1`)
+/// tokens, escrows and whitelisted resolvers
+struct Model {
+    now: i64,
+    balances: [[u64; 2]; USERS],
+    orders: Vec<ModelOrder>,
+    registered: [bool; USERS],
+}
+
+struct Fuzzer {
+    env: TestEnv,
+    model: Model,
+    /// Total supply of the src and dst tokens, all held by the users and the escrows
+    supply: [u64; 2],
+}
+
+impl Fuzzer {
+    async fn new() -> Self {
+        let env = TestEnv::new().await;
+        env.register(&env.charlie.pubkey()).await;
+        for user in 0..USERS {
+            let user = user_keypair(&env, user).pubkey();
+            for token in &env.tokens[..2] {
+                env.mint_to(token, &user, INITIAL_BALANCE).await;
+            }
+        }
+
+        let mut fuzzer = Self {
+            env,
+            model: Model {
+                now: START_TIME,
+                balances: [[0; 2]; USERS],
+                orders: vec![],
+                registered: [false, true, true, false],
+            },
+            supply: [0; 2],
+        };
+        fuzzer.model.balances = fuzzer.balances().await;
+        for token in 0..2 {
+            fuzzer.supply[token] = fuzzer.model.balances.iter().map(|b| b[token]).sum();
+        }
+        fuzzer
+    }
+
+    fn user(&self, user: usize) -> &Keypair {
+        user_keypair(&self.env, user)
+    }
+
+    fn ata(&self, user: usize, token: usize) -> Pubkey {
+        self.env
+            .ata(&self.user(user).pubkey(), &self.env.tokens[token])
+    }
+
+    fn user_index(&self, address: &Pubkey) -> usize {
+        (0..USERS)
+            .find(|&user| self.user(user).pubkey() == *address)
+            .unwrap()
+    }
+
+    async fn balances(&self) -> [[u64; 2]; USERS] {
+        let mut balances = [[0; 2]; USERS];
+        for (user, balance) in balances.iter_mut().enumerate() {
+            for (token, balance) in balance.iter_mut().enumerate() {
+                *balance = self.env.balance(&self.ata(user, token)).await;
+            }
+        }
+        balances
+    }
+
+    /// Checks the state of the programs against the model
+    async fn check(&self, step: &Step) -> Result<(), TestCaseError> {
+        let balances = self.balances().await;
+        prop_assert_eq!(balances, self.model.balances, "balances after {:?}", step);
+
+        let mut escrowed = 0;
+        for order in self.model.orders.iter() {
+            let ata = order.order.escrow_src_ata().unwrap();
+            match order.remaining {
+                Some(remaining) => {
+                    prop_assert_eq!(self.env.balance(&ata).await, remaining);
+                    escrowed += remaining;
+                }
+                None => prop_assert!(
+                    self.env.account(&ata).await.is_none(),
+                    "escrow ATA {} is not closed after {:?}",
+                    ata,
+                    step
+                ),
+            }
+        }
+
+        // Conservation of tokens: nothing is minted or burnt by the programs
+        let held = |token: usize| balances.iter().map(|b| b[token]).sum::<u64>();
+        prop_assert_eq!(held(0) + escrowed, self.supply[0]);
+        prop_assert_eq!(held(1), self.supply[1]);
+        Ok(())
+    }
+
+    /// Runs the step and updates the model, checking that the programs accept the step iff the
+    /// model does
+    async fn run(&mut self, step: &Step) -> Result<(), TestCaseError> {
+        let (expected, result) = match step.clone() {
+            Step::Create {
+                maker,
+                src_amount,
+                min_dst_amount,
+                protocol_fee,
+                initial_rate_bump,
+                premium,
+                cancellation_auction_duration,
+                lifetime,
+            } => {
+                let now = self.model.now as u32;
+                let mut order = self.env.order();
+                order.maker = self.user(maker).pubkey();
+                order.maker_receiver = order.maker;
+                order.config.src_amount = src_amount;
+                order.config.min_dst_amount = min_dst_amount;
+                order.config.estimated_dst_amount = min_dst_amount;
+                order.config.expiration_time = now + lifetime;
+                order.config.cancellation_auction_duration = cancellation_auction_duration;
+                order.config.dutch_auction_data =
+                    AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                        start_time: now,
+                        duration: lifetime,
+                        initial_rate_bump,
+                        points_and_time_deltas: vec![],
+                    });
+                order.config.fee.protocol_fee = protocol_fee;
+                if protocol_fee > 0 {
+                    order.protocol_dst_acc = Some(self.ata(3, 1));
+                }
+                match premium {
+                    Premium::None => {}
+                    Premium::Lamports(premium) => {
+                        order.config.fee.max_cancellation_premium = premium;
+                    }
+                    Premium::Src(fraction) => {
+                        order.config.fee.max_src_cancellation_premium =
+                            src_amount * fraction as u64 / u16::MAX as u64;
+                    }
+                }
+
+                let result = self
+                    .env
+                    .send(
+                        &[fusion_swap_ix::create(order.clone()).unwrap()],
+                        &[self.user(maker)],
+                    )
+                    .await;
+                if result.is_ok() {
+                    self.model.balances[maker][0] -= src_amount;
+                    self.model.orders.push(ModelOrder {
+                        order,
+                        remaining: Some(src_amount),
+                    });
+                }
+                (true, result)
+            }
+
+            Step::Fill {
+                taker,
+                order,
+                amount,
+            } => {
+                let Some(index) = self.order_index(order) else {
+                    return Ok(());
+                };
+                let order = self.model.orders[index].order.clone();
+                let amount = amount.index(
+                    self.model.orders[index]
+                        .remaining
+                        .unwrap_or(order.config.src_amount) as usize
+                        + 2,
+                ) as u64;
+                let expected = self.model.registered[taker]
+                    && self.model.now < order.config.expiration_time as i64
+                    && amount != 0
+                    && self.model.orders[index]
+                        .remaining
+                        .is_some_and(|remaining| amount <= remaining);
+
+                let result = self
+                    .env
+                    .send(
+                        &[
+                            fusion_swap_ix::fill(order.clone(), self.user(taker).pubkey(), amount)
+                                .unwrap(),
+                        ],
+                        &[self.user(taker)],
+                    )
+                    .await;
+                if result.is_ok() {
+                    let fee_amounts = quote(&order.config, self.model.now as u64, amount)
+                        .unwrap()
+                        .fee_amounts;
+                    let maker = self.user_index(&order.maker_receiver);
+                    let balances = &mut self.model.balances;
+                    balances[taker][0] += amount;
+                    balances[taker][1] -= fee_amounts.maker_amount + fee_amounts.protocol_fee;
+                    balances[maker][1] += fee_amounts.maker_amount;
+                    balances[3][1] += fee_amounts.protocol_fee;
+
+                    let remaining = &mut self.model.orders[index].remaining;
+                    *remaining = remaining
+                        .map(|remaining| remaining - amount)
+                        .filter(|&r| r > 0);
+                }
+                (expected, result)
+            }
+
+            Step::RedirectedFill {
+                taker,
+                order,
+                amount,
+                redirect,
+            } => {
+                let Some(index) = self.order_index(order) else {
+                    return Ok(());
+                };
+                let order = self.model.orders[index].order.clone();
+                let mut accounts = self
+                    .env
+                    .fill_accounts_for(&order, self.user(taker).pubkey());
+                match redirect {
+                    Redirect::MakerReceiver(attacker) => {
+                        accounts.maker_receiver = self.user(attacker).pubkey();
+                        accounts.maker_dst_ata = Some(self.ata(attacker, 1));
+                    }
+                    Redirect::ProtocolFee(attacker) => {
+                        accounts.protocol_dst_acc = Some(self.ata(attacker, 1));
+                    }
+                    Redirect::Escrow(other) => {
+                        let other = &self.model.orders[other.index(self.model.orders.len())];
+                        accounts.escrow = other.order.escrow().unwrap();
+                        accounts.escrow_src_ata = other.order.escrow_src_ata().unwrap();
+                    }
+                }
+                let honest = self
+                    .env
+                    .fill_accounts_for(&order, self.user(taker).pubkey());
+                if accounts.maker_receiver == honest.maker_receiver
+                    && accounts.protocol_dst_acc == honest.protocol_dst_acc
+                    && accounts.escrow == honest.escrow
+                {
+                    return Ok(());
+                }
+
+                let result = self
+                    .env
+                    .send(
+                        &[fusion_swap_instruction(
+                            accounts,
+                            fusion_swap::instruction::Fill {
+                                order: order.config,
+                                amount,
+                            },
+                        )],
+                        &[self.user(taker)],
+                    )
+                    .await;
+                (false, result)
+            }
+
+            Step::Cancel { signer, order } => {
+                let Some(index) = self.order_index(order) else {
+                    return Ok(());
+                };
+                self.cancel(signer, index).await
+            }
+
+            Step::CancelByResolver {
+                resolver,
+                order,
+                reward_limit,
+            } => {
+                let Some(index) = self.order_index(order) else {
+                    return Ok(());
+                };
+                let order = self.model.orders[index].order.clone();
+                let config = &order.config;
+                let expected = self.model.registered[resolver]
+                    && self.model.now >= config.expiration_time as i64
+                    && (config.fee.max_cancellation_premium > 0
+                        || config.fee.max_src_cancellation_premium > 0)
+                    && self.model.orders[index].remaining.is_some();
+
+                let resolver_key = self.user(resolver).pubkey();
+                let lamports_before = self.env.lamports(&resolver_key).await;
+                let result = self
+                    .env
+                    .send(
+                        &[fusion_swap_ix::cancel_by_resolver(
+                            order.clone(),
+                            resolver_key,
+                            reward_limit,
+                        )
+                        .unwrap()],
+                        &[self.user(resolver)],
+                    )
+                    .await;
+                if result.is_ok() {
+                    let premium = |max_premium| {
+                        calculate_premium(
+                            self.model.now as u32,
+                            config.expiration_time,
+                            config.cancellation_auction_duration,
+                            max_premium,
+                        )
+                        .min(reward_limit)
+                    };
+                    let lamports_premium = premium(config.fee.max_cancellation_premium);
+                    let remaining = self.model.orders[index].remaining.unwrap();
+                    let src_premium =
+                        premium(config.fee.max_src_cancellation_premium).min(remaining);
+                    let maker = self.user_index(&order.maker);
+                    self.model.balances[resolver][0] += src_premium;
+                    self.model.balances[maker][0] += remaining - src_premium;
+                    self.model.orders[index].remaining = None;
+
+                    if maker != resolver {
+                        let lamports = self.env.lamports(&resolver_key).await - lamports_before;
+                        prop_assert_eq!(lamports, lamports_premium);
+                    }
+                }
+                (expected, result)
+            }
+
+            Step::Register { signer, user } => {
+                let authority = signer.map_or(self.env.payer(), |signer| self.user(signer));
+                let result = self
+                    .env
+                    .send(
+                        &[whitelist_ix::register(
+                            authority.pubkey(),
+                            self.env.payer().pubkey(),
+                            registration(self.user(user).pubkey()),
+                            self.env.current_page().await,
+                        )],
+                        &[authority],
+                    )
+                    .await;
+                let expected = signer.is_none() && !self.model.registered[user];
+                if result.is_ok() {
+                    self.model.registered[user] = true;
+                }
+                (expected, result)
+            }
+
+            Step::Deregister { signer, user } => {
+                let authority = signer.map_or(self.env.payer(), |signer| self.user(signer));
+                let result = self
+                    .env
+                    .send(
+                        &[whitelist_ix::deregister(
+                            authority.pubkey(),
+                            self.env.payer().pubkey(),
+                            self.user(user).pubkey(),
+                            self.env.registry_page(&self.user(user).pubkey()).await,
+                        )],
+                        &[authority],
+                    )
+                    .await;
+                let expected = signer.is_none() && self.model.registered[user];
+                if result.is_ok() {
+                    self.model.registered[user] = false;
+                }
+                (expected, result)
+            }
+
+            Step::Warp { seconds } => {
+                self.model.now += seconds as i64;
+                self.env.set_time(self.model.now);
+                return self.check(step).await;
+            }
+        };
+
+        prop_assert_eq!(
+            result.is_ok(),
+            expected,
+            "{:?} resulted in {:?}",
+            step,
+            result
+        );
+        self.check(step).await
+    }
+
+    /// Cancels the order by `signer`, which passes its own src ATA as the maker's one
+    async fn cancel(
+        &mut self,
+        signer: usize,
+        index: usize,
+    ) -> (bool, Result<(), BanksClientError>) {
+        let order = self.model.orders[index].order.clone();
+        let is_maker = self.user(signer).pubkey() == order.maker;
+        let remaining = self.model.orders[index].remaining;
+
+        let instruction = if is_maker {
+            fusion_swap_ix::cancel(&order).unwrap()
+        } else {
+            fusion_swap_instruction(
+                fusion_swap::accounts::Cancel {
+                    maker: self.user(signer).pubkey(),
+                    src_mint: order.src_mint,
+                    escrow: order.escrow().unwrap(),
+                    escrow_src_ata: order.escrow_src_ata().unwrap(),
+                    maker_src_ata: Some(self.ata(signer, 0)),
+                    src_token_program: order.src_token_program,
+                },
+                fusion_swap::instruction::Cancel {
+                    order_hash: order.hash().unwrap(),
+                    order_src_asset_is_native: false,
+                },
+            )
+        };
+        let result = self.env.send(&[instruction], &[self.user(signer)]).await;
+        if result.is_ok() {
+            self.model.balances[signer][0] += remaining.unwrap_or_default();
+            self.model.orders[index].remaining = None;
+        }
+        // The maker can always take back the tokens of a live escrow
+        (is_maker && remaining.is_some(), result)
+    }
+
+    fn order_index(&self, order: Index) -> Option<usize> {
+        (!self.model.orders.is_empty()).then(|| order.index(self.model.orders.len()))
+    }
+}
+
+fn user_keypair(env: &TestEnv, user: usize) -> &Keypair {
+    [&env.alice, &env.bob, &env.charlie, &env.dave][user]
+}
+
+fn config() -> ProptestConfig {
+    ProptestConfig {
+        cases: std::env::var("PROPTEST_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(DEFAULT_CASES),
+        ..ProptestConfig::default()
+    }
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn instruction_sequences_keep_invariants(steps in vec(step(), 1..=32)) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let mut fuzzer = Fuzzer::new().await;
+            for step in steps.iter() {
+                fuzzer.run(step).await?;
+            }
+
+            // No sequence locks the maker's funds: every escrow still open can be cancelled
+            for index in 0..fuzzer.model.orders.len() {
+                let maker = fuzzer.user_index(&fuzzer.model.orders[index].order.maker);
+                let (expected, result) = fuzzer.cancel(maker, index).await;
+                prop_assert_eq!(result.is_ok(), expected, "final cancel resulted in {:?}", result);
+            }
+            fuzzer.check(&Step::Warp { seconds: 0 }).await?;
+            prop_assert_eq!(fuzzer.model.balances.iter().map(|b| b[0]).sum::<u64>(), fuzzer.supply[0]);
+            Ok(())
+        })?;
+    }
+}
@@ -0,0 +1,172 @@
+use fusion_fuzz::{arbitrary_auction_data, fee_config, order_config, valid_auction_data};
+use fusion_swap::{
+    auction::{calculate_premium, AuctionCurve, AuctionData},
+    quote::{get_fee_amounts, quote, FeeAmounts},
+};
+use proptest::prelude::*;
+
+/// Amounts for which no intermediate value of a fill overflows: the dst amount of a fill is at
+/// most `min_dst_amount` increased by a rate bump below 66%
+const MAX_AMOUNT: u64 = u64::MAX / 2;
+
+fn initial_rate_bump(auction: &AuctionData) -> u64 {
+    match auction {
+        AuctionData::PiecewiseLinear(curve) => curve.initial_rate_bump,
+        AuctionData::ExponentialDecay(curve) => curve.initial_rate_bump,
+        AuctionData::Step(curve) => curve.initial_rate_bump,
+        AuctionData::LinearWithPlateau(curve) => curve.initial_rate_bump,
+    }
+    .into()
+}
+
+/// Everything paid by the taker
+fn total(fee_amounts: &FeeAmounts) -> u64 {
+    fee_amounts.maker_amount
+        + fee_amounts.protocol_fee
+        + fee_amounts.integrator_fee
+        + fee_amounts.integrator_fee_shares.iter().sum::<u64>()
+        + fee_amounts.resolver_surplus
+}
+
+/// Dst amount of a fill and the amount its fees are taken from, which is at most the dst amount
+/// and a few units lower when the dst amount is rounded up
+fn dst_amount_and_fee_base() -> impl Strategy<Value = (u64, u64)> {
+    (0..=MAX_AMOUNT).prop_flat_map(|dst_amount| {
+        (
+            Just(dst_amount),
+            prop_oneof![dst_amount.saturating_sub(3)..=dst_amount, 0..=dst_amount],
+        )
+    })
+}
+
+proptest! {
+    #[test]
+    fn rate_bump_does_not_panic(auction in arbitrary_auction_data(), timestamp: u64) {
+        auction.rate_bump(timestamp);
+    }
+
+    #[test]
+    fn rate_bump_stays_within_initial_rate_bump(
+        auction in valid_auction_data(),
+        timestamp: u64,
+    ) {
+        prop_assert!(auction.rate_bump(timestamp) <= initial_rate_bump(&auction));
+    }
+
+    #[test]
+    fn rate_bump_does_not_increase_over_time(
+        auction in valid_auction_data(),
+        timestamp in 0..=u32::MAX as u64 * 2,
+        elapsed in 0..=u32::MAX as u64,
+    ) {
+        prop_assert!(auction.rate_bump(timestamp + elapsed) <= auction.rate_bump(timestamp));
+    }
+
+    #[test]
+    fn premium_does_not_exceed_max(
+        timestamp: u32,
+        auction_start_time: u32,
+        auction_duration: u32,
+        max_cancellation_premium: u64,
+    ) {
+        let premium = calculate_premium(
+            timestamp,
+            auction_start_time,
+            auction_duration,
+            max_cancellation_premium,
+        );
+        prop_assert!(premium <= max_cancellation_premium);
+    }
+
+    #[test]
+    fn premium_does_not_decrease_over_time(
+        timestamp: u32,
+        elapsed: u32,
+        auction_start_time: u32,
+        auction_duration: u32,
+        max_cancellation_premium: u64,
+    ) {
+        let premium = |timestamp| {
+            calculate_premium(
+                timestamp,
+                auction_start_time,
+                auction_duration,
+                max_cancellation_premium,
+            )
+        };
+        prop_assert!(premium(timestamp.saturating_add(elapsed)) >= premium(timestamp));
+    }
+
+    #[test]
+    fn quote_does_not_panic_for_any_amounts(
+        order in order_config(u64::MAX),
+        timestamp: u64,
+        amount: u64,
+    ) {
+        let _ = quote(&order, timestamp, amount);
+    }
+
+    #[test]
+    fn quote_does_not_overflow(
+        (order, amount) in order_config(MAX_AMOUNT)
+            .prop_flat_map(|order| (1..=order.src_amount).prop_map(move |amount| (order.clone(), amount))),
+        timestamp: u64,
+    ) {
+        prop_assert!(quote(&order, timestamp, amount).is_ok());
+    }
+
+    #[test]
+    fn fees_and_maker_amount_sum_up_to_dst_amount(
+        fee in fee_config(),
+        (dst_amount, fee_base) in dst_amount_and_fee_base(),
+        estimated_dst_amount in 0..=MAX_AMOUNT,
+    ) {
+        let fee_amounts =
+            get_fee_amounts(&fee, dst_amount, fee_base, estimated_dst_amount).unwrap();
+        prop_assert_eq!(total(&fee_amounts), dst_amount);
+        prop_assert!(fee_amounts.maker_surplus <= fee_amounts.maker_amount);
+        prop_assert!(fee_amounts.protocol_surplus <= fee_amounts.protocol_fee);
+        prop_assert!(fee_amounts.integrator_surplus <= fee_amounts.integrator_fee);
+    }
+
+    #[test]
+    fn surplus_is_only_taken_above_estimated_amount(
+        fee in fee_config(),
+        (dst_amount, fee_base) in dst_amount_and_fee_base(),
+        estimated_dst_amount in 0..=MAX_AMOUNT,
+    ) {
+        let fee_amounts =
+            get_fee_amounts(&fee, dst_amount, fee_base, estimated_dst_amount).unwrap();
+        let amount_after_fees = dst_amount
+            - (fee_amounts.protocol_fee - fee_amounts.protocol_surplus)
+            - (fee_amounts.integrator_fee - fee_amounts.integrator_surplus)
+            - fee_amounts.integrator_fee_shares.iter().sum::<u64>();
+        prop_assert!(fee_amounts.maker_amount >= amount_after_fees.min(estimated_dst_amount));
+    }
+
+    #[test]
+    fn splitting_fill_does_not_lower_maker_proceeds(
+        (order, amount, first_amount) in prop_oneof![order_config(100), order_config(MAX_AMOUNT)]
+            .prop_map(|mut order| {
+                order.src_amount = order.src_amount.max(2);
+                order
+            })
+            .prop_flat_map(|order| (Just(order.clone()), 2..=order.src_amount))
+            .prop_flat_map(|(order, amount)| (Just(order), Just(amount), 1..amount)),
+        timestamp: u64,
+    ) {
+        let whole = quote(&order, timestamp, amount).unwrap();
+        let first = quote(&order, timestamp, first_amount).unwrap();
+        let second = quote(&order, timestamp, amount - first_amount).unwrap();
+
+        prop_assert!(first.dst_amount + second.dst_amount >= whole.dst_amount);
+        prop_assert!(
+            first.fee_amounts.maker_amount + second.fee_amounts.maker_amount
+                >= whole.fee_amounts.maker_amount,
+            "{} + {} < {}",
+            first.fee_amounts.maker_amount,
+            second.fee_amounts.maker_amount,
+            whole.fee_amounts.maker_amount
+        );
+    }
+}
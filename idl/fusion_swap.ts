@@ -231,10 +231,84 @@ export type FusionSwap = {
           "writable": true,
           "signer": true
         },
+        {
+          "name": "resolverDelegate",
+          "docs": [
+            "Delegate record of `resolver` when it signs on behalf of a whitelisted resolver"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "resolver"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
         {
           "name": "resolverAccess",
           "docs": [
-            "Account allowed to cancel the order"
+            "Access of the resolver allowed to cancel the order"
           ],
           "pda": {
             "seeds": [
@@ -448,6 +522,14 @@ export type FusionSwap = {
             }
           }
         },
+        {
+          "name": "resolverSrcAta",
+          "docs": [
+            "Resolver's account of src_mint to receive the cancellation premium in src tokens"
+          ],
+          "writable": true,
+          "optional": true
+        },
         {
           "name": "srcTokenProgram"
         },
@@ -480,250 +562,134 @@ export type FusionSwap = {
       ]
     },
     {
-      "name": "create",
+      "name": "cancelByResolverBatch",
+      "docs": [
+        "Cancels several expired orders in one instruction. Every order takes a group of",
+        "`CANCEL_BY_RESOLVER_ACCOUNTS_LEN` remaining accounts laid out as in `cancel_by_resolver`.",
+        "Orders whose escrow ATA is already closed are skipped"
+      ],
       "discriminator": [
-        24,
-        30,
-        200,
-        40,
-        5,
-        28,
-        7,
-        119
+        39,
+        2,
+        80,
+        120,
+        74,
+        162,
+        198,
+        217
       ],
       "accounts": [
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "escrow",
-          "docs": [
-            "PDA derived from order details, acting as the authority for the escrow ATA"
-          ]
-        },
-        {
-          "name": "srcMint",
+          "name": "resolver",
           "docs": [
-            "Source asset"
-          ]
-        },
-        {
-          "name": "srcTokenProgram"
+            "Account that cancels the escrows"
+          ],
+          "signer": true
         },
         {
-          "name": "escrowSrcAta",
+          "name": "resolverDelegate",
           "docs": [
-            "ATA of src_mint to store escrowed tokens"
+            "Delegate record of `resolver` when it signs on behalf of a whitelisted resolver"
           ],
-          "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "escrow"
-              },
-              {
-                "kind": "account",
-                "path": "srcTokenProgram"
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
               },
               {
                 "kind": "account",
-                "path": "srcMint"
+                "path": "resolver"
               }
             ],
             "program": {
               "kind": "const",
               "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
                 123,
-                216,
-                219,
-                233,
+                72,
+                5,
+                135,
                 248,
-                89
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
               ]
             }
           }
         },
         {
-          "name": "maker",
-          "docs": [
-            "`maker`, who is willing to sell src token for dst token"
-          ],
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "makerSrcAta",
+          "name": "resolverAccess",
           "docs": [
-            "Maker's ATA of src_mint"
+            "Access of the resolver allowed to cancel the orders"
           ],
-          "writable": true,
-          "optional": true,
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "maker"
-              },
-              {
-                "kind": "account",
-                "path": "srcTokenProgram"
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
               },
               {
                 "kind": "account",
-                "path": "srcMint"
-              }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
-          }
-        },
-        {
-          "name": "dstMint",
-          "docs": [
-            "Destination asset"
-          ]
-        },
-        {
-          "name": "makerReceiver"
-        },
-        {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
-        },
-        {
-          "name": "protocolDstAcc",
-          "optional": true
-        },
-        {
-          "name": "integratorDstAcc",
-          "optional": true
-        }
-      ],
-      "args": [
-        {
-          "name": "order",
-          "type": {
-            "defined": {
-              "name": "orderConfig"
-            }
-          }
-        }
-      ]
-    },
-    {
-      "name": "fill",
-      "discriminator": [
-        168,
-        96,
-        183,
-        163,
-        92,
-        10,
-        40,
-        160
-      ],
-      "accounts": [
-        {
-          "name": "taker",
-          "docs": [
-            "`taker`, who buys `src_mint` for `dst_mint`"
-          ],
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "resolverAccess",
-          "docs": [
-            "Account allowed to fill the order"
-          ],
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  114,
-                  101,
-                  115,
-                  111,
-                  108,
-                  118,
-                  101,
-                  114,
-                  95,
-                  97,
-                  99,
-                  99,
-                  101,
-                  115,
-                  115
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "taker"
+                "path": "resolver"
               }
             ],
             "program": {
@@ -764,52 +730,101 @@ export type FusionSwap = {
               ]
             }
           }
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "maker",
-          "writable": true
+          "name": "orders",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "orderConfig"
+              }
+            }
+          }
         },
         {
-          "name": "makerReceiver",
-          "writable": true
-        },
+          "name": "rewardLimit",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "claimFees",
+      "docs": [
+        "Sweeps the fees accumulated in the beneficiary's fee vault for the given mint"
+      ],
+      "discriminator": [
+        82,
+        251,
+        233,
+        156,
+        12,
+        52,
+        184,
+        202
+      ],
+      "accounts": [
         {
-          "name": "srcMint",
+          "name": "beneficiary",
           "docs": [
-            "Maker asset"
-          ]
+            "Beneficiary of the fees accumulated in the vault"
+          ],
+          "signer": true
         },
         {
-          "name": "dstMint",
+          "name": "feeVaultAuthority",
           "docs": [
-            "Taker asset"
-          ]
+            "PDA derived from the beneficiary, acting as the authority for its fee vaults"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  102,
+                  101,
+                  101,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "beneficiary"
+              }
+            ]
+          }
         },
         {
-          "name": "escrow",
+          "name": "mint",
           "docs": [
-            "PDA derived from order details, acting as the authority for the escrow ATA"
+            "Fee asset"
           ]
         },
         {
-          "name": "escrowSrcAta",
+          "name": "feeVault",
           "docs": [
-            "ATA of src_mint to store escrowed tokens"
+            "ATA of mint to accumulate fees, used as a fee destination in orders"
           ],
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "account",
-                "path": "escrow"
+                "path": "feeVaultAuthority"
               },
               {
                 "kind": "account",
-                "path": "srcTokenProgram"
+                "path": "tokenProgram"
               },
               {
                 "kind": "account",
-                "path": "srcMint"
+                "path": "mint"
               }
             ],
             "program": {
@@ -852,46 +867,69 @@ export type FusionSwap = {
           }
         },
         {
-          "name": "takerSrcAta",
+          "name": "beneficiaryDstAcc",
           "docs": [
-            "Taker's ATA of src_mint"
+            "Account to receive the claimed fees"
           ],
           "writable": true
         },
         {
-          "name": "srcTokenProgram"
+          "name": "tokenProgram"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "create",
+      "discriminator": [
+        24,
+        30,
+        200,
+        40,
+        5,
+        28,
+        7,
+        119
+      ],
+      "accounts": [
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
         },
         {
-          "name": "dstTokenProgram"
+          "name": "escrow",
+          "docs": [
+            "PDA derived from order details, acting as the authority for the escrow ATA"
+          ]
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "srcMint",
+          "docs": [
+            "Source asset"
+          ]
         },
         {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+          "name": "srcTokenProgram"
         },
         {
-          "name": "makerDstAta",
+          "name": "escrowSrcAta",
           "docs": [
-            "Maker's ATA of dst_mint"
+            "ATA of src_mint to store escrowed tokens"
           ],
           "writable": true,
-          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "account",
-                "path": "makerReceiver"
+                "path": "escrow"
               },
               {
                 "kind": "account",
-                "path": "dstTokenProgram"
+                "path": "srcTokenProgram"
               },
               {
                 "kind": "account",
-                "path": "dstMint"
+                "path": "srcMint"
               }
             ],
             "program": {
@@ -934,9 +972,17 @@ export type FusionSwap = {
           }
         },
         {
-          "name": "takerDstAta",
+          "name": "maker",
           "docs": [
-            "Taker's ATA of dst_mint"
+            "`maker`, who is willing to sell src token for dst token"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "makerSrcAta",
+          "docs": [
+            "Maker's ATA of src_mint"
           ],
           "writable": true,
           "optional": true,
@@ -944,15 +990,15 @@ export type FusionSwap = {
             "seeds": [
               {
                 "kind": "account",
-                "path": "taker"
+                "path": "maker"
               },
               {
                 "kind": "account",
-                "path": "dstTokenProgram"
+                "path": "srcTokenProgram"
               },
               {
                 "kind": "account",
-                "path": "dstMint"
+                "path": "srcMint"
               }
             ],
             "program": {
@@ -994,14 +1040,25 @@ export type FusionSwap = {
             }
           }
         },
+        {
+          "name": "dstMint",
+          "docs": [
+            "Destination asset"
+          ]
+        },
+        {
+          "name": "makerReceiver"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
         {
           "name": "protocolDstAcc",
-          "writable": true,
           "optional": true
         },
         {
           "name": "integratorDstAcc",
-          "writable": true,
           "optional": true
         }
       ],
@@ -1013,27 +1070,1328 @@ export type FusionSwap = {
               "name": "orderConfig"
             }
           }
-        },
-        {
-          "name": "amount",
-          "type": "u64"
         }
       ]
-    }
-  ],
-  "accounts": [
+    },
     {
-      "name": "resolverAccess",
+      "name": "fill",
       "discriminator": [
-        32,
-        2,
-        74,
-        248,
-        174,
-        108,
+        168,
+        96,
+        183,
+        163,
+        92,
+        10,
+        40,
+        160
+      ],
+      "accounts": [
+        {
+          "name": "taker",
+          "docs": [
+            "`taker`, who buys `src_mint` for `dst_mint`"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "resolverDelegate",
+          "docs": [
+            "Delegate record of `taker` when it signs on behalf of a whitelisted resolver"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "taker"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "docs": [
+            "Access of the resolver allowed to fill the order, required by `fill`"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "taker"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "whitelistState",
+          "docs": [
+            "Whitelist state holding the resolver root and the minimum bond"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "resolverBond",
+          "docs": [
+            "Bond of the resolver, which does not exist if the resolver has never bonded"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "taker"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "maker",
+          "writable": true
+        },
+        {
+          "name": "makerReceiver",
+          "writable": true
+        },
+        {
+          "name": "srcMint",
+          "docs": [
+            "Maker asset"
+          ]
+        },
+        {
+          "name": "dstMint",
+          "docs": [
+            "Taker asset"
+          ]
+        },
+        {
+          "name": "escrow",
+          "docs": [
+            "PDA derived from order details, acting as the authority for the escrow ATA"
+          ]
+        },
+        {
+          "name": "escrowSrcAta",
+          "docs": [
+            "ATA of src_mint to store escrowed tokens"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "account",
+                "path": "srcTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "srcMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerSrcAta",
+          "docs": [
+            "Taker's ATA of src_mint"
+          ],
+          "writable": true
+        },
+        {
+          "name": "srcTokenProgram"
+        },
+        {
+          "name": "dstTokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "makerDstAta",
+          "docs": [
+            "Maker's ATA of dst_mint"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "makerReceiver"
+              },
+              {
+                "kind": "account",
+                "path": "dstTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "dstMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerDstAta",
+          "docs": [
+            "Taker's ATA of dst_mint"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "taker"
+              },
+              {
+                "kind": "account",
+                "path": "dstTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "dstMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "protocolDstAcc",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "integratorDstAcc",
+          "writable": true,
+          "optional": true
+        }
+      ],
+      "args": [
+        {
+          "name": "order",
+          "type": {
+            "defined": {
+              "name": "orderConfig"
+            }
+          }
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "fillWithProof",
+      "docs": [
+        "Fills the order by a resolver proven to be in the Merkle tree of",
+        "`WhitelistState::resolver_root` instead of holding a `ResolverAccess` account.",
+        "A delegate still needs the access of the resolver that added it"
+      ],
+      "discriminator": [
+        170,
+        180,
+        76,
+        119,
+        220,
+        23,
+        204,
+        32
+      ],
+      "accounts": [
+        {
+          "name": "taker",
+          "docs": [
+            "`taker`, who buys `src_mint` for `dst_mint`"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "resolverDelegate",
+          "docs": [
+            "Delegate record of `taker` when it signs on behalf of a whitelisted resolver"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "taker"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "docs": [
+            "Access of the resolver allowed to fill the order, required by `fill`"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "taker"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "whitelistState",
+          "docs": [
+            "Whitelist state holding the resolver root and the minimum bond"
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "resolverBond",
+          "docs": [
+            "Bond of the resolver, which does not exist if the resolver has never bonded"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "taker"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                70,
+                114,
+                58,
+                183,
+                136,
+                135,
+                252,
+                146,
+                80,
+                123,
+                72,
+                5,
+                135,
+                248,
+                186,
+                224,
+                25,
+                166,
+                101,
+                165,
+                0,
+                26,
+                153,
+                232,
+                9,
+                37,
+                218,
+                240,
+                178,
+                106,
+                153,
+                93
+              ]
+            }
+          }
+        },
+        {
+          "name": "maker",
+          "writable": true
+        },
+        {
+          "name": "makerReceiver",
+          "writable": true
+        },
+        {
+          "name": "srcMint",
+          "docs": [
+            "Maker asset"
+          ]
+        },
+        {
+          "name": "dstMint",
+          "docs": [
+            "Taker asset"
+          ]
+        },
+        {
+          "name": "escrow",
+          "docs": [
+            "PDA derived from order details, acting as the authority for the escrow ATA"
+          ]
+        },
+        {
+          "name": "escrowSrcAta",
+          "docs": [
+            "ATA of src_mint to store escrowed tokens"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "account",
+                "path": "srcTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "srcMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerSrcAta",
+          "docs": [
+            "Taker's ATA of src_mint"
+          ],
+          "writable": true
+        },
+        {
+          "name": "srcTokenProgram"
+        },
+        {
+          "name": "dstTokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "makerDstAta",
+          "docs": [
+            "Maker's ATA of dst_mint"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "makerReceiver"
+              },
+              {
+                "kind": "account",
+                "path": "dstTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "dstMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerDstAta",
+          "docs": [
+            "Taker's ATA of dst_mint"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "taker"
+              },
+              {
+                "kind": "account",
+                "path": "dstTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "dstMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "protocolDstAcc",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "integratorDstAcc",
+          "writable": true,
+          "optional": true
+        }
+      ],
+      "args": [
+        {
+          "name": "order",
+          "type": {
+            "defined": {
+              "name": "orderConfig"
+            }
+          }
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "proof",
+          "type": {
+            "defined": {
+              "name": "resolverProof"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "quote",
+      "docs": [
+        "Quotes a fill of `amount` src tokens at the current time without changing any account.",
+        "The quote is the return data of the instruction, so that simulating it gives the exact",
+        "amounts `fill` would transfer"
+      ],
+      "discriminator": [
+        149,
+        42,
+        109,
+        247,
+        134,
+        146,
+        213,
+        123
+      ],
+      "accounts": [
+        {
+          "name": "maker"
+        },
+        {
+          "name": "makerReceiver"
+        },
+        {
+          "name": "srcMint",
+          "docs": [
+            "Maker asset"
+          ]
+        },
+        {
+          "name": "dstMint",
+          "docs": [
+            "Taker asset"
+          ]
+        },
+        {
+          "name": "escrow",
+          "docs": [
+            "PDA derived from order details, acting as the authority for the escrow ATA"
+          ]
+        },
+        {
+          "name": "escrowSrcAta",
+          "docs": [
+            "ATA of src_mint to store escrowed tokens"
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "account",
+                "path": "srcTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "srcMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "srcTokenProgram"
+        },
+        {
+          "name": "protocolDstAcc",
+          "optional": true
+        },
+        {
+          "name": "integratorDstAcc",
+          "optional": true
+        }
+      ],
+      "args": [
+        {
+          "name": "order",
+          "type": {
+            "defined": {
+              "name": "orderConfig"
+            }
+          }
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "orderQuote"
+        }
+      }
+    }
+  ],
+  "accounts": [
+    {
+      "name": "resolverAccess",
+      "discriminator": [
+        32,
+        2,
+        74,
+        248,
+        174,
+        108,
         70,
         156
       ]
+    },
+    {
+      "name": "resolverDelegate",
+      "discriminator": [
+        95,
+        158,
+        209,
+        143,
+        40,
+        82,
+        118,
+        131
+      ]
+    },
+    {
+      "name": "whitelistState",
+      "discriminator": [
+        246,
+        118,
+        44,
+        60,
+        71,
+        37,
+        201,
+        55
+      ]
+    }
+  ],
+  "events": [
+    {
+      "name": "orderFilled",
+      "discriminator": [
+        120,
+        124,
+        109,
+        66,
+        249,
+        116,
+        174,
+        30
+      ]
     }
   ],
   "errors": [
@@ -1078,44 +2436,600 @@ export type FusionSwap = {
       "msg": "Protocol surplus fee too high"
     },
     {
-      "code": 6008,
-      "name": "inconsistentProtocolFeeConfig",
-      "msg": "Inconsistent protocol fee config"
+      "code": 6008,
+      "name": "inconsistentProtocolFeeConfig",
+      "msg": "Inconsistent protocol fee config"
+    },
+    {
+      "code": 6009,
+      "name": "inconsistentIntegratorFeeConfig",
+      "msg": "Inconsistent integrator fee config"
+    },
+    {
+      "code": 6010,
+      "name": "orderNotExpired",
+      "msg": "Order not expired"
+    },
+    {
+      "code": 6011,
+      "name": "invalidCancellationFee",
+      "msg": "Invalid cancellation fee"
+    },
+    {
+      "code": 6012,
+      "name": "cancelOrderByResolverIsForbidden",
+      "msg": "Cancel order by resolver is forbidden"
+    },
+    {
+      "code": 6013,
+      "name": "missingTakerDstAta",
+      "msg": "Missing taker dst ata"
+    },
+    {
+      "code": 6014,
+      "name": "missingMakerSrcAta",
+      "msg": "Missing maker src ata"
+    },
+    {
+      "code": 6015,
+      "name": "tooManyIntegratorFeeShares",
+      "msg": "Too many integrator fee shares"
+    },
+    {
+      "code": 6016,
+      "name": "invalidIntegratorFeeShare",
+      "msg": "Invalid integrator fee share"
+    },
+    {
+      "code": 6017,
+      "name": "inconsistentIntegratorFeeShares",
+      "msg": "Inconsistent integrator fee shares"
+    },
+    {
+      "code": 6018,
+      "name": "invalidTotalFee",
+      "msg": "Total fee too high"
+    },
+    {
+      "code": 6019,
+      "name": "noFeesToClaim",
+      "msg": "No fees to claim"
+    },
+    {
+      "code": 6020,
+      "name": "invalidSurplusSplit",
+      "msg": "Invalid surplus split"
+    },
+    {
+      "code": 6021,
+      "name": "missingResolverSrcAta",
+      "msg": "Missing resolver src ata"
+    },
+    {
+      "code": 6022,
+      "name": "inconsistentBatchAccounts",
+      "msg": "Inconsistent batch accounts"
+    },
+    {
+      "code": 6023,
+      "name": "tooManyAuctionPoints",
+      "msg": "Too many auction points"
+    },
+    {
+      "code": 6024,
+      "name": "increasingAuctionRateBump",
+      "msg": "Auction rate bumps must not increase"
+    },
+    {
+      "code": 6025,
+      "name": "auctionPointsExceedDuration",
+      "msg": "Auction points exceed auction duration"
+    },
+    {
+      "code": 6026,
+      "name": "auctionStartsAfterExpiration",
+      "msg": "Auction starts after order expiration"
+    },
+    {
+      "code": 6027,
+      "name": "resolverAccessNotValid",
+      "msg": "Resolver access is not valid at the current time"
+    },
+    {
+      "code": 6028,
+      "name": "resolverSuspended",
+      "msg": "Resolver is suspended"
+    },
+    {
+      "code": 6029,
+      "name": "missingResolverPermission",
+      "msg": "Resolver is not permitted to call the instruction"
+    },
+    {
+      "code": 6030,
+      "name": "missingResolverAccess",
+      "msg": "Missing resolver access"
+    },
+    {
+      "code": 6031,
+      "name": "missingWhitelistState",
+      "msg": "Missing whitelist state"
+    },
+    {
+      "code": 6032,
+      "name": "resolverRootNotSet",
+      "msg": "Resolver root is not set"
+    },
+    {
+      "code": 6033,
+      "name": "invalidResolverProof",
+      "msg": "Invalid resolver proof"
+    },
+    {
+      "code": 6034,
+      "name": "insufficientResolverBond",
+      "msg": "Resolver bond is below the minimum"
+    },
+    {
+      "code": 6035,
+      "name": "invalidResolverDelegate",
+      "msg": "Delegate was not added under the current registration of the resolver"
+    }
+  ],
+  "types": [
+    {
+      "name": "auctionData",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "piecewiseLinear",
+            "fields": [
+              {
+                "defined": {
+                  "name": "piecewiseLinearCurve"
+                }
+              }
+            ]
+          },
+          {
+            "name": "exponentialDecay",
+            "fields": [
+              {
+                "defined": {
+                  "name": "exponentialDecayCurve"
+                }
+              }
+            ]
+          },
+          {
+            "name": "step",
+            "fields": [
+              {
+                "defined": {
+                  "name": "stepCurve"
+                }
+              }
+            ]
+          },
+          {
+            "name": "linearWithPlateau",
+            "fields": [
+              {
+                "defined": {
+                  "name": "linearWithPlateauCurve"
+                }
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "exponentialDecayCurve",
+      "docs": [
+        "Rate bump halves every `half_life` seconds, linearly interpolated within each half-life,",
+        "and drops to zero at the end of the auction"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "startTime",
+            "type": "u32"
+          },
+          {
+            "name": "duration",
+            "type": "u32"
+          },
+          {
+            "name": "initialRateBump",
+            "type": "u16"
+          },
+          {
+            "name": "halfLife",
+            "type": "u32"
+          }
+        ]
+      }
+    },
+    {
+      "name": "feeAmounts",
+      "docs": [
+        "Amounts of `dst_mint` tokens the filled amount is split into"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "protocolFee",
+            "docs": [
+              "Protocol fee including the protocol surplus"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "integratorFee",
+            "docs": [
+              "Integrator fee including the integrator surplus"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "integratorFeeShares",
+            "docs": [
+              "Amounts paid to the integrator fee share recipients, in the order config order"
+            ],
+            "type": {
+              "vec": "u64"
+            }
+          },
+          {
+            "name": "makerAmount",
+            "docs": [
+              "Maker amount including the maker surplus"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "makerSurplus",
+            "type": "u64"
+          },
+          {
+            "name": "protocolSurplus",
+            "type": "u64"
+          },
+          {
+            "name": "integratorSurplus",
+            "type": "u64"
+          },
+          {
+            "name": "resolverSurplus",
+            "docs": [
+              "Part of the surplus the taker keeps, i.e. does not pay at all"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "feeConfig",
+      "docs": [
+        "Configuration for fees applied to the escrow"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "protocolFee",
+            "docs": [
+              "Protocol fee in basis points where `BASE_1E5` = 100%"
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "integratorFee",
+            "docs": [
+              "Integrator fee in basis points where `BASE_1E5` = 100%"
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "integratorFeeShares",
+            "docs": [
+              "Integrator fee shares paid to additional recipients, e.g. wallet, aggregator and referrer"
+            ],
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "integratorFeeShare"
+                }
+              }
+            }
+          },
+          {
+            "name": "surplusPercentage",
+            "docs": [
+              "Percentage of positive slippage taken by the protocol as an additional fee.",
+              "Value in basis points where `BASE_1E2` = 100%"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "integratorSurplusPercentage",
+            "docs": [
+              "Percentage of positive slippage taken by the integrator as an additional fee.",
+              "Value in basis points where `BASE_1E2` = 100%"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "resolverSurplusPercentage",
+            "docs": [
+              "Percentage of positive slippage left to the filling resolver.",
+              "Value in basis points where `BASE_1E2` = 100%"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "maxCancellationPremium",
+            "docs": [
+              "Maximum cancellation premium",
+              "Value in absolute lamports amount"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "maxSrcCancellationPremium",
+            "docs": [
+              "Maximum cancellation premium taken from the escrowed src tokens, for non-native src orders",
+              "Value in absolute src tokens amount"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6009,
-      "name": "inconsistentIntegratorFeeConfig",
-      "msg": "Inconsistent integrator fee config"
+      "name": "integratorFeeShare",
+      "docs": [
+        "Share of the integrator fee paid to an additional recipient"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "recipient",
+            "docs": [
+              "Account receiving the share, passed to `fill` in `remaining_accounts`"
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "fee",
+            "docs": [
+              "Share fee in basis points where `BASE_1E5` = 100%"
+            ],
+            "type": "u16"
+          }
+        ]
+      }
     },
     {
-      "code": 6010,
-      "name": "orderNotExpired",
-      "msg": "Order not expired"
+      "name": "linearWithPlateauCurve",
+      "docs": [
+        "Rate bump stays at the initial value during the exclusive period of `plateau_duration`",
+        "seconds, then decreases linearly to zero at the end of the auction"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "startTime",
+            "type": "u32"
+          },
+          {
+            "name": "duration",
+            "type": "u32"
+          },
+          {
+            "name": "initialRateBump",
+            "type": "u16"
+          },
+          {
+            "name": "plateauDuration",
+            "type": "u32"
+          }
+        ]
+      }
     },
     {
-      "code": 6011,
-      "name": "invalidCancellationFee",
-      "msg": "Invalid cancellation fee"
+      "name": "orderConfig",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "type": "u32"
+          },
+          {
+            "name": "srcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "minDstAmount",
+            "type": "u64"
+          },
+          {
+            "name": "estimatedDstAmount",
+            "type": "u64"
+          },
+          {
+            "name": "expirationTime",
+            "type": "u32"
+          },
+          {
+            "name": "srcAssetIsNative",
+            "type": "bool"
+          },
+          {
+            "name": "dstAssetIsNative",
+            "type": "bool"
+          },
+          {
+            "name": "fee",
+            "type": {
+              "defined": {
+                "name": "feeConfig"
+              }
+            }
+          },
+          {
+            "name": "dutchAuctionData",
+            "type": {
+              "defined": {
+                "name": "auctionData"
+              }
+            }
+          },
+          {
+            "name": "cancellationAuctionDuration",
+            "type": "u32"
+          }
+        ]
+      }
     },
     {
-      "code": 6012,
-      "name": "cancelOrderByResolverIsForbidden",
-      "msg": "Cancel order by resolver is forbidden"
+      "name": "orderFilled",
+      "docs": [
+        "Emitted on every fill with the split of the filled dst amount.",
+        "Surplus amounts are parts of the maker amount and fees respectively, except for",
+        "`resolver_surplus` which is not paid by the taker at all"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "orderHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "maker",
+            "type": "pubkey"
+          },
+          {
+            "name": "taker",
+            "type": "pubkey"
+          },
+          {
+            "name": "resolver",
+            "docs": [
+              "Whitelisted resolver the fill is attributed to, `taker` unless it signs as a delegate"
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "srcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "remainingSrcAmount",
+            "type": "u64"
+          },
+          {
+            "name": "dstAmount",
+            "type": "u64"
+          },
+          {
+            "name": "makerAmount",
+            "type": "u64"
+          },
+          {
+            "name": "protocolFee",
+            "type": "u64"
+          },
+          {
+            "name": "integratorFee",
+            "type": "u64"
+          },
+          {
+            "name": "integratorFeeShares",
+            "type": {
+              "vec": "u64"
+            }
+          },
+          {
+            "name": "makerSurplus",
+            "type": "u64"
+          },
+          {
+            "name": "protocolSurplus",
+            "type": "u64"
+          },
+          {
+            "name": "integratorSurplus",
+            "type": "u64"
+          },
+          {
+            "name": "resolverSurplus",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6013,
-      "name": "missingTakerDstAta",
-      "msg": "Missing taker dst ata"
+      "name": "orderQuote",
+      "docs": [
+        "Amounts of a fill at the current time, returned by `quote`"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "orderHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "rateBump",
+            "docs": [
+              "Rate bump of the auction curve, in basis points where `BASE_1E5` = 100%"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "dstAmount",
+            "docs": [
+              "Amount of `dst_mint` tokens the taker pays, including all fees"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "feeAmounts",
+            "type": {
+              "defined": {
+                "name": "feeAmounts"
+              }
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6014,
-      "name": "missingMakerSrcAta",
-      "msg": "Missing maker src ata"
-    }
-  ],
-  "types": [
-    {
-      "name": "auctionData",
+      "name": "piecewiseLinearCurve",
+      "docs": [
+        "Linear interpolation between points, decreasing to zero at the end of the auction"
+      ],
       "type": {
         "kind": "struct",
         "fields": [
@@ -1145,126 +3059,306 @@ export type FusionSwap = {
       }
     },
     {
-      "name": "feeConfig",
-      "docs": [
-        "Configuration for fees applied to the escrow"
-      ],
+      "name": "pointAndTimeDelta",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "protocolFee",
-            "docs": [
-              "Protocol fee in basis points where `BASE_1E5` = 100%"
-            ],
+            "name": "rateBump",
             "type": "u16"
           },
           {
-            "name": "integratorFee",
-            "docs": [
-              "Integrator fee in basis points where `BASE_1E5` = 100%"
-            ],
+            "name": "timeDelta",
             "type": "u16"
-          },
-          {
-            "name": "surplusPercentage",
-            "docs": [
-              "Percentage of positive slippage taken by the protocol as an additional fee.",
-              "Value in basis points where `BASE_1E2` = 100%"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "maxCancellationPremium",
-            "docs": [
-              "Maximum cancellation premium",
-              "Value in absolute lamports amount"
-            ],
-            "type": "u64"
           }
         ]
       }
     },
     {
-      "name": "orderConfig",
+      "name": "resolverAccess",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "id",
-            "type": "u32"
+            "name": "version",
+            "docs": [
+              "Version of the layout, `migration::RESOLVER_ACCESS_VERSION`"
+            ],
+            "type": "u8"
           },
           {
-            "name": "srcAmount",
-            "type": "u64"
+            "name": "bump",
+            "type": "u8"
           },
           {
-            "name": "minDstAmount",
-            "type": "u64"
+            "name": "resolver",
+            "type": "pubkey"
           },
           {
-            "name": "estimatedDstAmount",
+            "name": "payer",
+            "docs": [
+              "Account that paid the rent of the access, refunded on deregistration"
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "registeredAt",
+            "docs": [
+              "Unix timestamp of the registration"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "generation",
+            "docs": [
+              "Number of the registration among all registrations, see `WhitelistState::registrations`"
+            ],
             "type": "u64"
           },
           {
-            "name": "expirationTime",
+            "name": "label",
+            "type": "string"
+          },
+          {
+            "name": "registryPage",
+            "docs": [
+              "Page of the resolver registry holding the resolver"
+            ],
             "type": "u32"
           },
           {
-            "name": "srcAssetIsNative",
-            "type": "bool"
+            "name": "validFrom",
+            "docs": [
+              "Unix timestamp from which the access is valid"
+            ],
+            "type": "i64"
           },
           {
-            "name": "dstAssetIsNative",
-            "type": "bool"
+            "name": "validUntil",
+            "docs": [
+              "Unix timestamp at which the access lapses"
+            ],
+            "type": "i64"
           },
           {
-            "name": "fee",
+            "name": "status",
             "type": {
               "defined": {
-                "name": "feeConfig"
+                "name": "resolverStatus"
               }
             }
           },
           {
-            "name": "dutchAuctionData",
+            "name": "permissions",
+            "docs": [
+              "Bitmask of the `permissions` bits"
+            ],
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverDelegate",
+      "docs": [
+        "Key allowed to sign for `resolver`, derived from the delegate key"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "generation",
+            "docs": [
+              "`ResolverAccess::generation` of the resolver when the delegate was added. The delegate",
+              "is not valid for a later registration of the resolver"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverProof",
+      "docs": [
+        "Proof that a resolver is in the tree of `WhitelistState::resolver_root`"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "validUntil",
+            "docs": [
+              "Unix timestamp at which the access lapses, as committed in the leaf"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "proof",
+            "docs": [
+              "Sibling hashes from the leaf up to the root"
+            ],
             "type": {
-              "defined": {
-                "name": "auctionData"
+              "vec": {
+                "array": [
+                  "u8",
+                  32
+                ]
               }
             }
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "active"
           },
           {
-            "name": "cancellationAuctionDuration",
-            "type": "u32"
+            "name": "suspended"
           }
         ]
       }
     },
     {
-      "name": "pointAndTimeDelta",
+      "name": "stepCurve",
+      "docs": [
+        "Rate bump stays constant between points and jumps to the next value at each point,",
+        "then drops to zero at the end of the auction"
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "rateBump",
-            "type": "u16"
+            "name": "startTime",
+            "type": "u32"
           },
           {
-            "name": "timeDelta",
+            "name": "duration",
+            "type": "u32"
+          },
+          {
+            "name": "initialRateBump",
             "type": "u16"
+          },
+          {
+            "name": "pointsAndTimeDeltas",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "pointAndTimeDelta"
+                }
+              }
+            }
           }
         ]
       }
     },
     {
-      "name": "resolverAccess",
+      "name": "whitelistState",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "bump",
+            "name": "version",
+            "docs": [
+              "Version of the layout, `migration::WHITELIST_STATE_VERSION`"
+            ],
             "type": "u8"
+          },
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "pendingAuthority",
+            "docs": [
+              "Authority proposed by `propose_authority`, waiting to accept the transfer"
+            ],
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "operators",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "guardians",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "resolverRoot",
+            "docs": [
+              "Merkle root of the `merkle` tree of resolvers allowed to fill with a proof"
+            ],
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          },
+          {
+            "name": "resolverCount",
+            "docs": [
+              "Number of registered resolvers"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "currentPage",
+            "docs": [
+              "Page of the resolver registry new resolvers are added to"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "registrations",
+            "docs": [
+              "Number of registrations so far, including deregistered ones"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "bondMint",
+            "docs": [
+              "Asset of the resolver bonds, set by `set_bond_config`"
+            ],
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "minBond",
+            "docs": [
+              "Bonded amount required to fill, zero if no bond is required"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "unbondingPeriod",
+            "docs": [
+              "Seconds between `unbond` and the withdrawal of the unbonded amount"
+            ],
+            "type": "i64"
           }
         ]
       }
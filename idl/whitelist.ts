@@ -17,28 +17,28 @@ export type Whitelist = {
   ],
   "instructions": [
     {
-      "name": "deregister",
+      "name": "acceptAuthority",
       "docs": [
-        "Removes a user from the whitelist"
+        "Accepts the pending authority transfer by the proposed authority"
       ],
       "discriminator": [
-        161,
-        178,
-        39,
-        189,
-        231,
-        224,
-        13,
-        187
+        107,
+        86,
+        198,
+        91,
+        33,
+        12,
+        107,
+        160
       ],
       "accounts": [
         {
-          "name": "authority",
-          "writable": true,
+          "name": "pendingAuthority",
           "signer": true
         },
         {
           "name": "whitelistState",
+          "writable": true,
           "pda": {
             "seeds": [
               {
@@ -63,10 +63,42 @@ export type Whitelist = {
               }
             ]
           }
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "addDelegate",
+      "docs": [
+        "Authorizes `delegate` to sign `fill` and `cancel_by_resolver` on behalf of the",
+        "registered resolver, which pays for the delegate account. Both of them sign, and the",
+        "delegate is only valid for the current registration of the resolver"
+      ],
+      "discriminator": [
+        3,
+        67,
+        128,
+        218,
+        69,
+        139,
+        53,
+        88
+      ],
+      "accounts": [
+        {
+          "name": "resolver",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "delegate",
+          "docs": [
+            "Key signing on behalf of the resolver, which proves it accepts the delegation"
+          ],
+          "signer": true
         },
         {
           "name": "resolverAccess",
-          "writable": true,
           "pda": {
             "seeds": [
               {
@@ -90,8 +122,42 @@ export type Whitelist = {
                 ]
               },
               {
-                "kind": "arg",
-                "path": "user"
+                "kind": "account",
+                "path": "resolver"
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverDelegate",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "delegate"
               }
             ]
           }
@@ -101,37 +167,31 @@ export type Whitelist = {
           "address": "11111111111111111111111111111111"
         }
       ],
-      "args": [
-        {
-          "name": "user",
-          "type": "pubkey"
-        }
-      ]
+      "args": []
     },
     {
-      "name": "initialize",
+      "name": "bond",
       "docs": [
-        "Initializes the whitelist with the authority"
+        "Deposits `amount` of the bond mint to the bond of the resolver"
       ],
       "discriminator": [
-        175,
-        175,
-        109,
-        31,
-        13,
-        152,
-        155,
-        237
+        18,
+        108,
+        96,
+        244,
+        162,
+        35,
+        142,
+        202
       ],
       "accounts": [
         {
-          "name": "authority",
+          "name": "resolver",
           "writable": true,
           "signer": true
         },
         {
           "name": "whitelistState",
-          "writable": true,
           "pda": {
             "seeds": [
               {
@@ -157,36 +217,207 @@ export type Whitelist = {
             ]
           }
         },
+        {
+          "name": "resolverBond",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "resolver"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bondMint"
+        },
+        {
+          "name": "resolverSrcAta",
+          "writable": true
+        },
+        {
+          "name": "bondVault",
+          "docs": [
+            "ATA of the bond mint owned by the resolver bond, holding the bonded and unbonding amounts"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "resolverBond"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "bondMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
         }
       ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "cancelAuthorityTransfer",
+      "docs": [
+        "Cancels the pending authority transfer"
+      ],
+      "discriminator": [
+        94,
+        131,
+        125,
+        184,
+        183,
+        24,
+        125,
+        229
+      ],
+      "accounts": [
+        {
+          "name": "currentAuthority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
       "args": []
     },
     {
-      "name": "register",
+      "name": "deregister",
       "docs": [
-        "Registers a new user to the whitelist"
+        "Removes a user from the whitelist and the resolver registry, refunding the rent of the",
+        "access to its payer"
       ],
       "discriminator": [
-        211,
-        124,
-        67,
-        15,
-        211,
-        194,
+        161,
         178,
-        240
+        39,
+        189,
+        231,
+        224,
+        13,
+        187
       ],
       "accounts": [
         {
           "name": "authority",
-          "writable": true,
           "signer": true
         },
+        {
+          "name": "payer",
+          "docs": [
+            "Payer of the resolver access, receiving its rent back"
+          ],
+          "writable": true,
+          "relations": [
+            "resolverAccess"
+          ]
+        },
         {
           "name": "whitelistState",
+          "writable": true,
           "pda": {
             "seeds": [
               {
@@ -244,6 +475,40 @@ export type Whitelist = {
             ]
           }
         },
+        {
+          "name": "resolverPage",
+          "docs": [
+            "Page of the resolver registry holding the user"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  112,
+                  97,
+                  103,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "resolver_access.registry_page",
+                "account": "resolverAccess"
+              }
+            ]
+          }
+        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
@@ -257,24 +522,57 @@ export type Whitelist = {
       ]
     },
     {
-      "name": "setAuthority",
+      "name": "deregisterMany",
       "docs": [
-        "Sets the new whitelist authority"
+        "Removes several users in one instruction. Every user takes a group of",
+        "`DEREGISTER_ACCOUNTS_LEN` remaining accounts laid out as in `deregister`"
       ],
       "discriminator": [
-        133,
-        250,
-        37,
-        21,
-        110,
-        163,
-        26,
-        121
+        119,
+        128,
+        251,
+        130,
+        219,
+        162,
+        166,
+        217
       ],
       "accounts": [
         {
-          "name": "currentAuthority",
-          "writable": true,
+          "name": "authority",
+          "docs": [
+            "Operator deregistering the users, the same in every group of accounts"
+          ],
+          "signer": true
+        }
+      ],
+      "args": [
+        {
+          "name": "users",
+          "type": {
+            "vec": "pubkey"
+          }
+        }
+      ]
+    },
+    {
+      "name": "grantRole",
+      "docs": [
+        "Grants `role` to `user`"
+      ],
+      "discriminator": [
+        218,
+        234,
+        128,
+        15,
+        82,
+        33,
+        236,
+        253
+      ],
+      "accounts": [
+        {
+          "name": "authority",
           "signer": true
         },
         {
@@ -308,19 +606,1543 @@ export type Whitelist = {
       ],
       "args": [
         {
-          "name": "newAuthority",
+          "name": "role",
+          "type": {
+            "defined": {
+              "name": "role"
+            }
+          }
+        },
+        {
+          "name": "user",
           "type": "pubkey"
         }
       ]
-    }
-  ],
-  "accounts": [
+    },
     {
-      "name": "resolverAccess",
+      "name": "initialize",
+      "docs": [
+        "Initializes the whitelist with the authority, which is also granted the operator role"
+      ],
       "discriminator": [
-        32,
-        2,
-        74,
+        175,
+        175,
+        109,
+        31,
+        13,
+        152,
+        155,
+        237
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migrateResolverAccess",
+      "docs": [
+        "Migrates the access of `user` registered before its layout was versioned to the current",
+        "layout by an operator and adds the user to the resolver registry. The access keeps all",
+        "permissions without an expiry, `payer` pays the extra rent and gets the whole rent back on",
+        "`deregister`"
+      ],
+      "discriminator": [
+        94,
+        241,
+        44,
+        225,
+        62,
+        69,
+        95,
+        29
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "payer",
+          "docs": [
+            "Account paying the extra rent of the resolver access and the registry page"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "docs": [
+            "Resolver access in the layout before versioning"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "user"
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverPage",
+          "docs": [
+            "Current page of the resolver registry, created by the first registration to it"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  112,
+                  97,
+                  103,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "whitelist_state.current_page",
+                "account": "whitelistState"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "user",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "migrateWhitelistState",
+      "docs": [
+        "Migrates the whitelist state initialized before its layout was versioned to the current",
+        "layout, by its authority, who pays for the extra rent. The authority is granted the",
+        "operator role as on `initialize`, all other settings start at their defaults"
+      ],
+      "discriminator": [
+        118,
+        101,
+        224,
+        243,
+        203,
+        21,
+        41,
+        107
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "docs": [
+            "Whitelist state in the layout before versioning"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "proposeAuthority",
+      "docs": [
+        "Proposes `new_authority` as the next whitelist authority.",
+        "The authority is not changed until the proposed account accepts it,",
+        "a new proposal replaces the pending one"
+      ],
+      "discriminator": [
+        20,
+        148,
+        236,
+        198,
+        76,
+        119,
+        99,
+        142
+      ],
+      "accounts": [
+        {
+          "name": "currentAuthority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "newAuthority",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "register",
+      "docs": [
+        "Registers a new user to the whitelist with access from `valid_from`",
+        "until `valid_until`, both unix timestamps, to the instructions allowed by `permissions`.",
+        "The user is added to the current page of the resolver registry under `label`.",
+        "`payer` pays the rent of the access and gets it back on `deregister`"
+      ],
+      "discriminator": [
+        211,
+        124,
+        67,
+        15,
+        211,
+        194,
+        178,
+        240
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "payer",
+          "docs": [
+            "Account paying the rent of the resolver access and the registry page"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "user"
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverPage",
+          "docs": [
+            "Current page of the resolver registry, created by the first registration to it"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  112,
+                  97,
+                  103,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "whitelist_state.current_page",
+                "account": "whitelistState"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "user",
+          "type": "pubkey"
+        },
+        {
+          "name": "validFrom",
+          "type": "i64"
+        },
+        {
+          "name": "validUntil",
+          "type": "i64"
+        },
+        {
+          "name": "permissions",
+          "type": "u8"
+        },
+        {
+          "name": "label",
+          "type": "string"
+        }
+      ]
+    },
+    {
+      "name": "registerMany",
+      "docs": [
+        "Registers several users in one instruction. Every user takes a group of",
+        "`REGISTER_ACCOUNTS_LEN` remaining accounts laid out as in `register`, with the registry",
+        "page that is current once the previous users of the batch are added"
+      ],
+      "discriminator": [
+        122,
+        168,
+        150,
+        55,
+        19,
+        44,
+        73,
+        97
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "Operator registering the users, the same in every group of accounts"
+          ],
+          "signer": true
+        }
+      ],
+      "args": [
+        {
+          "name": "registrations",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "registration"
+              }
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "reinstate",
+      "docs": [
+        "Reinstates the suspended user access"
+      ],
+      "discriminator": [
+        168,
+        199,
+        183,
+        135,
+        124,
+        114,
+        176,
+        240
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "user"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "user",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "removeDelegate",
+      "docs": [
+        "Revokes `delegate` of the resolver and returns the rent to the resolver"
+      ],
+      "discriminator": [
+        94,
+        37,
+        16,
+        59,
+        7,
+        84,
+        97,
+        211
+      ],
+      "accounts": [
+        {
+          "name": "resolver",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "resolverDelegate",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "delegate"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "delegate",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "renew",
+      "docs": [
+        "Moves the end of the user access to `valid_until`, e.g. on KYC renewal"
+      ],
+      "discriminator": [
+        43,
+        239,
+        15,
+        46,
+        27,
+        7,
+        163,
+        73
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "user"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "user",
+          "type": "pubkey"
+        },
+        {
+          "name": "validUntil",
+          "type": "i64"
+        }
+      ]
+    },
+    {
+      "name": "revokeRole",
+      "docs": [
+        "Revokes `role` from `user`"
+      ],
+      "discriminator": [
+        179,
+        232,
+        2,
+        180,
+        48,
+        227,
+        82,
+        7
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "role",
+          "type": {
+            "defined": {
+              "name": "role"
+            }
+          }
+        },
+        {
+          "name": "user",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "setBondConfig",
+      "docs": [
+        "Requires resolvers to bond at least `min_bond` of `bond_mint` to fill, the native mint",
+        "for SOL bonds. A zero `min_bond` makes the bond optional. Unbonded tokens are withdrawable",
+        "`unbonding_period` seconds after `unbond`, at most `MAX_UNBONDING_PERIOD`. The mint can",
+        "not be changed once set"
+      ],
+      "discriminator": [
+        165,
+        131,
+        62,
+        211,
+        241,
+        154,
+        137,
+        122
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "bondMint",
+          "docs": [
+            "Asset resolvers bond"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "minBond",
+          "type": "u64"
+        },
+        {
+          "name": "unbondingPeriod",
+          "type": "i64"
+        }
+      ]
+    },
+    {
+      "name": "setResolverRoot",
+      "docs": [
+        "Replaces the Merkle root of resolvers allowed to fill with a proof instead of a",
+        "`ResolverAccess` account, `None` disables filling with a proof"
+      ],
+      "discriminator": [
+        135,
+        3,
+        29,
+        107,
+        236,
+        177,
+        32,
+        196
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "root",
+          "type": {
+            "option": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "slash",
+      "docs": [
+        "Slashes `amount` of the bond of `resolver` to `slash_dst_ata`. The unbonding amount is",
+        "slashed once the bond is exhausted"
+      ],
+      "discriminator": [
+        204,
+        141,
+        18,
+        161,
+        8,
+        177,
+        92,
+        142
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverBond",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "resolver"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bondMint"
+        },
+        {
+          "name": "bondVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "resolverBond"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "bondMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "slashDstAta",
+          "docs": [
+            "Account receiving the slashed tokens"
+          ],
+          "writable": true
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "resolver",
+          "type": "pubkey"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "suspend",
+      "docs": [
+        "Suspends the user access without closing its account"
+      ],
+      "discriminator": [
+        96,
+        208,
+        75,
+        109,
+        28,
+        113,
+        126,
+        130
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "user"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "user",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "unbond",
+      "docs": [
+        "Starts unbonding `amount` of the bond, `withdraw_bond` releases it once the unbonding",
+        "period has passed. Until then the amount can still be slashed"
+      ],
+      "discriminator": [
+        151,
+        129,
+        36,
+        46,
+        102,
+        195,
+        111,
+        122
+      ],
+      "accounts": [
+        {
+          "name": "resolver",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverBond",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "resolver"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "updatePermissions",
+      "docs": [
+        "Replaces the permissions of the user"
+      ],
+      "discriminator": [
+        190,
+        35,
+        201,
+        204,
+        193,
+        197,
+        109,
+        69
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverAccess",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  97,
+                  99,
+                  99,
+                  101,
+                  115,
+                  115
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "user"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "user",
+          "type": "pubkey"
+        },
+        {
+          "name": "permissions",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "withdrawBond",
+      "docs": [
+        "Withdraws the whole unbonding amount to the resolver once the unbonding period has passed"
+      ],
+      "discriminator": [
+        222,
+        199,
+        141,
+        31,
+        188,
+        93,
+        155,
+        40
+      ],
+      "accounts": [
+        {
+          "name": "resolver",
+          "signer": true
+        },
+        {
+          "name": "whitelistState",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  119,
+                  104,
+                  105,
+                  116,
+                  101,
+                  108,
+                  105,
+                  115,
+                  116,
+                  95,
+                  115,
+                  116,
+                  97,
+                  116,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "resolverBond",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  111,
+                  108,
+                  118,
+                  101,
+                  114,
+                  95,
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "resolver"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bondMint"
+        },
+        {
+          "name": "bondVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "resolverBond"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "bondMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "resolverDstAta",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": []
+    }
+  ],
+  "accounts": [
+    {
+      "name": "resolverAccess",
+      "discriminator": [
+        32,
+        2,
+        74,
         248,
         174,
         108,
@@ -329,35 +2151,885 @@ export type Whitelist = {
       ]
     },
     {
-      "name": "whitelistState",
-      "discriminator": [
-        246,
-        118,
-        44,
-        60,
-        71,
-        37,
-        201,
-        55
-      ]
-    }
-  ],
-  "errors": [
+      "name": "resolverBond",
+      "discriminator": [
+        63,
+        15,
+        161,
+        173,
+        95,
+        253,
+        189,
+        66
+      ]
+    },
+    {
+      "name": "resolverDelegate",
+      "discriminator": [
+        95,
+        158,
+        209,
+        143,
+        40,
+        82,
+        118,
+        131
+      ]
+    },
+    {
+      "name": "resolverPage",
+      "discriminator": [
+        109,
+        66,
+        90,
+        72,
+        182,
+        209,
+        43,
+        157
+      ]
+    },
+    {
+      "name": "whitelistState",
+      "discriminator": [
+        246,
+        118,
+        44,
+        60,
+        71,
+        37,
+        201,
+        55
+      ]
+    }
+  ],
+  "events": [
+    {
+      "name": "authorityTransferCancelled",
+      "discriminator": [
+        31,
+        228,
+        187,
+        148,
+        20,
+        99,
+        237,
+        48
+      ]
+    },
+    {
+      "name": "authorityTransferProposed",
+      "discriminator": [
+        103,
+        244,
+        27,
+        116,
+        177,
+        4,
+        100,
+        119
+      ]
+    },
+    {
+      "name": "authorityTransferred",
+      "discriminator": [
+        245,
+        109,
+        179,
+        54,
+        135,
+        92,
+        22,
+        64
+      ]
+    },
+    {
+      "name": "bondConfigUpdated",
+      "discriminator": [
+        34,
+        200,
+        124,
+        24,
+        147,
+        133,
+        175,
+        79
+      ]
+    },
+    {
+      "name": "bondWithdrawn",
+      "discriminator": [
+        111,
+        192,
+        154,
+        231,
+        58,
+        115,
+        130,
+        103
+      ]
+    },
+    {
+      "name": "bonded",
+      "discriminator": [
+        72,
+        106,
+        31,
+        121,
+        93,
+        125,
+        84,
+        0
+      ]
+    },
+    {
+      "name": "delegateAdded",
+      "discriminator": [
+        96,
+        159,
+        58,
+        144,
+        26,
+        171,
+        141,
+        70
+      ]
+    },
+    {
+      "name": "delegateRemoved",
+      "discriminator": [
+        91,
+        243,
+        235,
+        175,
+        109,
+        235,
+        217,
+        84
+      ]
+    },
+    {
+      "name": "permissionsUpdated",
+      "discriminator": [
+        55,
+        108,
+        73,
+        30,
+        34,
+        215,
+        188,
+        1
+      ]
+    },
+    {
+      "name": "resolverReinstated",
+      "discriminator": [
+        22,
+        171,
+        38,
+        132,
+        133,
+        16,
+        88,
+        200
+      ]
+    },
+    {
+      "name": "resolverRenewed",
+      "discriminator": [
+        148,
+        201,
+        83,
+        222,
+        130,
+        237,
+        7,
+        103
+      ]
+    },
+    {
+      "name": "resolverRootUpdated",
+      "discriminator": [
+        2,
+        103,
+        175,
+        146,
+        105,
+        88,
+        238,
+        229
+      ]
+    },
+    {
+      "name": "resolverSuspended",
+      "discriminator": [
+        30,
+        129,
+        55,
+        134,
+        200,
+        173,
+        160,
+        196
+      ]
+    },
+    {
+      "name": "roleGranted",
+      "discriminator": [
+        220,
+        183,
+        89,
+        228,
+        143,
+        63,
+        246,
+        58
+      ]
+    },
+    {
+      "name": "roleRevoked",
+      "discriminator": [
+        167,
+        183,
+        52,
+        229,
+        126,
+        206,
+        62,
+        61
+      ]
+    },
+    {
+      "name": "slashed",
+      "discriminator": [
+        98,
+        59,
+        249,
+        154,
+        233,
+        53,
+        98,
+        194
+      ]
+    },
+    {
+      "name": "unbondingStarted",
+      "discriminator": [
+        131,
+        124,
+        120,
+        42,
+        115,
+        228,
+        150,
+        57
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "unauthorized",
+      "msg": "unauthorized"
+    },
+    {
+      "code": 6001,
+      "name": "noPendingAuthority",
+      "msg": "No pending authority transfer"
+    },
+    {
+      "code": 6002,
+      "name": "roleAlreadyGranted",
+      "msg": "Role already granted"
+    },
+    {
+      "code": 6003,
+      "name": "roleNotGranted",
+      "msg": "Role not granted"
+    },
+    {
+      "code": 6004,
+      "name": "tooManyRoleMembers",
+      "msg": "Too many role members"
+    },
+    {
+      "code": 6005,
+      "name": "invalidAccessPeriod",
+      "msg": "Access must be valid from before it is valid until"
+    },
+    {
+      "code": 6006,
+      "name": "resolverAlreadySuspended",
+      "msg": "Resolver is already suspended"
+    },
+    {
+      "code": 6007,
+      "name": "resolverNotSuspended",
+      "msg": "Resolver is not suspended"
+    },
+    {
+      "code": 6008,
+      "name": "invalidPermissions",
+      "msg": "Unknown permission bits"
+    },
+    {
+      "code": 6009,
+      "name": "labelTooLong",
+      "msg": "Label is too long"
+    },
+    {
+      "code": 6010,
+      "name": "invalidPayer",
+      "msg": "Payer does not match the resolver access"
+    },
+    {
+      "code": 6011,
+      "name": "inconsistentBatchAccounts",
+      "msg": "Inconsistent batch accounts"
+    },
+    {
+      "code": 6012,
+      "name": "invalidUnbondingPeriod",
+      "msg": "Unbonding period must be between zero and a year"
+    },
+    {
+      "code": 6013,
+      "name": "bondMintChanged",
+      "msg": "Bond mint can not be changed"
+    },
+    {
+      "code": 6014,
+      "name": "invalidBondMint",
+      "msg": "Mint is not the bond mint"
+    },
+    {
+      "code": 6015,
+      "name": "invalidBondAmount",
+      "msg": "Bond amount must be positive"
+    },
+    {
+      "code": 6016,
+      "name": "insufficientBond",
+      "msg": "Not enough tokens in the bond"
+    },
+    {
+      "code": 6017,
+      "name": "unbondingNotElapsed",
+      "msg": "Unbonding period has not passed"
+    },
+    {
+      "code": 6018,
+      "name": "alreadyMigrated",
+      "msg": "Account is already migrated"
+    }
+  ],
+  "types": [
+    {
+      "name": "authorityTransferCancelled",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "pendingAuthority",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "authorityTransferProposed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "pendingAuthority",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "authorityTransferred",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "previousAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "newAuthority",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bondConfigUpdated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "bondMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "minBond",
+            "type": "u64"
+          },
+          {
+            "name": "unbondingPeriod",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bondWithdrawn",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bonded",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "delegateAdded",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "delegate",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
     {
-      "code": 6000,
-      "name": "unauthorized",
-      "msg": "unauthorized"
-    }
-  ],
-  "types": [
+      "name": "delegateRemoved",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "delegate",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "permissionsUpdated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "permissions",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "registration",
+      "docs": [
+        "Arguments of `register` for one user of `register_many`"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "validFrom",
+            "type": "i64"
+          },
+          {
+            "name": "validUntil",
+            "type": "i64"
+          },
+          {
+            "name": "permissions",
+            "type": "u8"
+          },
+          {
+            "name": "label",
+            "type": "string"
+          }
+        ]
+      }
+    },
     {
       "name": "resolverAccess",
       "type": {
         "kind": "struct",
         "fields": [
+          {
+            "name": "version",
+            "docs": [
+              "Version of the layout, `migration::RESOLVER_ACCESS_VERSION`"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "payer",
+            "docs": [
+              "Account that paid the rent of the access, refunded on deregistration"
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "registeredAt",
+            "docs": [
+              "Unix timestamp of the registration"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "generation",
+            "docs": [
+              "Number of the registration among all registrations, see `WhitelistState::registrations`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "label",
+            "type": "string"
+          },
+          {
+            "name": "registryPage",
+            "docs": [
+              "Page of the resolver registry holding the resolver"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "validFrom",
+            "docs": [
+              "Unix timestamp from which the access is valid"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "validUntil",
+            "docs": [
+              "Unix timestamp at which the access lapses"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "status",
+            "type": {
+              "defined": {
+                "name": "resolverStatus"
+              }
+            }
+          },
+          {
+            "name": "permissions",
+            "docs": [
+              "Bitmask of the `permissions` bits"
+            ],
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverBond",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "amount",
+            "docs": [
+              "Bonded amount, counted towards `WhitelistState::min_bond`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "unbondingAmount",
+            "docs": [
+              "Amount leaving the bond, withdrawable from `withdrawable_at`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "withdrawableAt",
+            "docs": [
+              "Unix timestamp from which `unbonding_amount` can be withdrawn"
+            ],
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverDelegate",
+      "docs": [
+        "Key allowed to sign for `resolver`, derived from the delegate key"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
           {
             "name": "bump",
             "type": "u8"
+          },
+          {
+            "name": "generation",
+            "docs": [
+              "`ResolverAccess::generation` of the resolver when the delegate was added. The delegate",
+              "is not valid for a later registration of the resolver"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverPage",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolvers",
+            "type": {
+              "vec": "pubkey"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverReinstated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverRenewed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "expiresAt",
+            "docs": [
+              "Unix timestamp at which the renewed access lapses"
+            ],
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverRootUpdated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "root",
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "active"
+          },
+          {
+            "name": "suspended"
+          }
+        ]
+      }
+    },
+    {
+      "name": "resolverSuspended",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "role",
+      "docs": [
+        "Roles granted by the whitelist authority, which acts as the admin managing them"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "operator"
+          },
+          {
+            "name": "guardian"
+          }
+        ]
+      }
+    },
+    {
+      "name": "roleGranted",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "role",
+            "type": {
+              "defined": {
+                "name": "role"
+              }
+            }
+          },
+          {
+            "name": "user",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "roleRevoked",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "role",
+            "type": {
+              "defined": {
+                "name": "role"
+              }
+            }
+          },
+          {
+            "name": "user",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "slashed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "unbondingStarted",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "resolver",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "withdrawableAt",
+            "type": "i64"
           }
         ]
       }
@@ -367,9 +3039,95 @@ export type Whitelist = {
       "type": {
         "kind": "struct",
         "fields": [
+          {
+            "name": "version",
+            "docs": [
+              "Version of the layout, `migration::WHITELIST_STATE_VERSION`"
+            ],
+            "type": "u8"
+          },
           {
             "name": "authority",
             "type": "pubkey"
+          },
+          {
+            "name": "pendingAuthority",
+            "docs": [
+              "Authority proposed by `propose_authority`, waiting to accept the transfer"
+            ],
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "operators",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "guardians",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "resolverRoot",
+            "docs": [
+              "Merkle root of the `merkle` tree of resolvers allowed to fill with a proof"
+            ],
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          },
+          {
+            "name": "resolverCount",
+            "docs": [
+              "Number of registered resolvers"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "currentPage",
+            "docs": [
+              "Page of the resolver registry new resolvers are added to"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "registrations",
+            "docs": [
+              "Number of registrations so far, including deregistered ones"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "bondMint",
+            "docs": [
+              "Asset of the resolver bonds, set by `set_bond_config`"
+            ],
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "minBond",
+            "docs": [
+              "Bonded amount required to fill, zero if no bond is required"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "unbondingPeriod",
+            "docs": [
+              "Seconds between `unbond` and the withdrawal of the unbonded amount"
+            ],
+            "type": "i64"
           }
         ]
       }
@@ -0,0 +1,835 @@
+//! Fixtures for the integration tests of the Fusion Swap and Whitelist programs on an in-process
+//! SVM. The built `fusion_swap.so` and `whitelist.so` are tested whenever `SBF_OUT_DIR` points to
+//! their directory (e.g. `target/deploy`), the programs are only run natively without it.
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    AccountDeserialize, AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    token::spl_token::native_mint,
+    token_2022::spl_token_2022::{
+        self,
+        extension::StateWithExtensions,
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use fusion_swap::auction::{AuctionData, PiecewiseLinearCurve};
+use fusion_swap_client::{
+    fusion_swap::{self as fusion_swap_ix, Order},
+    pda, permissions, whitelist as whitelist_ix, FeeConfig, OrderConfig, Registration,
+    ResolverAccess, WhitelistState,
+};
+use solana_program_test::{
+    processor, BanksClient, BanksClientError, BanksTransactionResultWithMetadata, ProgramTest,
+    ProgramTestBanksClientExt, ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::instruction as system_instruction;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+pub use anchor_spl::{token::ID as TOKEN_PROGRAM_ID, token_2022::ID as TOKEN_2022_PROGRAM_ID};
+pub use native_mint::ID as NATIVE_MINT;
+
+/// Access period of the resolvers registered by the tests, valid at any cluster time
+pub const ACCESS_VALID_FROM: i64 = 0;
+pub const ACCESS_VALID_UNTIL: i64 = i64::MAX;
+
+/// Cluster time at the start of every test
+pub const START_TIME: i64 = 1_750_000_000;
+pub const DEFAULT_EXPIRATION_TIME: u32 = START_TIME as u32 + 86_400;
+pub const DEFAULT_SRC_AMOUNT: u64 = 100;
+pub const DEFAULT_DST_AMOUNT: u64 = 30;
+/// Maximum compute unit limit of a transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// Size of the token account without extensions
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Registration of `user` with access to all instructions for the whole test, without a label
+pub fn registration(user: Pubkey) -> Registration {
+    Registration {
+        user,
+        valid_from: ACCESS_VALID_FROM,
+        valid_until: ACCESS_VALID_UNTIL,
+        permissions: permissions::ALL,
+        label: String::new(),
+    }
+}
+
+/// Directory of the shared objects of the programs, looked up in the same order as `ProgramTest`
+pub fn sbf_out_dir() -> Option<PathBuf> {
+    std::env::var_os("BPF_OUT_DIR")
+        .or_else(|| std::env::var_os("SBF_OUT_DIR"))
+        .map(PathBuf::from)
+}
+
+/// Whether the shared objects of the programs are tested instead of the native processors
+pub fn sbf() -> bool {
+    sbf_out_dir().is_some()
+}
+
+/// Anchor entrypoints require the accounts to live as long as their data, which the native
+/// processor does not guarantee. The accounts are leaked, which is fine for a test process
+fn fusion_swap_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    fusion_swap::entry(
+        program_id,
+        Box::leak(accounts.to_vec().into_boxed_slice()),
+        data,
+    )
+}
+
+fn whitelist_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    whitelist::entry(
+        program_id,
+        Box::leak(accounts.to_vec().into_boxed_slice()),
+        data,
+    )
+}
+
+/// Test cluster with three SPL Token mints and the native mint, four funded users holding ATAs
+/// of all of them and the whitelist initialized by the payer with `bob` registered as a resolver.
+/// `alice` holds the first token, `bob` and `charlie` hold the second one
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub alice: Keypair,
+    pub bob: Keypair,
+    pub charlie: Keypair,
+    pub dave: Keypair,
+    pub tokens: Vec<Pubkey>,
+    token_programs: HashMap<Pubkey, Pubkey>,
+    signatures: RefCell<HashSet<Signature>>,
+    order_id: Cell<u32>,
+}
+
+/// Order created on the cluster
+pub struct Escrow {
+    pub order: Order,
+    pub escrow: Pubkey,
+    pub ata: Pubkey,
+}
+
+impl TestEnv {
+    pub async fn new() -> Self {
+        let mut program_test = ProgramTest::default();
+        // Overrides the verbose default filter of `ProgramTest`, program logs are printed with
+        // `RUST_LOG=solana_runtime::message_processor::stable_log=debug`
+        solana_logger::setup_with_default("error");
+        // The native processors only stand in for the shared objects when they are not built
+        program_test.prefer_bpf(sbf());
+        if let Some(dir) = sbf_out_dir() {
+            for program in ["fusion_swap", "whitelist"] {
+                let path = dir.join(format!("{program}.so"));
+                assert!(
+                    path.is_file(),
+                    "{} does not exist, build the programs with `anchor build`",
+                    path.display()
+                );
+            }
+        }
+        program_test.add_program(
+            "fusion_swap",
+            fusion_swap::ID,
+            processor!(fusion_swap_entry),
+        );
+        program_test.add_program("whitelist", whitelist::ID, processor!(whitelist_entry));
+
+        let users: Vec<Keypair> = (0..4).map(|_| Keypair::new()).collect();
+        for user in users.iter() {
+            program_test.add_account(
+                user.pubkey(),
+                Account {
+                    lamports: LAMPORTS_PER_SOL,
+                    ..Account::default()
+                },
+            );
+        }
+
+        let context = program_test.start_with_context().await;
+        let [alice, bob, charlie, dave] = users.try_into().unwrap();
+        let mut env = Self {
+            signatures: RefCell::default(),
+            context,
+            alice,
+            bob,
+            charlie,
+            dave,
+            tokens: vec![],
+            token_programs: HashMap::from([(NATIVE_MINT, TOKEN_PROGRAM_ID)]),
+            order_id: Cell::new(0),
+        };
+        env.set_time(START_TIME);
+
+        for _ in 0..3 {
+            let mint = env.create_mint(TOKEN_PROGRAM_ID).await;
+            env.tokens.push(mint);
+        }
+        env.tokens.push(NATIVE_MINT);
+
+        let users = [
+            env.alice.pubkey(),
+            env.bob.pubkey(),
+            env.charlie.pubkey(),
+            env.dave.pubkey(),
+        ];
+        env.create_atas(&users, &env.tokens.clone()).await;
+
+        env.initialize_whitelist().await;
+        env.register(&env.bob.pubkey()).await;
+
+        env.mint_to(&env.tokens[0], &env.alice.pubkey(), 100_000_000)
+            .await;
+        env.mint_to(&env.tokens[1], &env.bob.pubkey(), 100_000_000)
+            .await;
+        env.mint_to(&env.tokens[1], &env.charlie.pubkey(), 100_000_000)
+            .await;
+        env
+    }
+
+    pub fn payer(&self) -> &Keypair {
+        &self.context.payer
+    }
+
+    fn banks(&self) -> BanksClient {
+        self.context.banks_client.clone()
+    }
+
+    /// Sends the instructions paid by the payer. A transaction identical to one already sent
+    /// waits for a new blockhash, so repeated instructions are not rejected as duplicates
+    pub async fn send(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        self.banks()
+            .process_transaction(self.transaction(instructions, signers).await)
+            .await
+    }
+
+    /// Sends the instructions and returns the program logs of the transaction. Native processors
+    /// print `sol_log_data` to stdout, so the logs hold events only when testing the shared objects
+    pub async fn send_with_logs(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<String>, BanksClientError> {
+        let result = self.send_with_metadata(instructions, signers).await?;
+        Ok(result
+            .metadata
+            .map(|metadata| metadata.log_messages)
+            .unwrap_or_default())
+    }
+
+    /// Simulates the instructions and returns the return data of the transaction
+    pub async fn simulate(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<u8>, BanksClientError> {
+        let result = self
+            .banks()
+            .simulate_transaction(self.transaction(instructions, signers).await)
+            .await?;
+        if let Some(Err(err)) = result.result {
+            return Err(BanksClientError::TransactionError(err));
+        }
+        Ok(result
+            .simulation_details
+            .and_then(|details| details.return_data)
+            .map(|return_data| return_data.data)
+            .unwrap_or_default())
+    }
+
+    /// Sends the instructions under the maximum compute unit limit and returns the compute units
+    /// consumed by the transaction, including the limit instruction. Only the shared objects are
+    /// metered, native processors are not
+    pub async fn send_with_compute_units(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<u64, BanksClientError> {
+        let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            MAX_COMPUTE_UNIT_LIMIT,
+        )];
+        all_instructions.extend_from_slice(instructions);
+        let result = self.send_with_metadata(&all_instructions, signers).await?;
+        Ok(result
+            .metadata
+            .map(|metadata| metadata.compute_units_consumed)
+            .unwrap_or_default())
+    }
+
+    /// Transactions with metadata are executed right away rather than queued after the ones sent
+    /// before, so they are retried while the accounts are still locked by a previous one
+    async fn send_with_metadata(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<BanksTransactionResultWithMetadata, BanksClientError> {
+        loop {
+            let result = self
+                .banks()
+                .process_transaction_with_metadata(self.transaction(instructions, signers).await)
+                .await?;
+            if result.result != Err(TransactionError::AccountInUse) {
+                result.result.clone()?;
+                return Ok(result);
+            }
+        }
+    }
+
+    async fn transaction(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let mut all_signers = vec![self.payer()];
+        all_signers.extend(signers);
+        let sign = |blockhash| {
+            Transaction::new_signed_with_payer(
+                instructions,
+                Some(&self.payer().pubkey()),
+                &all_signers,
+                blockhash,
+            )
+        };
+
+        let mut transaction = sign(self.banks().get_latest_blockhash().await.unwrap());
+        if self
+            .signatures
+            .borrow()
+            .contains(&transaction.signatures[0])
+        {
+            let blockhash = self
+                .banks()
+                .get_new_latest_blockhash(&transaction.message.recent_blockhash)
+                .await
+                .unwrap();
+            transaction = sign(blockhash);
+        }
+        self.signatures
+            .borrow_mut()
+            .insert(transaction.signatures[0]);
+        transaction
+    }
+
+    /// Moves the cluster clock to `unix_timestamp`
+    pub fn set_time(&self, unix_timestamp: i64) {
+        let clock = Clock {
+            unix_timestamp,
+            ..Clock::default()
+        };
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn create_mint(&mut self, token_program: Pubkey) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.banks().get_rent().await.unwrap();
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &self.payer().pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &token_program,
+                ),
+                spl_token_2022::instruction::initialize_mint2(
+                    &token_program,
+                    &mint.pubkey(),
+                    &self.payer().pubkey(),
+                    None,
+                    6,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        self.token_programs.insert(mint.pubkey(), token_program);
+        mint.pubkey()
+    }
+
+    pub fn token_program(&self, mint: &Pubkey) -> Pubkey {
+        self.token_programs[mint]
+    }
+
+    /// ATA of `owner` for `mint` under the token program of the mint
+    pub fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        pda::ata(owner, mint, &self.token_program(mint))
+    }
+
+    pub async fn create_atas(&self, owners: &[Pubkey], mints: &[Pubkey]) {
+        let instructions: Vec<Instruction> = owners
+            .iter()
+            .flat_map(|owner| {
+                mints.iter().map(|mint| {
+                    create_associated_token_account_idempotent(
+                        &self.payer().pubkey(),
+                        owner,
+                        mint,
+                        &self.token_program(mint),
+                    )
+                })
+            })
+            .collect();
+        for chunk in instructions.chunks(4) {
+            self.send(chunk, &[]).await.unwrap();
+        }
+    }
+
+    /// Mints `amount` tokens to the ATA of `owner`
+    pub async fn mint_to(&self, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let token_program = self.token_program(mint);
+        self.send(
+            &[spl_token_2022::instruction::mint_to(
+                &token_program,
+                mint,
+                &self.ata(owner, mint),
+                &self.payer().pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Wraps `amount` lamports of `owner` into its wrapped SOL ATA
+    pub async fn wrap_native(&self, owner: &Keypair, amount: u64) {
+        let ata = self.ata(&owner.pubkey(), &NATIVE_MINT);
+        self.send(
+            &[
+                system_instruction::transfer(&owner.pubkey(), &ata, amount),
+                spl_token_2022::instruction::sync_native(&TOKEN_PROGRAM_ID, &ata).unwrap(),
+            ],
+            &[owner],
+        )
+        .await
+        .unwrap();
+    }
+
+    pub async fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.banks().get_account(*address).await.unwrap()
+    }
+
+    pub async fn lamports(&self, address: &Pubkey) -> u64 {
+        self.account(address)
+            .await
+            .map(|account| account.lamports)
+            .unwrap_or_default()
+    }
+
+    /// Balance of the token account, panics if the account does not exist
+    pub async fn balance(&self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .account(token_account)
+            .await
+            .unwrap_or_else(|| panic!("token account {token_account} does not exist"));
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    pub async fn balances(&self, token_accounts: &[Pubkey]) -> Vec<u64> {
+        let mut balances = vec![];
+        for token_account in token_accounts {
+            balances.push(self.balance(token_account).await);
+        }
+        balances
+    }
+
+    pub async fn minimum_balance(&self, len: usize) -> u64 {
+        self.banks().get_rent().await.unwrap().minimum_balance(len)
+    }
+
+    /// Replaces the account at `address` with a rent exempt one of `owner` holding `data`, e.g.
+    /// a program account in a layout that is no longer created
+    pub async fn set_account(&mut self, address: &Pubkey, owner: &Pubkey, data: Vec<u8>) {
+        let account = Account {
+            lamports: self.minimum_balance(data.len()).await,
+            data,
+            owner: *owner,
+            ..Account::default()
+        };
+        self.context.set_account(address, &account.into());
+    }
+
+    pub async fn token_account_rent(&self) -> u64 {
+        self.banks()
+            .get_rent()
+            .await
+            .unwrap()
+            .minimum_balance(TOKEN_ACCOUNT_LEN)
+    }
+
+    pub async fn initialize_whitelist(&self) {
+        self.send(&[whitelist_ix::initialize(self.payer().pubkey())], &[])
+            .await
+            .unwrap();
+    }
+
+    /// Page of the resolver registry the next resolver is added to
+    pub async fn current_page(&self) -> u32 {
+        let account = self.account(&pda::whitelist_state()).await.unwrap();
+        WhitelistState::try_deserialize(&mut &account.data[..])
+            .unwrap()
+            .current_page
+    }
+
+    /// Page of the resolver registry holding `user`, the first page if it is not registered
+    pub async fn registry_page(&self, user: &Pubkey) -> u32 {
+        self.account(&pda::resolver_access(user))
+            .await
+            .map_or(0, |account| {
+                ResolverAccess::try_deserialize(&mut &account.data[..])
+                    .unwrap()
+                    .registry_page
+            })
+    }
+
+    /// Registers `user` as a resolver by the payer
+    pub async fn register(&self, user: &Pubkey) {
+        self.send(
+            &[whitelist_ix::register(
+                self.payer().pubkey(),
+                self.payer().pubkey(),
+                registration(*user),
+                self.current_page().await,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Renews the access of `user` until `valid_until` by the payer
+    pub async fn renew(&self, user: &Pubkey, valid_until: i64) {
+        self.send(
+            &[whitelist_ix::renew(
+                self.payer().pubkey(),
+                *user,
+                valid_until,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Replaces the permissions of `user` by the payer
+    pub async fn update_permissions(&self, user: &Pubkey, permissions: u8) {
+        self.send(
+            &[whitelist_ix::update_permissions(
+                self.payer().pubkey(),
+                *user,
+                permissions,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Authorizes `delegate` to sign for `bob`
+    pub async fn add_delegate(&self, delegate: &Keypair) {
+        self.send(
+            &[whitelist_ix::add_delegate(
+                self.bob.pubkey(),
+                delegate.pubkey(),
+            )],
+            &[&self.bob, delegate],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Replaces the resolver root of the whitelist by the payer
+    pub async fn set_resolver_root(&self, root: Option<[u8; 32]>) {
+        self.send(
+            &[whitelist_ix::set_resolver_root(self.payer().pubkey(), root)],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Requires a bond of `min_bond` of the second token to fill, set by the payer
+    pub async fn set_bond_config(&self, min_bond: u64, unbonding_period: i64) {
+        self.send(
+            &[whitelist_ix::set_bond_config(
+                self.payer().pubkey(),
+                self.tokens[1],
+                min_bond,
+                unbonding_period,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Bonds `amount` of the second token by `resolver`
+    pub async fn bond(&self, resolver: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+        self.send(
+            &[whitelist_ix::bond(
+                resolver.pubkey(),
+                self.tokens[1],
+                self.token_program(&self.tokens[1]),
+                amount,
+            )],
+            &[resolver],
+        )
+        .await
+    }
+
+    /// Suspends `user` by the payer
+    pub async fn suspend(&self, user: &Pubkey) {
+        self.send(&[whitelist_ix::suspend(self.payer().pubkey(), *user)], &[])
+            .await
+            .unwrap();
+    }
+
+    /// Deregisters `user` by the payer
+    pub async fn deregister(&self, user: &Pubkey) {
+        self.send(
+            &[whitelist_ix::deregister(
+                self.payer().pubkey(),
+                self.payer().pubkey(),
+                *user,
+                self.registry_page(user).await,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Order of `alice` selling `DEFAULT_SRC_AMOUNT` of the first token for `DEFAULT_DST_AMOUNT`
+    /// of the second one without fees and rate bumps. Every order gets a new id
+    pub fn order(&self) -> Order {
+        let id = self.order_id.get();
+        self.order_id.set(id + 1);
+        Order {
+            config: OrderConfig {
+                id,
+                src_amount: DEFAULT_SRC_AMOUNT,
+                min_dst_amount: DEFAULT_DST_AMOUNT,
+                estimated_dst_amount: DEFAULT_DST_AMOUNT,
+                expiration_time: DEFAULT_EXPIRATION_TIME,
+                src_asset_is_native: false,
+                dst_asset_is_native: false,
+                fee: FeeConfig {
+                    protocol_fee: 0,
+                    integrator_fee: 0,
+                    integrator_fee_shares: vec![],
+                    surplus_percentage: 0,
+                    integrator_surplus_percentage: 0,
+                    resolver_surplus_percentage: 0,
+                    max_cancellation_premium: 0,
+                    max_src_cancellation_premium: 0,
+                },
+                dutch_auction_data: AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                    start_time: 0,
+                    duration: 32_000,
+                    initial_rate_bump: 0,
+                    points_and_time_deltas: vec![],
+                }),
+                cancellation_auction_duration: 0,
+            },
+            maker: self.alice.pubkey(),
+            maker_receiver: self.alice.pubkey(),
+            src_mint: self.tokens[0],
+            dst_mint: self.tokens[1],
+            protocol_dst_acc: None,
+            integrator_dst_acc: None,
+            src_token_program: TOKEN_PROGRAM_ID,
+            dst_token_program: TOKEN_PROGRAM_ID,
+        }
+    }
+
+    /// Creates the escrow of the order by `alice`. Wrapped SOL for non-native orders is
+    /// prepared for `alice` as the maker and `bob` as the taker
+    pub async fn create_escrow(&self, order: Order) -> Result<Escrow, BanksClientError> {
+        if order.src_mint == NATIVE_MINT && !order.config.src_asset_is_native {
+            self.wrap_native(&self.alice, order.config.src_amount).await;
+        }
+        if order.dst_mint == NATIVE_MINT && !order.config.dst_asset_is_native {
+            self.wrap_native(&self.bob, order.config.min_dst_amount)
+                .await;
+        }
+
+        self.send(
+            &[fusion_swap_ix::create(order.clone()).unwrap()],
+            &[&self.alice],
+        )
+        .await?;
+        Ok(Escrow {
+            escrow: order.escrow().unwrap(),
+            ata: order.escrow_src_ata().unwrap(),
+            order,
+        })
+    }
+
+    /// Accounts of `fill` by `bob` using his ATAs
+    pub fn fill_accounts(&self, order: &Order) -> fusion_swap::accounts::Fill {
+        self.fill_accounts_for(order, self.bob.pubkey())
+    }
+
+    /// Accounts of `fill` by `taker` using its ATAs
+    pub fn fill_accounts_for(&self, order: &Order, taker: Pubkey) -> fusion_swap::accounts::Fill {
+        let dst_is_native = order.config.dst_asset_is_native;
+        fusion_swap::accounts::Fill {
+            taker,
+            resolver_delegate: None,
+            resolver_access: Some(pda::resolver_access(&taker)),
+            whitelist_state: Some(pda::whitelist_state()),
+            resolver_bond: pda::resolver_bond(&taker),
+            maker: order.maker,
+            maker_receiver: order.maker_receiver,
+            src_mint: order.src_mint,
+            dst_mint: order.dst_mint,
+            escrow: order.escrow().unwrap(),
+            escrow_src_ata: order.escrow_src_ata().unwrap(),
+            taker_src_ata: pda::ata(&taker, &order.src_mint, &order.src_token_program),
+            src_token_program: order.src_token_program,
+            dst_token_program: order.dst_token_program,
+            system_program: anchor_lang::system_program::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            maker_dst_ata: (!dst_is_native).then(|| {
+                pda::ata(
+                    &order.maker_receiver,
+                    &order.dst_mint,
+                    &order.dst_token_program,
+                )
+            }),
+            taker_dst_ata: (!dst_is_native)
+                .then(|| pda::ata(&taker, &order.dst_mint, &order.dst_token_program)),
+            protocol_dst_acc: order.protocol_dst_acc,
+            integrator_dst_acc: order.integrator_dst_acc,
+        }
+    }
+
+    /// Accounts of `cancel_by_resolver` by `bob` taking the premium in lamports
+    pub fn cancel_by_resolver_accounts(
+        &self,
+        order: &Order,
+    ) -> fusion_swap::accounts::CancelByResolver {
+        let resolver = self.bob.pubkey();
+        fusion_swap::accounts::CancelByResolver {
+            resolver,
+            resolver_delegate: None,
+            resolver_access: pda::resolver_access(&resolver),
+            maker: order.maker,
+            maker_receiver: order.maker_receiver,
+            src_mint: order.src_mint,
+            dst_mint: order.dst_mint,
+            escrow: order.escrow().unwrap(),
+            escrow_src_ata: order.escrow_src_ata().unwrap(),
+            maker_src_ata: (!order.config.src_asset_is_native)
+                .then(|| pda::ata(&order.maker, &order.src_mint, &order.src_token_program)),
+            resolver_src_ata: None,
+            src_token_program: order.src_token_program,
+            system_program: anchor_lang::system_program::ID,
+            protocol_dst_acc: order.protocol_dst_acc,
+            integrator_dst_acc: order.integrator_dst_acc,
+        }
+    }
+
+    /// Fills `amount` of the order by `bob`
+    pub async fn fill(&self, order: &Order, amount: u64) -> Result<(), BanksClientError> {
+        self.send(
+            &[fusion_swap_ix::fill(order.clone(), self.bob.pubkey(), amount).unwrap()],
+            &[&self.bob],
+        )
+        .await
+    }
+
+    /// Cancels the order by `alice`
+    pub async fn cancel(&self, order: &Order) -> Result<(), BanksClientError> {
+        self.send(&[fusion_swap_ix::cancel(order).unwrap()], &[&self.alice])
+            .await
+    }
+
+    /// Cancels the expired order by `bob`
+    pub async fn cancel_by_resolver(
+        &self,
+        order: &Order,
+        reward_limit: u64,
+    ) -> Result<(), BanksClientError> {
+        self.send(
+            &[
+                fusion_swap_ix::cancel_by_resolver(order.clone(), self.bob.pubkey(), reward_limit)
+                    .unwrap(),
+            ],
+            &[&self.bob],
+        )
+        .await
+    }
+}
+
+/// Builds an instruction of the Fusion Swap program from the accounts and the data
+pub fn fusion_swap_instruction(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: fusion_swap::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Asserts that the transaction failed with the custom program error, e.g. an Anchor or a
+/// program error code
+pub fn assert_error<T>(result: Result<T, BanksClientError>, error: impl Into<u32>) {
+    assert_instruction_error(result, InstructionError::Custom(error.into()));
+}
+
+pub fn assert_instruction_error<T>(result: Result<T, BanksClientError>, error: InstructionError) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(_, actual)))
+        | Err(BanksClientError::SimulationError {
+            err: TransactionError::InstructionError(_, actual),
+            ..
+        }) => assert_eq!(actual, error),
+        Err(other) => panic!("expected {error:?}, got {other:?}"),
+        Ok(_) => panic!("expected {error:?}, the transaction succeeded"),
+    }
+}
+
+/// Events of type `T` emitted in the program logs
+pub fn events<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<T> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter_map(|data| {
+            data.strip_prefix(T::DISCRIMINATOR)
+                .and_then(|event| T::try_from_slice(event).ok())
+        })
+        .collect()
+}
+
+/// Balance changes between two snapshots of the same accounts
+pub fn deltas(before: &[u64], after: &[u64]) -> Vec<i64> {
+    before
+        .iter()
+        .zip(after)
+        .map(|(before, after)| *after as i64 - *before as i64)
+        .collect()
+}
@@ -0,0 +1,382 @@
+use anchor_lang::AccountDeserialize;
+use fusion_integration_tests::{
+    assert_error, assert_instruction_error, deltas, events, sbf, TestEnv, DEFAULT_SRC_AMOUNT,
+    START_TIME,
+};
+use fusion_swap::error::FusionError;
+use fusion_swap_client::{fusion_swap as fusion_swap_ix, pda, whitelist as whitelist_ix};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use whitelist::{
+    bond::{ResolverBond, MAX_UNBONDING_PERIOD},
+    error::WhitelistError,
+    BondConfigUpdated, BondWithdrawn, Bonded, Slashed, UnbondingStarted,
+};
+
+const MIN_BOND: u64 = 1_000;
+const UNBONDING_PERIOD: i64 = 86_400;
+
+async fn resolver_bond(env: &TestEnv, resolver: &Pubkey) -> ResolverBond {
+    let account = env.account(&pda::resolver_bond(resolver)).await.unwrap();
+    ResolverBond::try_deserialize(&mut &account.data[..]).unwrap()
+}
+
+fn bond_vault(env: &TestEnv, resolver: &Pubkey) -> Pubkey {
+    let mint = env.tokens[1];
+    pda::bond_vault(resolver, &mint, &env.token_program(&mint))
+}
+
+async fn unbond(env: &TestEnv, resolver: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+    env.send(
+        &[whitelist_ix::unbond(resolver.pubkey(), amount)],
+        &[resolver],
+    )
+    .await
+}
+
+async fn withdraw_bond(env: &TestEnv, resolver: &Keypair) -> Result<(), BanksClientError> {
+    let mint = env.tokens[1];
+    env.send(
+        &[whitelist_ix::withdraw_bond(
+            resolver.pubkey(),
+            mint,
+            env.token_program(&mint),
+        )],
+        &[resolver],
+    )
+    .await
+}
+
+/// Slashes `amount` of the bond of `bob` to the ATA of `charlie`
+async fn slash(env: &TestEnv, authority: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+    let mint = env.tokens[1];
+    env.send(
+        &[whitelist_ix::slash(
+            authority.pubkey(),
+            env.bob.pubkey(),
+            mint,
+            env.token_program(&mint),
+            env.ata(&env.charlie.pubkey(), &mint),
+            amount,
+        )],
+        &[authority],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn bonds_and_withdraws_after_unbonding_period() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    let accounts = [
+        env.ata(&env.bob.pubkey(), &env.tokens[1]),
+        bond_vault(&env, &env.bob.pubkey()),
+    ];
+
+    let before = env.balances(&accounts[..1]).await;
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+    let bond = resolver_bond(&env, &env.bob.pubkey()).await;
+    assert_eq!(bond.resolver, env.bob.pubkey());
+    assert_eq!(bond.amount, MIN_BOND);
+    assert_eq!(env.balance(&accounts[1]).await, MIN_BOND);
+    assert_eq!(
+        deltas(&before, &env.balances(&accounts[..1]).await),
+        [-(MIN_BOND as i64)]
+    );
+
+    unbond(&env, &env.bob, 400).await.unwrap();
+    let bond = resolver_bond(&env, &env.bob.pubkey()).await;
+    assert_eq!((bond.amount, bond.unbonding_amount), (600, 400));
+    assert_eq!(bond.withdrawable_at, START_TIME + UNBONDING_PERIOD);
+
+    let result = withdraw_bond(&env, &env.bob).await;
+    assert_error(result, WhitelistError::UnbondingNotElapsed);
+
+    env.set_time(START_TIME + UNBONDING_PERIOD);
+    let before = env.balances(&accounts).await;
+    withdraw_bond(&env, &env.bob).await.unwrap();
+    assert_eq!(deltas(&before, &env.balances(&accounts).await), [400, -400]);
+    assert_eq!(
+        resolver_bond(&env, &env.bob.pubkey())
+            .await
+            .unbonding_amount,
+        0
+    );
+
+    let result = withdraw_bond(&env, &env.bob).await;
+    assert_error(result, WhitelistError::InvalidBondAmount);
+}
+
+#[tokio::test]
+async fn cannot_unbond_more_than_bond() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+
+    let result = unbond(&env, &env.bob, MIN_BOND + 1).await;
+    assert_error(result, WhitelistError::InsufficientBond);
+}
+
+#[tokio::test]
+async fn cannot_bond_other_mint() {
+    let env = TestEnv::new().await;
+    let mint = env.tokens[0];
+    let bond = whitelist_ix::bond(env.bob.pubkey(), mint, env.token_program(&mint), MIN_BOND);
+
+    // No bond mint is set yet
+    let result = env.send(&[bond.clone()], &[&env.bob]).await;
+    assert_error(result, WhitelistError::InvalidBondMint);
+
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    let result = env.send(&[bond], &[&env.bob]).await;
+    assert_error(result, WhitelistError::InvalidBondMint);
+}
+
+#[tokio::test]
+async fn updates_bond_config() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+
+    let logs = env
+        .send_with_logs(
+            &[whitelist_ix::set_bond_config(
+                env.payer().pubkey(),
+                env.tokens[1],
+                0,
+                0,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    if sbf() {
+        let events = events::<BondConfigUpdated>(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].bond_mint, env.tokens[1]);
+        assert_eq!(events[0].min_bond, 0);
+    }
+
+    let result = env
+        .send(
+            &[whitelist_ix::set_bond_config(
+                env.payer().pubkey(),
+                env.tokens[0],
+                MIN_BOND,
+                UNBONDING_PERIOD,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::BondMintChanged);
+
+    let result = env
+        .send(
+            &[whitelist_ix::set_bond_config(
+                env.payer().pubkey(),
+                env.tokens[1],
+                MIN_BOND,
+                -1,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidUnbondingPeriod);
+
+    let result = env
+        .send(
+            &[whitelist_ix::set_bond_config(
+                env.payer().pubkey(),
+                env.tokens[1],
+                MIN_BOND,
+                MAX_UNBONDING_PERIOD + 1,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidUnbondingPeriod);
+
+    env.set_bond_config(MIN_BOND, MAX_UNBONDING_PERIOD).await;
+}
+
+#[tokio::test]
+async fn unbond_fails_when_withdrawal_time_overflows() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, MAX_UNBONDING_PERIOD).await;
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+
+    env.set_time(i64::MAX - MAX_UNBONDING_PERIOD + 1);
+    let result = unbond(&env, &env.bob, 400).await;
+    assert_instruction_error(result, InstructionError::ArithmeticOverflow);
+
+    env.set_time(i64::MAX - MAX_UNBONDING_PERIOD);
+    unbond(&env, &env.bob, 400).await.unwrap();
+    assert_eq!(
+        resolver_bond(&env, &env.bob.pubkey()).await.withdrawable_at,
+        i64::MAX
+    );
+}
+
+#[tokio::test]
+async fn only_authority_can_set_bond_config() {
+    let env = TestEnv::new().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::set_bond_config(
+                env.charlie.pubkey(),
+                env.tokens[1],
+                MIN_BOND,
+                UNBONDING_PERIOD,
+            )],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn fill_requires_min_bond() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::InsufficientResolverBond);
+
+    env.bond(&env.bob, MIN_BOND - 1).await.unwrap();
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::InsufficientResolverBond);
+
+    env.bond(&env.bob, 1).await.unwrap();
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+}
+
+#[tokio::test]
+async fn unbonding_amount_does_not_count_towards_min_bond() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+    unbond(&env, &env.bob, 1).await.unwrap();
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::InsufficientResolverBond);
+}
+
+#[tokio::test]
+async fn delegate_fills_with_bond_of_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    env.add_delegate(&env.charlie).await;
+    let delegated_fill = fusion_swap_ix::delegated_fill(
+        escrow.order,
+        env.charlie.pubkey(),
+        env.bob.pubkey(),
+        DEFAULT_SRC_AMOUNT,
+    )
+    .unwrap();
+
+    // The bond of the delegate itself does not count
+    env.bond(&env.charlie, MIN_BOND).await.unwrap();
+    let result = env.send(&[delegated_fill.clone()], &[&env.charlie]).await;
+    assert_error(result, FusionError::InsufficientResolverBond);
+
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+    env.send(&[delegated_fill], &[&env.charlie]).await.unwrap();
+}
+
+#[tokio::test]
+async fn slashes_bond_then_unbonding_amount() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+    unbond(&env, &env.bob, 400).await.unwrap();
+    let accounts = [
+        bond_vault(&env, &env.bob.pubkey()),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+    ];
+
+    let before = env.balances(&accounts).await;
+    slash(&env, env.payer(), 700).await.unwrap();
+    assert_eq!(deltas(&before, &env.balances(&accounts).await), [-700, 700]);
+    let bond = resolver_bond(&env, &env.bob.pubkey()).await;
+    assert_eq!((bond.amount, bond.unbonding_amount), (0, 300));
+
+    let result = slash(&env, env.payer(), 301).await;
+    assert_error(result, WhitelistError::InsufficientBond);
+}
+
+#[tokio::test]
+async fn only_authority_can_slash() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    env.bond(&env.bob, MIN_BOND).await.unwrap();
+
+    let result = slash(&env, &env.charlie, MIN_BOND).await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn emits_bond_events() {
+    let env = TestEnv::new().await;
+    env.set_bond_config(MIN_BOND, UNBONDING_PERIOD).await;
+    let mint = env.tokens[1];
+    let token_program = env.token_program(&mint);
+
+    let logs = env
+        .send_with_logs(
+            &[
+                whitelist_ix::bond(env.bob.pubkey(), mint, token_program, MIN_BOND),
+                whitelist_ix::unbond(env.bob.pubkey(), 400),
+                whitelist_ix::slash(
+                    env.payer().pubkey(),
+                    env.bob.pubkey(),
+                    mint,
+                    token_program,
+                    env.ata(&env.charlie.pubkey(), &mint),
+                    100,
+                ),
+            ],
+            &[&env.bob],
+        )
+        .await
+        .unwrap();
+    env.set_time(START_TIME + UNBONDING_PERIOD);
+    let withdraw_logs = env
+        .send_with_logs(
+            &[whitelist_ix::withdraw_bond(
+                env.bob.pubkey(),
+                mint,
+                token_program,
+            )],
+            &[&env.bob],
+        )
+        .await
+        .unwrap();
+
+    if sbf() {
+        let bonded = events::<Bonded>(&logs);
+        assert_eq!(bonded.len(), 1);
+        assert_eq!(bonded[0].resolver, env.bob.pubkey());
+        assert_eq!(bonded[0].amount, MIN_BOND);
+
+        let unbonding = events::<UnbondingStarted>(&logs);
+        assert_eq!(unbonding.len(), 1);
+        assert_eq!(unbonding[0].amount, 400);
+        assert_eq!(unbonding[0].withdrawable_at, START_TIME + UNBONDING_PERIOD);
+
+        let slashed = events::<Slashed>(&logs);
+        assert_eq!(slashed.len(), 1);
+        assert_eq!(slashed[0].resolver, env.bob.pubkey());
+        assert_eq!(slashed[0].amount, 100);
+
+        let withdrawn = events::<BondWithdrawn>(&withdraw_logs);
+        assert_eq!(withdrawn.len(), 1);
+        assert_eq!(withdrawn[0].amount, 400);
+    }
+}
@@ -0,0 +1,553 @@
+use anchor_lang::{error::ErrorCode, prelude::AccountMeta, prelude::Pubkey};
+use fusion_integration_tests::{
+    assert_error, deltas, fusion_swap_instruction, Escrow, TestEnv, DEFAULT_EXPIRATION_TIME,
+    NATIVE_MINT, START_TIME,
+};
+use fusion_swap::{error::FusionError, instruction};
+use fusion_swap_client::{fusion_swap as fusion_swap_ix, fusion_swap::Order, permissions};
+use solana_sdk::signature::Signer;
+
+const SRC_AMOUNT: u64 = 1_000_000;
+/// 50% of the src amount
+const MAX_CANCELLATION_PREMIUM: u64 = SRC_AMOUNT / 2;
+const REWARD_LIMIT: u64 = MAX_CANCELLATION_PREMIUM;
+const AUCTION_DURATION: u32 = 32_000;
+
+/// Order of `SRC_AMOUNT` with the cancellation auction and no premiums
+fn cancellable_order(env: &TestEnv) -> Order {
+    let mut order = env.order();
+    order.config.src_amount = SRC_AMOUNT;
+    order.config.cancellation_auction_duration = AUCTION_DURATION;
+    order
+}
+
+/// Order paying up to `MAX_CANCELLATION_PREMIUM` lamports to the resolver
+fn premium_order(env: &TestEnv) -> Order {
+    let mut order = cancellable_order(env);
+    order.config.fee.max_cancellation_premium = MAX_CANCELLATION_PREMIUM;
+    order
+}
+
+/// Order of native SOL paying up to `MAX_CANCELLATION_PREMIUM` lamports to the resolver
+fn native_order(env: &TestEnv) -> Order {
+    let mut order = premium_order(env);
+    order.src_mint = NATIVE_MINT;
+    order.config.src_asset_is_native = true;
+    order.config.src_amount = 10_000;
+    order
+}
+
+/// Order paying up to `MAX_CANCELLATION_PREMIUM` src tokens to the resolver
+fn src_premium_order(env: &TestEnv) -> Order {
+    let mut order = cancellable_order(env);
+    order.config.fee.max_src_cancellation_premium = MAX_CANCELLATION_PREMIUM;
+    order
+}
+
+/// Maker's and resolver's ATAs of the src token
+fn src_atas(env: &TestEnv) -> [Pubkey; 2] {
+    [
+        env.ata(&env.alice.pubkey(), &env.tokens[0]),
+        env.ata(&env.bob.pubkey(), &env.tokens[0]),
+    ]
+}
+
+/// Balance changes of the maker and the resolver: lamports followed by src tokens
+async fn cancel_deltas(env: &TestEnv, cancel: impl std::future::Future<Output = ()>) -> Vec<i64> {
+    let snapshot = || async {
+        let mut balances = vec![
+            env.lamports(&env.alice.pubkey()).await,
+            env.lamports(&env.bob.pubkey()).await,
+        ];
+        balances.extend(env.balances(&src_atas(env)).await);
+        balances
+    };
+    let before = snapshot().await;
+    cancel.await;
+    deltas(&before, &snapshot().await)
+}
+
+async fn cancel_by_resolver(env: &TestEnv, escrow: &Escrow, reward_limit: u64) {
+    env.cancel_by_resolver(&escrow.order, reward_limit)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn cancels_for_free_at_auction_start() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    let rent = env.token_account_rent().await as i64;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    assert_eq!(
+        cancel_deltas(&env, cancel_by_resolver(&env, &escrow, REWARD_LIMIT)).await,
+        [rent, 0, SRC_AMOUNT as i64, 0]
+    );
+}
+
+#[tokio::test]
+async fn cannot_cancel_spl_order_without_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    let accounts = fusion_swap::accounts::CancelByResolver {
+        maker_src_ata: None,
+        ..env.cancel_by_resolver_accounts(&escrow.order)
+    };
+    let result = env
+        .send(
+            &[fusion_swap_instruction(
+                accounts,
+                instruction::CancelByResolver {
+                    order: escrow.order.config.clone(),
+                    reward_limit: REWARD_LIMIT,
+                },
+            )],
+            &[&env.bob],
+        )
+        .await;
+    // A missing maker src ATA marks the order as native, which contradicts the order config
+    assert_error(result, FusionError::InconsistentNativeSrcTrait);
+}
+
+#[tokio::test]
+async fn cancels_at_different_points_of_auction() {
+    let env = TestEnv::new().await;
+    let rent = env.token_account_rent().await;
+
+    for percentage in [10, 25, 50, 100] {
+        let time_elapsed = AUCTION_DURATION as u64 * percentage / 100;
+        // 1%, 2.5% and 7.5% of the token account rent
+        for premium_bps in [100, 250, 750] {
+            let max_cancellation_premium = rent * premium_bps / 10_000;
+            env.set_time(START_TIME);
+            let mut order = cancellable_order(&env);
+            order.config.fee.max_cancellation_premium = max_cancellation_premium;
+            let escrow = env.create_escrow(order).await.unwrap();
+
+            env.set_time(DEFAULT_EXPIRATION_TIME as i64 + time_elapsed as i64);
+            let premium =
+                (max_cancellation_premium * time_elapsed / AUCTION_DURATION as u64) as i64;
+            assert_eq!(
+                cancel_deltas(&env, cancel_by_resolver(&env, &escrow, REWARD_LIMIT)).await,
+                [rent as i64 - premium, premium, SRC_AMOUNT as i64, 0]
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn cancels_after_auction() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    let rent = env.token_account_rent().await as i64;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    let premium = MAX_CANCELLATION_PREMIUM as i64;
+    assert_eq!(
+        cancel_deltas(&env, cancel_by_resolver(&env, &escrow, REWARD_LIMIT)).await,
+        [rent - premium, premium, SRC_AMOUNT as i64, 0]
+    );
+}
+
+#[tokio::test]
+async fn takes_reward_below_auction_premium() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    let rent = env.token_account_rent().await as i64;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    assert_eq!(
+        cancel_deltas(&env, cancel_by_resolver(&env, &escrow, 1)).await,
+        [rent - 1, 1, SRC_AMOUNT as i64, 0]
+    );
+}
+
+#[tokio::test]
+async fn returns_native_src_assets_to_maker() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(native_order(&env)).await.unwrap();
+    let rent = env.token_account_rent().await as i64;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    assert_eq!(
+        cancel_deltas(&env, cancel_by_resolver(&env, &escrow, 0)).await,
+        [escrow.order.config.src_amount as i64 + rent, 0, 0, 0]
+    );
+}
+
+#[tokio::test]
+async fn cancels_native_order_without_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(native_order(&env)).await.unwrap();
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    let accounts = env.cancel_by_resolver_accounts(&escrow.order);
+    assert!(accounts.maker_src_ata.is_none());
+    env.send(
+        &[fusion_swap_instruction(
+            accounts,
+            instruction::CancelByResolver {
+                order: escrow.order.config.clone(),
+                reward_limit: 0,
+            },
+        )],
+        &[&env.bob],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn cannot_cancel_native_order_with_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(native_order(&env)).await.unwrap();
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    let accounts = fusion_swap::accounts::CancelByResolver {
+        maker_src_ata: Some(env.ata(&env.alice.pubkey(), &NATIVE_MINT)),
+        ..env.cancel_by_resolver_accounts(&escrow.order)
+    };
+    let result = env
+        .send(
+            &[fusion_swap_instruction(
+                accounts,
+                instruction::CancelByResolver {
+                    order: escrow.order.config.clone(),
+                    reward_limit: 0,
+                },
+            )],
+            &[&env.bob],
+        )
+        .await;
+    assert_error(result, FusionError::InconsistentNativeSrcTrait);
+}
+
+#[tokio::test]
+async fn cannot_cancel_before_expiration() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+
+    let result = env.cancel_by_resolver(&escrow.order, REWARD_LIMIT).await;
+    assert_error(result, FusionError::OrderNotExpired);
+}
+
+#[tokio::test]
+async fn cannot_cancel_by_non_whitelisted_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::cancel_by_resolver(
+                escrow.order.clone(),
+                env.charlie.pubkey(),
+                REWARD_LIMIT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+}
+
+#[tokio::test]
+async fn cannot_cancel_by_resolver_with_lapsed_access() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.renew(&env.bob.pubkey(), DEFAULT_EXPIRATION_TIME as i64)
+        .await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    let result = env.cancel_by_resolver(&escrow.order, REWARD_LIMIT).await;
+    assert_error(result, FusionError::ResolverAccessNotValid);
+}
+
+#[tokio::test]
+async fn cannot_cancel_batch_by_resolver_with_lapsed_access() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.renew(&env.bob.pubkey(), DEFAULT_EXPIRATION_TIME as i64)
+        .await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::cancel_by_resolver_batch(
+                vec![escrow.order],
+                env.bob.pubkey(),
+                REWARD_LIMIT,
+            )
+            .unwrap()],
+            &[&env.bob],
+        )
+        .await;
+    assert_error(result, FusionError::ResolverAccessNotValid);
+}
+
+#[tokio::test]
+async fn cannot_cancel_by_suspended_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.suspend(&env.bob.pubkey()).await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    let result = env.cancel_by_resolver(&escrow.order, REWARD_LIMIT).await;
+    assert_error(result, FusionError::ResolverSuspended);
+}
+
+#[tokio::test]
+async fn cancels_by_resolver_with_only_cancel_permission() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.update_permissions(&env.bob.pubkey(), permissions::CANCEL)
+        .await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    cancel_by_resolver(&env, &escrow, REWARD_LIMIT).await;
+    assert!(env.account(&escrow.ata).await.is_none());
+}
+
+#[tokio::test]
+async fn cannot_cancel_by_resolver_without_cancel_permission() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.update_permissions(&env.bob.pubkey(), permissions::FILL)
+        .await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    let result = env.cancel_by_resolver(&escrow.order, REWARD_LIMIT).await;
+    assert_error(result, FusionError::MissingResolverPermission);
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::cancel_by_resolver_batch(
+                vec![escrow.order],
+                env.bob.pubkey(),
+                REWARD_LIMIT,
+            )
+            .unwrap()],
+            &[&env.bob],
+        )
+        .await;
+    assert_error(result, FusionError::MissingResolverPermission);
+}
+
+#[tokio::test]
+async fn cancels_by_delegate_of_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    let before = env.lamports(&env.charlie.pubkey()).await;
+    env.send(
+        &[fusion_swap_ix::delegated_cancel_by_resolver(
+            escrow.order,
+            env.charlie.pubkey(),
+            env.bob.pubkey(),
+            REWARD_LIMIT,
+        )
+        .unwrap()],
+        &[&env.charlie],
+    )
+    .await
+    .unwrap();
+    assert!(env.account(&escrow.ata).await.is_none());
+    // The delegate takes the premium
+    assert_eq!(
+        env.lamports(&env.charlie.pubkey()).await - before,
+        MAX_CANCELLATION_PREMIUM
+    );
+}
+
+#[tokio::test]
+async fn does_not_cancel_by_delegate_of_earlier_registration() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.deregister(&env.bob.pubkey()).await;
+    env.register(&env.bob.pubkey()).await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::delegated_cancel_by_resolver(
+                escrow.order,
+                env.charlie.pubkey(),
+                env.bob.pubkey(),
+                REWARD_LIMIT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, FusionError::InvalidResolverDelegate);
+}
+
+#[tokio::test]
+async fn cancels_batch_by_delegate_of_resolver() {
+    let env = TestEnv::new().await;
+    let escrows = [
+        env.create_escrow(premium_order(&env)).await.unwrap(),
+        env.create_escrow(premium_order(&env)).await.unwrap(),
+    ];
+    env.add_delegate(&env.charlie).await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + 1);
+
+    env.send(
+        &[fusion_swap_ix::delegated_cancel_by_resolver_batch(
+            escrows.iter().map(|escrow| escrow.order.clone()).collect(),
+            env.charlie.pubkey(),
+            env.bob.pubkey(),
+            REWARD_LIMIT,
+        )
+        .unwrap()],
+        &[&env.charlie],
+    )
+    .await
+    .unwrap();
+    for escrow in escrows {
+        assert!(env.account(&escrow.ata).await.is_none());
+    }
+}
+
+#[tokio::test]
+async fn cannot_create_escrow_with_premium_above_rent() {
+    let env = TestEnv::new().await;
+    let mut order = cancellable_order(&env);
+    order.config.fee.max_cancellation_premium = env.token_account_rent().await + 1;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidCancellationFee);
+}
+
+#[tokio::test]
+async fn pays_premium_in_src_tokens() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(src_premium_order(&env)).await.unwrap();
+    let rent = env.token_account_rent().await as i64;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    let premium = MAX_CANCELLATION_PREMIUM as i64;
+    assert_eq!(
+        cancel_deltas(&env, cancel_by_resolver(&env, &escrow, REWARD_LIMIT)).await,
+        [rent, 0, SRC_AMOUNT as i64 - premium, premium]
+    );
+}
+
+#[tokio::test]
+async fn cannot_take_src_premium_without_resolver_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(src_premium_order(&env)).await.unwrap();
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    let result = env
+        .send(
+            &[fusion_swap_instruction(
+                env.cancel_by_resolver_accounts(&escrow.order),
+                instruction::CancelByResolver {
+                    order: escrow.order.config.clone(),
+                    reward_limit: REWARD_LIMIT,
+                },
+            )],
+            &[&env.bob],
+        )
+        .await;
+    assert_error(result, FusionError::MissingResolverSrcAta);
+}
+
+#[tokio::test]
+async fn cannot_create_escrow_with_lamport_and_src_premiums() {
+    let env = TestEnv::new().await;
+    let mut order = cancellable_order(&env);
+    order.config.fee.max_cancellation_premium = 1;
+    order.config.fee.max_src_cancellation_premium = 1;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidCancellationFee);
+}
+
+#[tokio::test]
+async fn cannot_create_escrow_with_src_premium_above_src_amount() {
+    let env = TestEnv::new().await;
+    let mut order = cancellable_order(&env);
+    order.config.fee.max_src_cancellation_premium = SRC_AMOUNT + 1;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidCancellationFee);
+}
+
+#[tokio::test]
+async fn cannot_create_native_escrow_with_src_premium() {
+    let env = TestEnv::new().await;
+    let mut order = native_order(&env);
+    order.config.src_amount = SRC_AMOUNT;
+    order.config.fee.max_cancellation_premium = 0;
+    order.config.fee.max_src_cancellation_premium = 1;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidCancellationFee);
+}
+
+#[tokio::test]
+async fn cancels_batch_skipping_closed_orders() {
+    let env = TestEnv::new().await;
+    let mut orders = vec![];
+    for _ in 0..3 {
+        orders.push(env.create_escrow(premium_order(&env)).await.unwrap().order);
+    }
+    let rent = env.token_account_rent().await as i64;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    // The first order is already cancelled and must be skipped by the batch
+    env.cancel_by_resolver(&orders[0], REWARD_LIMIT)
+        .await
+        .unwrap();
+
+    let batch = async {
+        env.send(
+            &[
+                fusion_swap_ix::cancel_by_resolver_batch(orders, env.bob.pubkey(), REWARD_LIMIT)
+                    .unwrap(),
+            ],
+            &[&env.bob],
+        )
+        .await
+        .unwrap()
+    };
+    let premium = MAX_CANCELLATION_PREMIUM as i64;
+    assert_eq!(
+        cancel_deltas(&env, batch).await,
+        [2 * (rent - premium), 2 * premium, 2 * SRC_AMOUNT as i64, 0]
+    );
+}
+
+#[tokio::test]
+async fn cannot_cancel_batch_with_inconsistent_accounts() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(premium_order(&env)).await.unwrap();
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64 + AUCTION_DURATION as i64 + 1);
+
+    let mut instruction = fusion_swap_instruction(
+        fusion_swap::accounts::CancelByResolverBatch {
+            resolver: env.bob.pubkey(),
+            resolver_delegate: None,
+            resolver_access: fusion_swap_client::pda::resolver_access(&env.bob.pubkey()),
+        },
+        instruction::CancelByResolverBatch {
+            orders: vec![escrow.order.config.clone()],
+            reward_limit: REWARD_LIMIT,
+        },
+    );
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(escrow.escrow, false),
+        AccountMeta::new(escrow.ata, false),
+    ]);
+
+    let result = env.send(&[instruction], &[&env.bob]).await;
+    assert_error(result, FusionError::InconsistentBatchAccounts);
+}
@@ -0,0 +1,449 @@
+//! Compute units consumed by every instruction variant, checked against the baseline in
+//! `compute-units.txt`. Only the shared objects are metered, so the benchmarks are ignored by a
+//! plain `cargo test` and run by `yarn test:compute-units`, which builds the programs and sets
+//! `SBF_OUT_DIR`. Every measured variant must be in the baseline and every baseline entry must be
+//! measured. Set `UPDATE_COMPUTE_UNITS=1` to write the measured units to the baseline instead.
+
+use fusion_integration_tests::{
+    registration, sbf, TestEnv, ACCESS_VALID_UNTIL, DEFAULT_SRC_AMOUNT, NATIVE_MINT, START_TIME,
+    TOKEN_PROGRAM_ID,
+};
+use fusion_swap::{
+    auction::{
+        AuctionData, ExponentialDecayCurve, LinearWithPlateauCurve, PiecewiseLinearCurve,
+        PointAndTimeDelta, StepCurve, MAX_AUCTION_POINTS,
+    },
+    IntegratorFeeShare, MAX_INTEGRATOR_FEE_SHARES,
+};
+use fusion_swap_client::{
+    fusion_swap::{self as fusion_swap_ix, Order},
+    merkle::{self, ResolverProof},
+    pda, whitelist as whitelist_ix,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{cell::RefCell, collections::BTreeMap, fmt::Write};
+
+const BASELINE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/compute-units.txt");
+/// Compute units may grow by this many percent of the baseline before the check fails
+const THRESHOLD_PERCENT: u64 = 5;
+
+/// Duration of the auction curves, the curve points span its first half
+const AUCTION_DURATION: u32 = 2 * MAX_AUCTION_POINTS as u32 * 100;
+
+struct Bench {
+    env: TestEnv,
+    units: RefCell<BTreeMap<String, u64>>,
+}
+
+impl Bench {
+    async fn measure(&self, name: &str, instruction: Instruction, signers: &[&Keypair]) {
+        let units = self
+            .env
+            .send_with_compute_units(&[instruction], signers)
+            .await
+            .unwrap_or_else(|error| panic!("{name} failed: {error}"));
+        self.units.borrow_mut().insert(name.to_string(), units);
+    }
+
+    /// Measures `create` of the order by `alice`
+    async fn create(&self, name: &str, order: Order) {
+        if order.src_mint == NATIVE_MINT && !order.config.src_asset_is_native {
+            self.env
+                .wrap_native(&self.env.alice, order.config.src_amount)
+                .await;
+        }
+        self.measure(
+            name,
+            fusion_swap_ix::create(order).unwrap(),
+            &[&self.env.alice],
+        )
+        .await;
+    }
+
+    /// Creates the escrow of the order and measures a `fill` of `amount` by `bob`
+    async fn fill(&self, name: &str, order: Order, amount: u64) {
+        let escrow = self.env.create_escrow(order).await.unwrap();
+        self.measure(
+            name,
+            fusion_swap_ix::fill(escrow.order, self.env.bob.pubkey(), amount).unwrap(),
+            &[&self.env.bob],
+        )
+        .await;
+    }
+
+    /// Creates the escrow of the order and measures its `cancel` by `alice`
+    async fn cancel(&self, name: &str, order: Order) {
+        let escrow = self.env.create_escrow(order).await.unwrap();
+        self.measure(
+            name,
+            fusion_swap_ix::cancel(&escrow.order).unwrap(),
+            &[&self.env.alice],
+        )
+        .await;
+    }
+
+    /// Creates the escrows of orders expiring after a second and measures their cancellation by
+    /// `bob`, in a batch if there are several of them
+    async fn cancel_by_resolver(&self, name: &str, orders: Vec<Order>) {
+        let mut escrowed = vec![];
+        for mut order in orders {
+            order.config.expiration_time = START_TIME as u32 + 1;
+            escrowed.push(self.env.create_escrow(order).await.unwrap().order);
+        }
+        let resolver = self.env.bob.pubkey();
+        let instruction = if escrowed.len() == 1 {
+            fusion_swap_ix::cancel_by_resolver(escrowed.remove(0), resolver, u64::MAX)
+        } else {
+            fusion_swap_ix::cancel_by_resolver_batch(escrowed, resolver, u64::MAX)
+        }
+        .unwrap();
+
+        self.env.set_time(START_TIME + 1);
+        self.measure(name, instruction, &[&self.env.bob]).await;
+        self.env.set_time(START_TIME);
+    }
+}
+
+fn with_fees(mut order: Order, env: &TestEnv) -> Order {
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    let dave_dst_ata = env.ata(&env.dave.pubkey(), &env.tokens[1]);
+    order.config.fee.protocol_fee = 1_000;
+    order.protocol_dst_acc = Some(dave_dst_ata);
+    order.config.fee.integrator_fee = 1_000;
+    order.integrator_dst_acc = Some(charlie_dst_ata);
+    order
+}
+
+fn with_fee_shares(mut order: Order, env: &TestEnv) -> Order {
+    let dave_dst_ata = env.ata(&env.dave.pubkey(), &env.tokens[1]);
+    order.config.fee.integrator_fee_shares = (0..MAX_INTEGRATOR_FEE_SHARES)
+        .map(|_| IntegratorFeeShare {
+            recipient: dave_dst_ata,
+            fee: 1_000,
+        })
+        .collect();
+    order
+}
+
+fn with_native_dst(mut order: Order) -> Order {
+    order.dst_mint = NATIVE_MINT;
+    order.config.dst_asset_is_native = true;
+    order
+}
+
+fn with_native_src(mut order: Order) -> Order {
+    order.src_mint = NATIVE_MINT;
+    order.config.src_asset_is_native = true;
+    order
+}
+
+/// Maker receiver without an ATA of the dst mint, created by `fill`
+fn with_new_maker_receiver(mut order: Order) -> Order {
+    order.maker_receiver = Pubkey::new_unique();
+    order
+}
+
+/// Curve points decreasing the rate bump by 100 every 100 seconds
+fn points(len: usize) -> Vec<PointAndTimeDelta> {
+    (0..len)
+        .map(|point| PointAndTimeDelta {
+            rate_bump: (MAX_AUCTION_POINTS - point) as u16 * 100,
+            time_delta: 100,
+        })
+        .collect()
+}
+
+fn with_auction(mut order: Order, dutch_auction_data: AuctionData) -> Order {
+    order.config.dutch_auction_data = dutch_auction_data;
+    order
+}
+
+fn piecewise_linear(len: usize) -> AuctionData {
+    AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+        start_time: START_TIME as u32,
+        duration: AUCTION_DURATION,
+        initial_rate_bump: MAX_AUCTION_POINTS as u16 * 100,
+        points_and_time_deltas: points(len),
+    })
+}
+
+fn step(len: usize) -> AuctionData {
+    AuctionData::Step(StepCurve {
+        start_time: START_TIME as u32,
+        duration: AUCTION_DURATION,
+        initial_rate_bump: MAX_AUCTION_POINTS as u16 * 100,
+        points_and_time_deltas: points(len),
+    })
+}
+
+/// Regressions beyond the threshold, variants missing from the baseline and baseline entries
+/// that are no longer measured
+fn compare(units: &BTreeMap<String, u64>, baseline: &BTreeMap<String, u64>) -> Vec<String> {
+    let mut failures = vec![];
+    for (name, &units) in units.iter() {
+        match baseline.get(name) {
+            Some(&baseline) if units * 100 > baseline * (100 + THRESHOLD_PERCENT) => {
+                failures.push(format!(
+                    "{name}: {units} CU, baseline {baseline} CU, more than {THRESHOLD_PERCENT}% over"
+                ));
+            }
+            Some(&baseline) => println!("{name}: {units} CU, baseline {baseline} CU"),
+            None => failures.push(format!("{name}: {units} CU, not in the baseline")),
+        }
+    }
+    for name in baseline.keys().filter(|name| !units.contains_key(*name)) {
+        failures.push(format!("{name}: in the baseline, but not measured"));
+    }
+    failures
+}
+
+fn read_baseline() -> BTreeMap<String, u64> {
+    std::fs::read_to_string(BASELINE)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (name, units) = line
+                .split_once(" = ")
+                .unwrap_or_else(|| panic!("malformed baseline line: {line}"));
+            (name.to_string(), units.parse().unwrap())
+        })
+        .collect()
+}
+
+fn write_baseline(units: &BTreeMap<String, u64>) {
+    let mut baseline = String::from(
+        "# Compute units of the Fusion Swap and Whitelist instructions, written by\n\
+         # `UPDATE_COMPUTE_UNITS=1 yarn test:compute-units`\n",
+    );
+    for (name, units) in units.iter() {
+        writeln!(baseline, "{name} = {units}").unwrap();
+    }
+    std::fs::write(BASELINE, baseline).unwrap();
+}
+
+#[tokio::test]
+#[ignore = "meters the shared objects, run by `yarn test:compute-units`"]
+async fn compute_units_do_not_regress() {
+    assert!(
+        sbf(),
+        "native processors are not metered, set SBF_OUT_DIR to the directory of the built programs"
+    );
+
+    let bench = Bench {
+        env: TestEnv::new().await,
+        units: RefCell::default(),
+    };
+    let env = &bench.env;
+
+    let variants = || {
+        let mut wrapped_native_src = env.order();
+        wrapped_native_src.src_mint = NATIVE_MINT;
+        let mut wrapped_native_dst = env.order();
+        wrapped_native_dst.dst_mint = NATIVE_MINT;
+        [
+            ("spl", env.order()),
+            ("wrapped_native_src", wrapped_native_src),
+            ("wrapped_native_dst", wrapped_native_dst),
+            ("native_src", with_native_src(env.order())),
+            ("native_dst", with_native_dst(env.order())),
+            ("with_fees", with_fees(env.order(), env)),
+            (
+                "with_fee_shares",
+                with_fee_shares(with_fees(env.order(), env), env),
+            ),
+            (
+                "native_dst_with_fees",
+                with_fees(with_native_dst(env.order()), env),
+            ),
+            (
+                "maker_dst_ata_creation",
+                with_new_maker_receiver(env.order()),
+            ),
+            (
+                "maker_dst_ata_creation_with_fees",
+                with_fees(with_new_maker_receiver(env.order()), env),
+            ),
+        ]
+    };
+    let curves = || {
+        [0, 1, MAX_AUCTION_POINTS / 2, MAX_AUCTION_POINTS]
+            .map(|len| {
+                (
+                    format!("piecewise_linear_{len}_points"),
+                    piecewise_linear(len),
+                )
+            })
+            .into_iter()
+            .chain([
+                (
+                    format!("step_{MAX_AUCTION_POINTS}_points"),
+                    step(MAX_AUCTION_POINTS),
+                ),
+                (
+                    "exponential_decay".to_string(),
+                    AuctionData::ExponentialDecay(ExponentialDecayCurve {
+                        start_time: START_TIME as u32,
+                        duration: AUCTION_DURATION,
+                        initial_rate_bump: 10_000,
+                        half_life: 300,
+                    }),
+                ),
+                (
+                    "linear_with_plateau".to_string(),
+                    AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                        start_time: START_TIME as u32,
+                        duration: AUCTION_DURATION,
+                        initial_rate_bump: 10_000,
+                        plateau_duration: AUCTION_DURATION / 4,
+                    }),
+                ),
+            ])
+            .map(|(name, curve)| (name, with_auction(env.order(), curve)))
+    };
+
+    for (name, order) in variants() {
+        bench.create(&format!("create/{name}"), order).await;
+    }
+    for (name, order) in curves() {
+        bench.create(&format!("create/{name}"), order).await;
+    }
+
+    for (name, order) in variants() {
+        bench
+            .fill(&format!("fill/{name}"), order, DEFAULT_SRC_AMOUNT)
+            .await;
+    }
+    bench
+        .fill("fill/partial", env.order(), DEFAULT_SRC_AMOUNT / 2)
+        .await;
+    let fee_vault_authority = pda::fee_vault_authority(&env.charlie.pubkey());
+    env.create_atas(&[fee_vault_authority], &[env.tokens[1]])
+        .await;
+    let fee_vault = pda::fee_vault(&env.charlie.pubkey(), &env.tokens[1], &TOKEN_PROGRAM_ID);
+    let mut fee_vault_order = env.order();
+    fee_vault_order.config.fee.protocol_fee = 10_000;
+    fee_vault_order.protocol_dst_acc = Some(fee_vault);
+    bench
+        .fill("fill/fee_vault", fee_vault_order, DEFAULT_SRC_AMOUNT)
+        .await;
+    // Bob among 1024 resolvers of the tree, so that the proof has 10 hashes
+    let mut leaves: Vec<_> = (0..1024)
+        .map(|_| merkle::leaf(&Pubkey::new_unique(), ACCESS_VALID_UNTIL))
+        .collect();
+    leaves[0] = merkle::leaf(&env.bob.pubkey(), ACCESS_VALID_UNTIL);
+    env.set_resolver_root(merkle::root(&leaves)).await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let proof = ResolverProof {
+        valid_until: ACCESS_VALID_UNTIL,
+        proof: merkle::proof(&leaves, 0),
+    };
+    bench
+        .measure(
+            "fill_with_proof/1024_resolvers",
+            fusion_swap_ix::fill_with_proof(
+                escrow.order,
+                env.bob.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+                proof,
+            )
+            .unwrap(),
+            &[&env.bob],
+        )
+        .await;
+    // Past the last point of the curves, so that all of them are iterated
+    env.set_time(START_TIME + AUCTION_DURATION as i64 * 3 / 4);
+    for (name, order) in curves() {
+        bench
+            .fill(&format!("fill/{name}"), order, DEFAULT_SRC_AMOUNT)
+            .await;
+    }
+    env.set_time(START_TIME);
+
+    bench.cancel("cancel/spl", env.order()).await;
+    bench
+        .cancel("cancel/native_src", with_native_src(env.order()))
+        .await;
+
+    let with_premium = |mut order: Order| {
+        order.config.fee.max_cancellation_premium = 1_000;
+        order
+    };
+    let mut src_premium = env.order();
+    src_premium.config.fee.max_src_cancellation_premium = 10;
+    for (name, orders) in [
+        (
+            "cancel_by_resolver/lamports_premium",
+            vec![with_premium(env.order())],
+        ),
+        ("cancel_by_resolver/src_premium", vec![src_premium]),
+        (
+            "cancel_by_resolver/native_src",
+            vec![with_premium(with_native_src(env.order()))],
+        ),
+        (
+            "cancel_by_resolver_batch/4_orders",
+            (0..4).map(|_| with_premium(env.order())).collect(),
+        ),
+    ] {
+        bench.cancel_by_resolver(name, orders).await;
+    }
+
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    bench
+        .measure(
+            "claim_fees",
+            fusion_swap_ix::claim_fees(
+                env.charlie.pubkey(),
+                env.tokens[1],
+                charlie_dst_ata,
+                TOKEN_PROGRAM_ID,
+            ),
+            &[&env.charlie],
+        )
+        .await;
+
+    let authority = env.payer().pubkey();
+    let user = env.dave.pubkey();
+    bench
+        .measure(
+            "whitelist/register",
+            whitelist_ix::register(
+                authority,
+                env.payer().pubkey(),
+                registration(user),
+                env.current_page().await,
+            ),
+            &[],
+        )
+        .await;
+    bench
+        .measure(
+            "whitelist/deregister",
+            whitelist_ix::deregister(
+                authority,
+                env.payer().pubkey(),
+                user,
+                env.registry_page(&user).await,
+            ),
+            &[],
+        )
+        .await;
+
+    let units = bench.units.into_inner();
+    if std::env::var_os("UPDATE_COMPUTE_UNITS").is_some() {
+        write_baseline(&units);
+        return;
+    }
+    let failures = compare(&units, &read_baseline());
+    assert!(
+        failures.is_empty(),
+        "compute units do not match the baseline, update it with `UPDATE_COMPUTE_UNITS=1 yarn \
+         test:compute-units` if the changes are expected:\n{}",
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,447 @@
+use anchor_lang::prelude::Pubkey;
+use fusion_integration_tests::{
+    assert_error, deltas, events, sbf, Escrow, TestEnv, DEFAULT_DST_AMOUNT,
+    DEFAULT_EXPIRATION_TIME, DEFAULT_SRC_AMOUNT, START_TIME,
+};
+use fusion_swap::{
+    auction::{
+        AuctionData, ExponentialDecayCurve, LinearWithPlateauCurve, PiecewiseLinearCurve,
+        PointAndTimeDelta, StepCurve,
+    },
+    error::FusionError,
+    OrderFilled,
+};
+use fusion_swap_client::{fusion_swap as fusion_swap_ix, fusion_swap::Order};
+use solana_sdk::signature::Signer;
+
+const BASE_POINTS: u64 = 100_000;
+const DURATION: u32 = 32_000;
+const INITIAL_RATE_BUMP: u16 = 50_000;
+
+fn points() -> Vec<PointAndTimeDelta> {
+    vec![
+        PointAndTimeDelta {
+            rate_bump: 20_000,
+            time_delta: 10_000,
+        },
+        PointAndTimeDelta {
+            rate_bump: 10_000,
+            time_delta: 20_000,
+        },
+    ]
+}
+
+fn auction() -> PiecewiseLinearCurve {
+    PiecewiseLinearCurve {
+        start_time: START_TIME as u32,
+        duration: DURATION,
+        initial_rate_bump: INITIAL_RATE_BUMP,
+        points_and_time_deltas: points(),
+    }
+}
+
+fn dst_amount_with_rate_bump(rate_bump: u64) -> u64 {
+    DEFAULT_DST_AMOUNT * (BASE_POINTS + rate_bump) / BASE_POINTS
+}
+
+fn auction_order(env: &TestEnv, auction: AuctionData) -> Order {
+    let mut order = env.order();
+    order.config.dutch_auction_data = auction;
+    order
+}
+
+/// Charlie's and dave's ATAs of the dst token as the protocol and integrator fee recipients
+fn fee_accounts(env: &TestEnv) -> (Pubkey, Pubkey) {
+    (
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+        env.ata(&env.dave.pubkey(), &env.tokens[1]),
+    )
+}
+
+/// Maker dst, taker src and taker dst ATAs followed by `extra` accounts
+fn tracked(env: &TestEnv, extra: &[Pubkey]) -> Vec<Pubkey> {
+    let mut accounts = vec![
+        env.ata(&env.alice.pubkey(), &env.tokens[1]),
+        env.ata(&env.bob.pubkey(), &env.tokens[0]),
+        env.ata(&env.bob.pubkey(), &env.tokens[1]),
+    ];
+    accounts.extend(extra);
+    accounts
+}
+
+/// Fills the whole order by bob and returns the balance changes of the tracked accounts
+async fn fill_all(env: &TestEnv, escrow: &Escrow, extra: &[Pubkey]) -> Vec<i64> {
+    let accounts = tracked(env, extra);
+    let before = env.balances(&accounts).await;
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+    assert!(env.account(&escrow.ata).await.is_none());
+    deltas(&before, &env.balances(&accounts).await)
+}
+
+async fn setup() -> (TestEnv, Escrow) {
+    let env = TestEnv::new().await;
+    let order = auction_order(&env, AuctionData::PiecewiseLinear(auction()));
+    let escrow = env.create_escrow(order).await.unwrap();
+    (env, escrow)
+}
+
+fn assert_between(actual: &[i64], min: &[i64], max: &[i64]) {
+    for ((actual, min), max) in actual.iter().zip(min).zip(max) {
+        assert!(
+            min <= actual && actual <= max,
+            "{actual} not in [{min}, {max}]"
+        );
+    }
+}
+
+#[tokio::test]
+async fn fails_after_expiration_time() {
+    let (env, escrow) = setup().await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::OrderExpired);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_after_expiration_time() {
+    let env = TestEnv::new().await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    let result = env.create_escrow(env.order()).await;
+    assert_error(result, FusionError::OrderExpired);
+}
+
+#[tokio::test]
+async fn fills_with_initial_rate_bump_before_auction_started() {
+    let (env, escrow) = setup().await;
+    env.set_time(START_TIME - 1000);
+
+    let dst_amount = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64) as i64;
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [dst_amount, DEFAULT_SRC_AMOUNT as i64, -dst_amount]
+    );
+}
+
+#[tokio::test]
+async fn fills_between_auction_start_and_first_point() {
+    let (env, escrow) = setup().await;
+    env.set_time(START_TIME + points()[0].time_delta as i64 / 2);
+
+    let max = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64) as i64;
+    let min = dst_amount_with_rate_bump(points()[0].rate_bump as u64) as i64;
+    let src = DEFAULT_SRC_AMOUNT as i64;
+    assert_between(
+        &fill_all(&env, &escrow, &[]).await,
+        &[min, src, -max],
+        &[max, src, -min],
+    );
+}
+
+#[tokio::test]
+async fn fills_between_points() {
+    let (env, escrow) = setup().await;
+    env.set_time(START_TIME + points()[0].time_delta as i64 + points()[1].time_delta as i64 / 2);
+
+    let max = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64) as i64;
+    let min = dst_amount_with_rate_bump(points()[1].rate_bump as u64) as i64;
+    let src = DEFAULT_SRC_AMOUNT as i64;
+    assert_between(
+        &fill_all(&env, &escrow, &[]).await,
+        &[min, src, -max],
+        &[max, src, -min],
+    );
+}
+
+#[tokio::test]
+async fn fills_with_default_price_after_auction_finished() {
+    let (env, escrow) = setup().await;
+    env.set_time(START_TIME + DURATION as i64 + 1);
+
+    let dst_amount = DEFAULT_DST_AMOUNT as i64;
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [dst_amount, DEFAULT_SRC_AMOUNT as i64, -dst_amount]
+    );
+}
+
+#[tokio::test]
+async fn fills_with_step_rate_between_points_of_step_curve() {
+    let env = TestEnv::new().await;
+    let curve = auction();
+    let order = auction_order(
+        &env,
+        AuctionData::Step(StepCurve {
+            start_time: curve.start_time,
+            duration: curve.duration,
+            initial_rate_bump: curve.initial_rate_bump,
+            points_and_time_deltas: curve.points_and_time_deltas,
+        }),
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+    env.set_time(START_TIME + points()[0].time_delta as i64 + points()[1].time_delta as i64 / 4);
+
+    let dst_amount = dst_amount_with_rate_bump(points()[0].rate_bump as u64) as i64;
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [dst_amount, DEFAULT_SRC_AMOUNT as i64, -dst_amount]
+    );
+}
+
+#[tokio::test]
+async fn fills_with_initial_rate_bump_during_plateau() {
+    let env = TestEnv::new().await;
+    let order = auction_order(
+        &env,
+        AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+            start_time: START_TIME as u32,
+            duration: DURATION,
+            initial_rate_bump: INITIAL_RATE_BUMP,
+            plateau_duration: DURATION / 2,
+        }),
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+    env.set_time(START_TIME + DURATION as i64 / 4);
+
+    let dst_amount = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64) as i64;
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [dst_amount, DEFAULT_SRC_AMOUNT as i64, -dst_amount]
+    );
+}
+
+#[tokio::test]
+async fn fills_with_halved_rate_after_half_life_of_exponential_decay() {
+    let env = TestEnv::new().await;
+    let half_life = 8000;
+    let order = auction_order(
+        &env,
+        AuctionData::ExponentialDecay(ExponentialDecayCurve {
+            start_time: START_TIME as u32,
+            duration: DURATION,
+            initial_rate_bump: INITIAL_RATE_BUMP,
+            half_life,
+        }),
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+    env.set_time(START_TIME + half_life as i64);
+
+    let dst_amount = (DEFAULT_DST_AMOUNT * (BASE_POINTS + INITIAL_RATE_BUMP as u64 / 2))
+        .div_ceil(BASE_POINTS) as i64;
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [dst_amount, DEFAULT_SRC_AMOUNT as i64, -dst_amount]
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_surplus() {
+    let env = TestEnv::new().await;
+    let (protocol_dst_acc, _) = fee_accounts(&env);
+    let mut order = auction_order(&env, AuctionData::PiecewiseLinear(auction()));
+    order.protocol_dst_acc = Some(protocol_dst_acc);
+    order.config.fee.surplus_percentage = 50;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let dst_amount = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64) as i64;
+    let surplus = (dst_amount - DEFAULT_DST_AMOUNT as i64) / 2;
+    assert_eq!(
+        fill_all(&env, &escrow, &[protocol_dst_acc]).await,
+        [
+            dst_amount - surplus,
+            DEFAULT_SRC_AMOUNT as i64,
+            -dst_amount,
+            surplus
+        ]
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_surplus_split() {
+    let env = TestEnv::new().await;
+    let (protocol_dst_acc, integrator_dst_acc) = fee_accounts(&env);
+    let mut order = auction_order(&env, AuctionData::PiecewiseLinear(auction()));
+    order.protocol_dst_acc = Some(protocol_dst_acc);
+    order.integrator_dst_acc = Some(integrator_dst_acc);
+    order.config.fee.surplus_percentage = 20;
+    order.config.fee.integrator_surplus_percentage = 30;
+    order.config.fee.resolver_surplus_percentage = 10;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let accounts = tracked(&env, &[protocol_dst_acc, integrator_dst_acc]);
+    let before = env.balances(&accounts).await;
+    let logs = env
+        .send_with_logs(
+            &[
+                fusion_swap_ix::fill(escrow.order.clone(), env.bob.pubkey(), DEFAULT_SRC_AMOUNT)
+                    .unwrap(),
+            ],
+            &[&env.bob],
+        )
+        .await
+        .unwrap();
+    let results = deltas(&before, &env.balances(&accounts).await);
+
+    let dst_amount = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64);
+    let surplus = dst_amount - DEFAULT_DST_AMOUNT;
+    let protocol_surplus = surplus * 20 / 100;
+    let integrator_surplus = surplus * 30 / 100;
+    let resolver_surplus = surplus * 10 / 100;
+    let maker_surplus = surplus - protocol_surplus - integrator_surplus - resolver_surplus;
+    assert_eq!(
+        results,
+        [
+            (DEFAULT_DST_AMOUNT + maker_surplus) as i64,
+            DEFAULT_SRC_AMOUNT as i64,
+            -((dst_amount - resolver_surplus) as i64),
+            protocol_surplus as i64,
+            integrator_surplus as i64,
+        ]
+    );
+
+    if sbf() {
+        let events = events::<OrderFilled>(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].dst_amount, dst_amount);
+        assert_eq!(events[0].maker_surplus, maker_surplus);
+        assert_eq!(events[0].protocol_surplus, protocol_surplus);
+        assert_eq!(events[0].integrator_surplus, integrator_surplus);
+        assert_eq!(events[0].resolver_surplus, resolver_surplus);
+    }
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_surplus_split_above_100_percent() {
+    let env = TestEnv::new().await;
+    let (protocol_dst_acc, integrator_dst_acc) = fee_accounts(&env);
+    let mut order = auction_order(&env, AuctionData::PiecewiseLinear(auction()));
+    order.protocol_dst_acc = Some(protocol_dst_acc);
+    order.integrator_dst_acc = Some(integrator_dst_acc);
+    order.config.fee.surplus_percentage = 50;
+    order.config.fee.integrator_surplus_percentage = 40;
+    order.config.fee.resolver_surplus_percentage = 11;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidSurplusSplit);
+}
+
+#[tokio::test]
+async fn executes_trade_with_all_fees() {
+    let env = TestEnv::new().await;
+    let (protocol_dst_acc, integrator_dst_acc) = fee_accounts(&env);
+    let mut order = auction_order(
+        &env,
+        AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+            points_and_time_deltas: vec![],
+            ..auction()
+        }),
+    );
+    order.protocol_dst_acc = Some(protocol_dst_acc);
+    order.integrator_dst_acc = Some(integrator_dst_acc);
+    order.config.fee.protocol_fee = 10_000;
+    order.config.fee.integrator_fee = 15_000;
+    order.config.fee.surplus_percentage = 50;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let dst_amount = dst_amount_with_rate_bump(INITIAL_RATE_BUMP as u64) as i64;
+    let integrator_fee = dst_amount * 15 / 100;
+    let protocol_fee = dst_amount / 10;
+    let surplus = (dst_amount - integrator_fee - protocol_fee - DEFAULT_DST_AMOUNT as i64) / 2;
+    assert_eq!(
+        fill_all(&env, &escrow, &[protocol_dst_acc, integrator_dst_acc]).await,
+        [
+            dst_amount - integrator_fee - protocol_fee - surplus,
+            DEFAULT_SRC_AMOUNT as i64,
+            -dst_amount,
+            protocol_fee + surplus,
+            integrator_fee,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_too_many_auction_points() {
+    let env = TestEnv::new().await;
+    let order = auction_order(
+        &env,
+        AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+            points_and_time_deltas: vec![
+                PointAndTimeDelta {
+                    rate_bump: 0,
+                    time_delta: 1,
+                };
+                17
+            ],
+            ..auction()
+        }),
+    );
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::TooManyAuctionPoints);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_increasing_rate_bumps() {
+    let env = TestEnv::new().await;
+    let order = auction_order(
+        &env,
+        AuctionData::Step(StepCurve {
+            start_time: START_TIME as u32,
+            duration: DURATION,
+            initial_rate_bump: INITIAL_RATE_BUMP,
+            points_and_time_deltas: vec![
+                PointAndTimeDelta {
+                    rate_bump: 10_000,
+                    time_delta: 10_000,
+                },
+                PointAndTimeDelta {
+                    rate_bump: 20_000,
+                    time_delta: 10_000,
+                },
+            ],
+        }),
+    );
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::IncreasingAuctionRateBump);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_points_beyond_duration() {
+    let env = TestEnv::new().await;
+    let order = auction_order(
+        &env,
+        AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+            points_and_time_deltas: vec![
+                PointAndTimeDelta {
+                    rate_bump: 20_000,
+                    time_delta: 20_000,
+                },
+                PointAndTimeDelta {
+                    rate_bump: 10_000,
+                    time_delta: 20_000,
+                },
+            ],
+            ..auction()
+        }),
+    );
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::AuctionPointsExceedDuration);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_auction_start_after_expiration() {
+    let env = TestEnv::new().await;
+    let order = auction_order(
+        &env,
+        AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+            start_time: DEFAULT_EXPIRATION_TIME + 1,
+            ..auction()
+        }),
+    );
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::AuctionStartsAfterExpiration);
+}
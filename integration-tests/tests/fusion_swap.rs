@@ -0,0 +1,1779 @@
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::{AccountMeta, Pubkey},
+};
+use anchor_spl::token::spl_token::{self, error::TokenError};
+use fusion_integration_tests::{
+    assert_error, assert_instruction_error, deltas, events, fusion_swap_instruction, registration,
+    sbf, Escrow, TestEnv, ACCESS_VALID_UNTIL, DEFAULT_DST_AMOUNT, DEFAULT_SRC_AMOUNT, NATIVE_MINT,
+    START_TIME, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use fusion_swap::{error::FusionError, instruction, OrderFilled};
+use fusion_swap_client::{
+    fusion_swap::{self as fusion_swap_ix, Order},
+    merkle::{self, ResolverProof},
+    pda, permissions, whitelist as whitelist_ix, IntegratorFeeShare, Registration,
+};
+use solana_program_test::BanksClientError;
+use solana_sdk::{instruction::InstructionError, signature::Signer};
+
+/// Accounts of `create` by the maker of the order
+fn create_accounts(order: &Order) -> fusion_swap::accounts::Create {
+    let escrow = order.escrow().unwrap();
+    fusion_swap::accounts::Create {
+        system_program: anchor_lang::system_program::ID,
+        escrow,
+        src_mint: order.src_mint,
+        src_token_program: order.src_token_program,
+        escrow_src_ata: pda::ata(&escrow, &order.src_mint, &order.src_token_program),
+        maker: order.maker,
+        maker_src_ata: order.maker_src_ata(),
+        dst_mint: order.dst_mint,
+        maker_receiver: order.maker_receiver,
+        associated_token_program: anchor_spl::associated_token::ID,
+        protocol_dst_acc: order.protocol_dst_acc,
+        integrator_dst_acc: order.integrator_dst_acc,
+    }
+}
+
+/// Accounts of `cancel` by the maker of the order
+fn cancel_accounts(order: &Order) -> fusion_swap::accounts::Cancel {
+    fusion_swap::accounts::Cancel {
+        maker: order.maker,
+        src_mint: order.src_mint,
+        escrow: order.escrow().unwrap(),
+        escrow_src_ata: order.escrow_src_ata().unwrap(),
+        maker_src_ata: order.maker_src_ata(),
+        src_token_program: order.src_token_program,
+    }
+}
+
+async fn create_with(
+    env: &TestEnv,
+    order: &Order,
+    accounts: fusion_swap::accounts::Create,
+) -> Result<(), BanksClientError> {
+    let instruction = fusion_swap_instruction(
+        accounts,
+        instruction::Create {
+            order: order.config.clone(),
+        },
+    );
+    env.send(&[instruction], &[&env.alice]).await
+}
+
+/// Fills `amount` of the order by bob with the given accounts and no remaining accounts
+async fn fill_with(
+    env: &TestEnv,
+    order: &Order,
+    accounts: fusion_swap::accounts::Fill,
+    amount: u64,
+) -> Result<(), BanksClientError> {
+    let instruction = fusion_swap_instruction(
+        accounts,
+        instruction::Fill {
+            order: order.config.clone(),
+            amount,
+        },
+    );
+    env.send(&[instruction], &[&env.bob]).await
+}
+
+async fn cancel_with(
+    env: &TestEnv,
+    order: &Order,
+    accounts: fusion_swap::accounts::Cancel,
+    order_src_asset_is_native: bool,
+) -> Result<(), BanksClientError> {
+    let instruction = fusion_swap_instruction(
+        accounts,
+        instruction::Cancel {
+            order_hash: order.hash().unwrap(),
+            order_src_asset_is_native,
+        },
+    );
+    env.send(&[instruction], &[&env.alice]).await
+}
+
+/// Maker dst, taker src and taker dst ATAs followed by `extra` accounts
+fn tracked(env: &TestEnv, order: &Order, extra: &[Pubkey]) -> Vec<Pubkey> {
+    let mut accounts = vec![
+        env.ata(&order.maker_receiver, &order.dst_mint),
+        env.ata(&env.bob.pubkey(), &order.src_mint),
+        env.ata(&env.bob.pubkey(), &order.dst_mint),
+    ];
+    accounts.extend(extra);
+    accounts
+}
+
+/// Runs `action` and returns the balance changes of the token accounts
+async fn track(
+    env: &TestEnv,
+    accounts: &[Pubkey],
+    action: impl std::future::Future<Output = ()>,
+) -> Vec<i64> {
+    let before = env.balances(accounts).await;
+    action.await;
+    deltas(&before, &env.balances(accounts).await)
+}
+
+/// Runs `action` and returns the lamport changes of the accounts
+async fn track_lamports(
+    env: &TestEnv,
+    accounts: &[Pubkey],
+    action: impl std::future::Future<Output = ()>,
+) -> Vec<i64> {
+    let snapshot = || async {
+        let mut lamports = vec![];
+        for account in accounts {
+            lamports.push(env.lamports(account).await);
+        }
+        lamports
+    };
+    let before = snapshot().await;
+    action.await;
+    deltas(&before, &snapshot().await)
+}
+
+/// Fills the whole order by bob and returns the balance changes of the tracked accounts
+async fn fill_all(env: &TestEnv, escrow: &Escrow, extra: &[Pubkey]) -> Vec<i64> {
+    let accounts = tracked(env, &escrow.order, extra);
+    let results = track(env, &accounts, async {
+        env.fill(&escrow.order, escrow.order.config.src_amount)
+            .await
+            .unwrap();
+    })
+    .await;
+    assert!(env.account(&escrow.ata).await.is_none());
+    results
+}
+
+fn with_protocol_fee(mut order: Order, protocol_dst_acc: Pubkey, protocol_fee: u16) -> Order {
+    order.config.fee.protocol_fee = protocol_fee;
+    order.protocol_dst_acc = Some(protocol_dst_acc);
+    order
+}
+
+fn with_integrator_fee(mut order: Order, integrator_dst_acc: Pubkey, integrator_fee: u16) -> Order {
+    order.config.fee.integrator_fee = integrator_fee;
+    order.integrator_dst_acc = Some(integrator_dst_acc);
+    order
+}
+
+fn with_native_dst(mut order: Order) -> Order {
+    order.dst_mint = NATIVE_MINT;
+    order.config.dst_asset_is_native = true;
+    order
+}
+
+fn with_fee_shares(mut order: Order, shares: &[(Pubkey, u16)]) -> Order {
+    order.config.fee.integrator_fee_shares = shares
+        .iter()
+        .map(|&(recipient, fee)| IntegratorFeeShare { recipient, fee })
+        .collect();
+    order
+}
+
+#[tokio::test]
+async fn executes_trade() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_different_maker_receiver() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.maker_receiver = env.charlie.pubkey();
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_without_u64_overflow() {
+    // amount * amount is greater than u64::MAX
+    const AMOUNT: u64 = 10_000_000_000;
+    let env = TestEnv::new().await;
+    env.mint_to(&env.tokens[0], &env.alice.pubkey(), AMOUNT)
+        .await;
+    env.mint_to(&env.tokens[1], &env.bob.pubkey(), AMOUNT).await;
+    let mut order = env.order();
+    order.config.src_amount = AMOUNT;
+    order.config.min_dst_amount = AMOUNT;
+    order.config.estimated_dst_amount = AMOUNT;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let amount = AMOUNT as i64;
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [amount, amount, -amount]
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_different_taker_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let charlie_src_ata = env.ata(&env.charlie.pubkey(), &env.tokens[0]);
+    let accounts = tracked(&env, &escrow.order, &[charlie_src_ata]);
+
+    let results = track(&env, &accounts, async {
+        fill_with(
+            &env,
+            &escrow.order,
+            fusion_swap::accounts::Fill {
+                taker_src_ata: charlie_src_ata,
+                ..env.fill_accounts(&escrow.order)
+            },
+            DEFAULT_SRC_AMOUNT,
+        )
+        .await
+        .unwrap();
+    })
+    .await;
+    assert_eq!(results, [30, 0, -30, 100].map(i64::from));
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_maker_dst_ata_of_wrong_mint() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            maker_dst_ata: Some(env.ata(&env.alice.pubkey(), &env.tokens[2])),
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintTokenMint);
+}
+
+#[tokio::test]
+async fn executes_trade_with_wrapped_native_src() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.src_mint = NATIVE_MINT;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_wrapped_native_dst() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.dst_mint = NATIVE_MINT;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+/// Creates two Token 2022 mints, the first one held by alice and the second one by bob
+async fn token_2022_mints(env: &mut TestEnv) -> (Pubkey, Pubkey) {
+    let src_mint = env.create_mint(TOKEN_2022_PROGRAM_ID).await;
+    let dst_mint = env.create_mint(TOKEN_2022_PROGRAM_ID).await;
+    env.create_atas(
+        &[env.alice.pubkey(), env.bob.pubkey()],
+        &[src_mint, dst_mint],
+    )
+    .await;
+    env.mint_to(&src_mint, &env.alice.pubkey(), 100_000_000)
+        .await;
+    env.mint_to(&dst_mint, &env.bob.pubkey(), 100_000_000).await;
+    (src_mint, dst_mint)
+}
+
+#[tokio::test]
+async fn executes_trade_from_spl_token_to_token_2022() {
+    let mut env = TestEnv::new().await;
+    let (_, dst_mint) = token_2022_mints(&mut env).await;
+    let mut order = env.order();
+    order.dst_mint = dst_mint;
+    order.dst_token_program = TOKEN_2022_PROGRAM_ID;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_from_token_2022_to_spl_token() {
+    let mut env = TestEnv::new().await;
+    let (src_mint, _) = token_2022_mints(&mut env).await;
+    let mut order = env.order();
+    order.src_mint = src_mint;
+    order.src_token_program = TOKEN_2022_PROGRAM_ID;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_between_token_2022_tokens() {
+    let mut env = TestEnv::new().await;
+    let (src_mint, dst_mint) = token_2022_mints(&mut env).await;
+    let mut order = env.order();
+    order.src_mint = src_mint;
+    order.src_token_program = TOKEN_2022_PROGRAM_ID;
+    order.dst_mint = dst_mint;
+    order.dst_token_program = TOKEN_2022_PROGRAM_ID;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[]).await,
+        [30, 100, -30].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn cancels_escrow_with_token_2022() {
+    let mut env = TestEnv::new().await;
+    let (src_mint, _) = token_2022_mints(&mut env).await;
+    let mut order = env.order();
+    order.src_mint = src_mint;
+    order.src_token_program = TOKEN_2022_PROGRAM_ID;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let maker_src_ata = env.ata(&env.alice.pubkey(), &src_mint);
+    let results = track(&env, &[maker_src_ata], async {
+        env.cancel(&escrow.order).await.unwrap();
+    })
+    .await;
+    assert_eq!(results, [DEFAULT_SRC_AMOUNT as i64]);
+}
+
+#[tokio::test]
+async fn executes_trade_with_protocol_fee() {
+    let env = TestEnv::new().await;
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    // 10%
+    let order = with_protocol_fee(env.order(), charlie_dst_ata, 10_000);
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[charlie_dst_ata]).await,
+        [27, 100, -30, 3].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_native_dst_and_protocol_fee() {
+    let env = TestEnv::new().await;
+    let charlie_native_ata = env.ata(&env.charlie.pubkey(), &NATIVE_MINT);
+    let order = with_protocol_fee(with_native_dst(env.order()), charlie_native_ata, 10_000);
+
+    let results = track_lamports(&env, &[env.alice.pubkey(), charlie_native_ata], async {
+        let escrow = env.create_escrow(order).await.unwrap();
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+        assert!(env.account(&escrow.ata).await.is_none());
+    })
+    .await;
+    assert_eq!(results, [27, 3]);
+}
+
+#[tokio::test]
+async fn executes_trade_with_integrator_fee() {
+    let env = TestEnv::new().await;
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    // 15%
+    let order = with_integrator_fee(env.order(), charlie_dst_ata, 15_000);
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[charlie_dst_ata]).await,
+        [26, 100, -30, 4].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_integrator_fee_shares() {
+    let env = TestEnv::new().await;
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    let dave_dst_ata = env.ata(&env.dave.pubkey(), &env.tokens[1]);
+    // 10% to the integrator, 5% and 2% shared with dave and charlie
+    let order = with_fee_shares(
+        with_integrator_fee(env.order(), charlie_dst_ata, 10_000),
+        &[(dave_dst_ata, 5_000), (charlie_dst_ata, 2_000)],
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    // Fees are floored: 3 to the integrator, 1 and 0 to the share recipients
+    assert_eq!(
+        fill_all(&env, &escrow, &[charlie_dst_ata, dave_dst_ata]).await,
+        [26, 100, -30, 3, 1].map(i64::from)
+    );
+}
+
+#[tokio::test]
+async fn executes_trade_with_native_dst_and_integrator_fee() {
+    let env = TestEnv::new().await;
+    let charlie_native_ata = env.ata(&env.charlie.pubkey(), &NATIVE_MINT);
+    let order = with_integrator_fee(with_native_dst(env.order()), charlie_native_ata, 15_000);
+
+    let results = track_lamports(&env, &[env.alice.pubkey(), charlie_native_ata], async {
+        let escrow = env.create_escrow(order).await.unwrap();
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+        assert!(env.account(&escrow.ata).await.is_none());
+    })
+    .await;
+    assert_eq!(results, [26, 4]);
+}
+
+#[tokio::test]
+async fn executes_trade_with_native_dst_and_different_maker_receiver() {
+    let env = TestEnv::new().await;
+    let mut order = with_native_dst(env.order());
+    order.maker_receiver = env.charlie.pubkey();
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let results = track_lamports(&env, &[env.charlie.pubkey()], async {
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+    })
+    .await;
+    assert_eq!(results, [DEFAULT_DST_AMOUNT as i64]);
+    assert!(env.account(&escrow.ata).await.is_none());
+}
+
+#[tokio::test]
+async fn executes_trade_with_wrapped_native_dst_and_fees() {
+    let env = TestEnv::new().await;
+    let charlie_native_ata = env.ata(&env.charlie.pubkey(), &NATIVE_MINT);
+    let dave_native_ata = env.ata(&env.dave.pubkey(), &NATIVE_MINT);
+    let mut order = with_integrator_fee(
+        with_protocol_fee(env.order(), charlie_native_ata, 10_000),
+        dave_native_ata,
+        15_000,
+    );
+    order.dst_mint = NATIVE_MINT;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    assert_eq!(
+        fill_all(&env, &escrow, &[charlie_native_ata, dave_native_ata]).await,
+        [23, 100, -30, 3, 4].map(i64::from)
+    );
+}
+
+/// Creates charlie's fee vault of `mint`
+async fn create_fee_vault(env: &TestEnv, mint: &Pubkey) -> Pubkey {
+    let authority = pda::fee_vault_authority(&env.charlie.pubkey());
+    env.create_atas(&[authority], &[*mint]).await;
+    pda::fee_vault(&env.charlie.pubkey(), mint, &TOKEN_PROGRAM_ID)
+}
+
+#[tokio::test]
+async fn credits_protocol_fee_to_fee_vault_and_claims_it() {
+    let env = TestEnv::new().await;
+    let fee_vault = create_fee_vault(&env, &env.tokens[1]).await;
+    let order = with_protocol_fee(env.order(), fee_vault, 10_000);
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let results = fill_all(&env, &escrow, &[fee_vault]).await;
+    assert_eq!([results[0], results[3]], [27, 3]);
+
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    let results = track(&env, &[fee_vault, charlie_dst_ata], async {
+        env.send(
+            &[fusion_swap_ix::claim_fees(
+                env.charlie.pubkey(),
+                env.tokens[1],
+                charlie_dst_ata,
+                TOKEN_PROGRAM_ID,
+            )],
+            &[&env.charlie],
+        )
+        .await
+        .unwrap();
+    })
+    .await;
+    assert_eq!(results, [-3, 3]);
+}
+
+#[tokio::test]
+async fn claims_native_fees_from_wrapped_native_fee_vault() {
+    let env = TestEnv::new().await;
+    let fee_vault = create_fee_vault(&env, &NATIVE_MINT).await;
+    let order = with_protocol_fee(with_native_dst(env.order()), fee_vault, 10_000);
+    let escrow = env.create_escrow(order).await.unwrap();
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+
+    let charlie_native_ata = env.ata(&env.charlie.pubkey(), &NATIVE_MINT);
+    let results = track(&env, &[charlie_native_ata], async {
+        env.send(
+            &[fusion_swap_ix::claim_fees(
+                env.charlie.pubkey(),
+                NATIVE_MINT,
+                charlie_native_ata,
+                TOKEN_PROGRAM_ID,
+            )],
+            &[&env.charlie],
+        )
+        .await
+        .unwrap();
+    })
+    .await;
+    assert_eq!(results, [3]);
+}
+
+#[tokio::test]
+async fn does_not_claim_fees_from_fee_vault_of_another_beneficiary() {
+    let env = TestEnv::new().await;
+    let fee_vault = create_fee_vault(&env, &env.tokens[1]).await;
+
+    let instruction = fusion_swap_instruction(
+        fusion_swap::accounts::ClaimFees {
+            beneficiary: env.dave.pubkey(),
+            fee_vault_authority: pda::fee_vault_authority(&env.dave.pubkey()),
+            mint: env.tokens[1],
+            fee_vault,
+            beneficiary_dst_acc: env.ata(&env.dave.pubkey(), &env.tokens[1]),
+            token_program: TOKEN_PROGRAM_ID,
+        },
+        instruction::ClaimFees {},
+    );
+    let result = env.send(&[instruction], &[&env.dave]).await;
+    // The vault is owned by the vault authority of charlie
+    assert_error(result, ErrorCode::ConstraintTokenOwner);
+}
+
+#[tokio::test]
+async fn does_not_claim_fees_from_empty_fee_vault() {
+    let env = TestEnv::new().await;
+    create_fee_vault(&env, &env.tokens[1]).await;
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::claim_fees(
+                env.charlie.pubkey(),
+                env.tokens[1],
+                env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+                TOKEN_PROGRAM_ID,
+            )],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, FusionError::NoFeesToClaim);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_amount_above_escrow_balance() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT * 10).await;
+    assert_error(result, FusionError::NotEnoughTokensInEscrow);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_without_taker_dst_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            taker_dst_ata: None,
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, FusionError::MissingTakerDstAta);
+}
+
+#[tokio::test]
+async fn creates_maker_dst_ata_if_missing() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let maker_dst_ata = env.ata(&env.alice.pubkey(), &env.tokens[1]);
+    let alice = env.alice.pubkey();
+    env.send(
+        &[spl_token::instruction::close_account(
+            &TOKEN_PROGRAM_ID,
+            &maker_dst_ata,
+            &alice,
+            &alice,
+            &[],
+        )
+        .unwrap()],
+        &[&env.alice],
+    )
+    .await
+    .unwrap();
+    assert!(env.account(&maker_dst_ata).await.is_none());
+
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+    assert_eq!(env.balance(&maker_dst_ata).await, DEFAULT_DST_AMOUNT);
+}
+
+#[tokio::test]
+async fn does_not_create_with_zero_src_amount() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.config.src_amount = 0;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn does_not_create_with_spl_token_without_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let order = env.order();
+
+    let result = create_with(
+        &env,
+        &order,
+        fusion_swap::accounts::Create {
+            maker_src_ata: None,
+            ..create_accounts(&order)
+        },
+    )
+    .await;
+    assert_error(result, FusionError::InconsistentNativeSrcTrait);
+}
+
+#[tokio::test]
+async fn does_not_create_with_zero_min_dst_amount() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.config.min_dst_amount = 0;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_twice() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = env.create_escrow(escrow.order).await;
+    // The escrow ATA is already owned by the token program
+    assert_instruction_error(result, InstructionError::IllegalOwner);
+}
+
+/// Two escrows of the default order
+async fn two_escrows(env: &TestEnv) -> (Escrow, Escrow) {
+    (
+        env.create_escrow(env.order()).await.unwrap(),
+        env.create_escrow(env.order()).await.unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_order_id() {
+    let env = TestEnv::new().await;
+    let (first, second) = two_escrows(&env).await;
+
+    let result = fill_with(
+        &env,
+        &second.order,
+        env.fill_accounts(&first.order),
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_escrow_ata() {
+    let env = TestEnv::new().await;
+    let (first, second) = two_escrows(&env).await;
+
+    let result = fill_with(
+        &env,
+        &first.order,
+        fusion_swap::accounts::Fill {
+            escrow_src_ata: second.ata,
+            ..env.fill_accounts(&first.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintTokenOwner);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_dst_mint() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            dst_mint: env.tokens[0],
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintTokenMint);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_maker_receiver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            maker_receiver: env.charlie.pubkey(),
+            maker_dst_ata: Some(env.ata(&env.charlie.pubkey(), &env.tokens[1])),
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_wrong_surplus_percentage() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    // 146%
+    order.config.fee.surplus_percentage = 146;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidProtocolSurplusFee);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_protocol_dst_acc_outside_of_order() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.config.fee.protocol_fee = 10_000;
+
+    let result = create_with(
+        &env,
+        &order,
+        fusion_swap::accounts::Create {
+            protocol_dst_acc: Some(env.ata(&env.charlie.pubkey(), &env.tokens[0])),
+            ..create_accounts(&order)
+        },
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_integrator_dst_acc_outside_of_order() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.config.fee.integrator_fee = 10_000;
+
+    let result = create_with(
+        &env,
+        &order,
+        fusion_swap::accounts::Create {
+            integrator_dst_acc: Some(env.ata(&env.charlie.pubkey(), &env.tokens[0])),
+            ..create_accounts(&order)
+        },
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_protocol_dst_acc_authority() {
+    let env = TestEnv::new().await;
+    let order = with_protocol_fee(
+        env.order(),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+        10_000,
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            protocol_dst_acc: Some(env.ata(&env.bob.pubkey(), &env.tokens[1])),
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_protocol_dst_acc_mint() {
+    let env = TestEnv::new().await;
+    let order = with_protocol_fee(
+        env.order(),
+        env.ata(&env.charlie.pubkey(), &env.tokens[0]),
+        10_000,
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, TokenError::MintMismatch as u32);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_without_protocol_dst_acc() {
+    let env = TestEnv::new().await;
+    let order = with_protocol_fee(
+        env.order(),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+        10_000,
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            protocol_dst_acc: None,
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_integrator_dst_acc_authority() {
+    let env = TestEnv::new().await;
+    let order = with_integrator_fee(
+        env.order(),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+        10_000,
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            integrator_dst_acc: Some(env.ata(&env.bob.pubkey(), &env.tokens[1])),
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_integrator_dst_acc_mint() {
+    let env = TestEnv::new().await;
+    let order = with_integrator_fee(
+        env.order(),
+        env.ata(&env.charlie.pubkey(), &env.tokens[0]),
+        10_000,
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, TokenError::MintMismatch as u32);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_without_integrator_dst_acc() {
+    let env = TestEnv::new().await;
+    let order = with_integrator_fee(
+        env.order(),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+        10_000,
+    );
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            integrator_dst_acc: None,
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+/// Order sharing 5% of the dst amount with dave
+fn fee_share_order(env: &TestEnv) -> Order {
+    with_fee_shares(
+        env.order(),
+        &[(env.ata(&env.dave.pubkey(), &env.tokens[1]), 5_000)],
+    )
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_integrator_fee_share_recipient() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(fee_share_order(&env)).await.unwrap();
+
+    let mut instruction = fusion_swap_instruction(
+        env.fill_accounts(&escrow.order),
+        instruction::Fill {
+            order: escrow.order.config.clone(),
+            amount: DEFAULT_SRC_AMOUNT,
+        },
+    );
+    instruction.accounts.push(AccountMeta::new(
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+        false,
+    ));
+    let result = env.send(&[instruction], &[&env.bob]).await;
+    assert_error(result, FusionError::InconsistentIntegratorFeeShares);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_without_integrator_fee_share_recipients() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(fee_share_order(&env)).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        env.fill_accounts(&escrow.order),
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, FusionError::InconsistentIntegratorFeeShares);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_too_many_integrator_fee_shares() {
+    let env = TestEnv::new().await;
+    let dave_dst_ata = env.ata(&env.dave.pubkey(), &env.tokens[1]);
+    let order = with_fee_shares(env.order(), &[(dave_dst_ata, 1_000); 4]);
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::TooManyIntegratorFeeShares);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_zero_integrator_fee_share() {
+    let env = TestEnv::new().await;
+    let dave_dst_ata = env.ata(&env.dave.pubkey(), &env.tokens[1]);
+    let order = with_fee_shares(env.order(), &[(dave_dst_ata, 0)]);
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidIntegratorFeeShare);
+}
+
+#[tokio::test]
+async fn does_not_create_escrow_with_total_fee_above_100_percent() {
+    let env = TestEnv::new().await;
+    let charlie_dst_ata = env.ata(&env.charlie.pubkey(), &env.tokens[1]);
+    let dave_dst_ata = env.ata(&env.dave.pubkey(), &env.tokens[1]);
+    // 50% + 30% + 20.001%
+    let order = with_fee_shares(
+        with_integrator_fee(
+            with_protocol_fee(env.order(), charlie_dst_ata, 50_000),
+            charlie_dst_ata,
+            30_000,
+        ),
+        &[(dave_dst_ata, 20_001)],
+    );
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InvalidTotalFee);
+}
+
+#[tokio::test]
+async fn executes_multiple_trades() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let mut accounts = tracked(&env, &escrow.order, &[]);
+    accounts.insert(0, escrow.ata);
+
+    let results = track(&env, &accounts, async {
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT / 2)
+            .await
+            .unwrap();
+    })
+    .await;
+    assert_eq!(results, [-50, 15, 50, -15]);
+
+    let results = track(&env, &accounts[1..], async {
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT / 2)
+            .await
+            .unwrap();
+    })
+    .await;
+    assert_eq!(results, [15, 50, -15]);
+    assert!(env.account(&escrow.ata).await.is_none());
+}
+
+#[tokio::test]
+async fn executes_multiple_trades_with_rounding() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.config.src_amount = 101;
+    order.config.min_dst_amount = 101;
+    order.config.estimated_dst_amount = 101;
+    let escrow = env.create_escrow(order).await.unwrap();
+    let mut accounts = tracked(&env, &escrow.order, &[]);
+    accounts.insert(0, escrow.ata);
+
+    for _ in 0..2 {
+        let results = track(&env, &accounts, async {
+            env.fill(&escrow.order, 50).await.unwrap();
+        })
+        .await;
+        assert_eq!(results, [-50, 50, 50, -50]);
+    }
+
+    let results = track(&env, &accounts[1..], async {
+        env.fill(&escrow.order, 1).await.unwrap();
+    })
+    .await;
+    assert_eq!(results, [1, 1, -1]);
+    assert!(env.account(&escrow.ata).await.is_none());
+}
+
+#[tokio::test]
+async fn cancels_trade() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let maker_src_ata = env.ata(&env.alice.pubkey(), &env.tokens[0]);
+    let results = track(&env, &[maker_src_ata], async {
+        env.cancel(&escrow.order).await.unwrap();
+    })
+    .await;
+    assert_eq!(results, [DEFAULT_SRC_AMOUNT as i64]);
+    assert!(env.account(&escrow.ata).await.is_none());
+}
+
+/// Order of native SOL that does not expire
+fn native_src_order(env: &TestEnv) -> Order {
+    let mut order = env.order();
+    order.src_mint = NATIVE_MINT;
+    order.config.src_asset_is_native = true;
+    order.config.src_amount = 10_000;
+    order.config.expiration_time = u32::MAX;
+    order
+}
+
+#[tokio::test]
+async fn cancels_native_src_without_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(native_src_order(&env)).await.unwrap();
+    let rent = env.token_account_rent().await;
+
+    let results = track_lamports(&env, &[env.alice.pubkey()], async {
+        cancel_with(&env, &escrow.order, cancel_accounts(&escrow.order), true)
+            .await
+            .unwrap();
+    })
+    .await;
+    assert_eq!(results, [(10_000 + rent) as i64]);
+}
+
+#[tokio::test]
+async fn does_not_cancel_spl_token_without_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = cancel_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Cancel {
+            maker_src_ata: None,
+            ..cancel_accounts(&escrow.order)
+        },
+        false,
+    )
+    .await;
+    assert_error(result, FusionError::InconsistentNativeSrcTrait);
+}
+
+#[tokio::test]
+async fn cancels_wrapped_native_src_as_native() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.src_mint = NATIVE_MINT;
+    let escrow = env.create_escrow(order).await.unwrap();
+    let rent = env.token_account_rent().await;
+
+    let results = track_lamports(&env, &[env.alice.pubkey()], async {
+        cancel_with(
+            &env,
+            &escrow.order,
+            fusion_swap::accounts::Cancel {
+                maker_src_ata: None,
+                ..cancel_accounts(&escrow.order)
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    })
+    .await;
+    assert_eq!(results, [(DEFAULT_SRC_AMOUNT + rent) as i64]);
+}
+
+#[tokio::test]
+async fn does_not_cancel_with_wrong_order_hash() {
+    let env = TestEnv::new().await;
+    let (first, second) = two_escrows(&env).await;
+
+    let result = cancel_with(&env, &second.order, cancel_accounts(&first.order), false).await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_cancel_with_wrong_escrow_ata() {
+    let env = TestEnv::new().await;
+    let (first, second) = two_escrows(&env).await;
+
+    let result = cancel_with(
+        &env,
+        &first.order,
+        fusion_swap::accounts::Cancel {
+            escrow_src_ata: second.ata,
+            ..cancel_accounts(&first.order)
+        },
+        false,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintTokenOwner);
+}
+
+#[tokio::test]
+async fn does_not_cancel_by_wrong_maker() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let instruction = fusion_swap_instruction(
+        fusion_swap::accounts::Cancel {
+            maker: env.charlie.pubkey(),
+            maker_src_ata: Some(env.ata(&env.charlie.pubkey(), &env.tokens[0])),
+            ..cancel_accounts(&escrow.order)
+        },
+        instruction::Cancel {
+            order_hash: escrow.order.hash().unwrap(),
+            order_src_asset_is_native: false,
+        },
+    );
+    let result = env.send(&[instruction], &[&env.charlie]).await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_non_whitelisted_taker() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = env
+        .send(
+            &[
+                fusion_swap_ix::fill(escrow.order, env.charlie.pubkey(), DEFAULT_SRC_AMOUNT)
+                    .unwrap(),
+            ],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_taker_with_lapsed_access() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.renew(&env.bob.pubkey(), START_TIME).await;
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::ResolverAccessNotValid);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_taker_before_access_is_valid() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.send(
+        &[whitelist_ix::register(
+            env.payer().pubkey(),
+            env.payer().pubkey(),
+            Registration {
+                valid_from: START_TIME + 1,
+                ..registration(env.charlie.pubkey())
+            },
+            env.current_page().await,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let result = env
+        .send(
+            &[
+                fusion_swap_ix::fill(escrow.order, env.charlie.pubkey(), DEFAULT_SRC_AMOUNT)
+                    .unwrap(),
+            ],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, FusionError::ResolverAccessNotValid);
+}
+
+#[tokio::test]
+async fn executes_trade_after_access_renewal() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.renew(&env.bob.pubkey(), START_TIME).await;
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::ResolverAccessNotValid);
+
+    env.renew(&env.bob.pubkey(), START_TIME + 1).await;
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_suspended_taker() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.suspend(&env.bob.pubkey()).await;
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::ResolverSuspended);
+}
+
+#[tokio::test]
+async fn executes_trade_after_reinstatement() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.suspend(&env.bob.pubkey()).await;
+    env.send(
+        &[whitelist_ix::reinstate(
+            env.payer().pubkey(),
+            env.bob.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_taker_without_fill_permission() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.update_permissions(&env.bob.pubkey(), permissions::CANCEL)
+        .await;
+
+    let result = env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::MissingResolverPermission);
+}
+
+#[tokio::test]
+async fn executes_trade_by_delegate_of_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    let accounts = [
+        env.ata(&env.charlie.pubkey(), &env.tokens[0]),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+    ];
+
+    let before = env.balances(&accounts).await;
+    let logs = env
+        .send_with_logs(
+            &[fusion_swap_ix::delegated_fill(
+                escrow.order,
+                env.charlie.pubkey(),
+                env.bob.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await
+        .unwrap();
+    assert!(env.account(&escrow.ata).await.is_none());
+    assert_eq!(
+        deltas(&before, &env.balances(&accounts).await),
+        [DEFAULT_SRC_AMOUNT as i64, -(DEFAULT_DST_AMOUNT as i64),]
+    );
+
+    if sbf() {
+        let events = events::<OrderFilled>(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].taker, env.charlie.pubkey());
+        assert_eq!(events[0].resolver, env.bob.pubkey());
+    }
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_delegate_of_suspended_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.suspend(&env.bob.pubkey()).await;
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::delegated_fill(
+                escrow.order,
+                env.charlie.pubkey(),
+                env.bob.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, FusionError::ResolverSuspended);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_removed_delegate() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.send(
+        &[whitelist_ix::remove_delegate(
+            env.bob.pubkey(),
+            env.charlie.pubkey(),
+        )],
+        &[&env.bob],
+    )
+    .await
+    .unwrap();
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::delegated_fill(
+                escrow.order,
+                env.charlie.pubkey(),
+                env.bob.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_delegate_with_access_of_other_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.register(&env.dave.pubkey()).await;
+
+    // Delegate of bob presenting the access of dave
+    let result = env
+        .send(
+            &[fusion_swap_ix::delegated_fill(
+                escrow.order,
+                env.charlie.pubkey(),
+                env.dave.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_by_delegate_of_earlier_registration() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.deregister(&env.bob.pubkey()).await;
+    env.register(&env.bob.pubkey()).await;
+
+    let result = env
+        .send(
+            &[fusion_swap_ix::delegated_fill(
+                escrow.order,
+                env.charlie.pubkey(),
+                env.bob.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, FusionError::InvalidResolverDelegate);
+}
+
+/// Resolver tree of `(resolver, valid_until)` leaves and the proof of the leaf at `index`
+fn resolver_tree(leaves: &[(Pubkey, i64)], index: usize) -> ([u8; 32], ResolverProof) {
+    let hashes: Vec<_> = leaves
+        .iter()
+        .map(|(resolver, valid_until)| merkle::leaf(resolver, *valid_until))
+        .collect();
+    let proof = ResolverProof {
+        valid_until: leaves[index].1,
+        proof: merkle::proof(&hashes, index),
+    };
+    (merkle::root(&hashes).unwrap(), proof)
+}
+
+/// Fills the whole order by charlie with a proof instead of a resolver access
+async fn fill_with_proof(
+    env: &TestEnv,
+    escrow: &Escrow,
+    proof: ResolverProof,
+) -> Result<(), BanksClientError> {
+    env.send(
+        &[fusion_swap_ix::fill_with_proof(
+            escrow.order.clone(),
+            env.charlie.pubkey(),
+            DEFAULT_SRC_AMOUNT,
+            proof,
+        )
+        .unwrap()],
+        &[&env.charlie],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn executes_trade_by_resolver_in_tree() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let (root, proof) = resolver_tree(
+        &[
+            (env.dave.pubkey(), ACCESS_VALID_UNTIL),
+            (env.charlie.pubkey(), ACCESS_VALID_UNTIL),
+            (Pubkey::new_unique(), ACCESS_VALID_UNTIL),
+        ],
+        1,
+    );
+    env.set_resolver_root(Some(root)).await;
+    let accounts = [
+        env.ata(&env.charlie.pubkey(), &env.tokens[0]),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+    ];
+
+    let before = env.balances(&accounts).await;
+    fill_with_proof(&env, &escrow, proof).await.unwrap();
+    assert!(env.account(&escrow.ata).await.is_none());
+    assert_eq!(
+        deltas(&before, &env.balances(&accounts).await),
+        [DEFAULT_SRC_AMOUNT as i64, -(DEFAULT_DST_AMOUNT as i64)]
+    );
+}
+
+/// Fills the whole order by charlie as the delegate of bob, proving bob is in the tree
+async fn delegated_fill_with_proof(
+    env: &TestEnv,
+    escrow: &Escrow,
+    proof: ResolverProof,
+) -> Result<(), BanksClientError> {
+    env.send(
+        &[fusion_swap_ix::delegated_fill_with_proof(
+            escrow.order.clone(),
+            env.charlie.pubkey(),
+            env.bob.pubkey(),
+            DEFAULT_SRC_AMOUNT,
+            proof,
+        )
+        .unwrap()],
+        &[&env.charlie],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn executes_trade_by_delegate_of_resolver_in_tree() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    let (root, proof) = resolver_tree(&[(env.bob.pubkey(), ACCESS_VALID_UNTIL)], 0);
+    env.set_resolver_root(Some(root)).await;
+
+    delegated_fill_with_proof(&env, &escrow, proof)
+        .await
+        .unwrap();
+    assert!(env.account(&escrow.ata).await.is_none());
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_proof_by_delegate_of_deregistered_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    env.add_delegate(&env.charlie).await;
+    env.deregister(&env.bob.pubkey()).await;
+    let (root, proof) = resolver_tree(&[(env.bob.pubkey(), ACCESS_VALID_UNTIL)], 0);
+    env.set_resolver_root(Some(root)).await;
+
+    let result = delegated_fill_with_proof(&env, &escrow, proof.clone()).await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+
+    // Without the closed access
+    let accounts = fusion_swap::accounts::Fill {
+        resolver_delegate: Some(pda::resolver_delegate(&env.charlie.pubkey())),
+        resolver_access: None,
+        resolver_bond: pda::resolver_bond(&env.bob.pubkey()),
+        ..env.fill_accounts_for(&escrow.order, env.charlie.pubkey())
+    };
+    let result = env
+        .send(
+            &[fusion_swap_instruction(
+                accounts,
+                instruction::FillWithProof {
+                    order: escrow.order.config.clone(),
+                    amount: DEFAULT_SRC_AMOUNT,
+                    proof,
+                },
+            )],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, FusionError::MissingResolverAccess);
+
+    env.register(&env.bob.pubkey()).await;
+    let (_, proof) = resolver_tree(&[(env.bob.pubkey(), ACCESS_VALID_UNTIL)], 0);
+    let result = delegated_fill_with_proof(&env, &escrow, proof).await;
+    assert_error(result, FusionError::InvalidResolverDelegate);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_proof_of_other_resolver() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let (root, proof) = resolver_tree(
+        &[
+            (env.dave.pubkey(), ACCESS_VALID_UNTIL),
+            (env.bob.pubkey(), ACCESS_VALID_UNTIL),
+        ],
+        0,
+    );
+    env.set_resolver_root(Some(root)).await;
+
+    let result = fill_with_proof(&env, &escrow, proof).await;
+    assert_error(result, FusionError::InvalidResolverProof);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_proof_of_replaced_root() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let (root, proof) = resolver_tree(&[(env.charlie.pubkey(), ACCESS_VALID_UNTIL)], 0);
+    env.set_resolver_root(Some(root)).await;
+    let (root, _) = resolver_tree(&[(env.dave.pubkey(), ACCESS_VALID_UNTIL)], 0);
+    env.set_resolver_root(Some(root)).await;
+
+    let result = fill_with_proof(&env, &escrow, proof).await;
+    assert_error(result, FusionError::InvalidResolverProof);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_proof_of_lapsed_leaf() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let (root, proof) = resolver_tree(&[(env.charlie.pubkey(), START_TIME)], 0);
+    env.set_resolver_root(Some(root)).await;
+
+    let result = fill_with_proof(&env, &escrow, proof).await;
+    assert_error(result, FusionError::ResolverAccessNotValid);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_proof_without_root() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let (_, proof) = resolver_tree(&[(env.charlie.pubkey(), ACCESS_VALID_UNTIL)], 0);
+
+    let result = fill_with_proof(&env, &escrow, proof).await;
+    assert_error(result, FusionError::ResolverRootNotSet);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_without_resolver_access() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let accounts = fusion_swap::accounts::Fill {
+        resolver_access: None,
+        ..env.fill_accounts(&escrow.order)
+    };
+
+    let result = fill_with(&env, &escrow.order, accounts, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::MissingResolverAccess);
+}
+
+#[tokio::test]
+async fn executes_partial_fill_and_cancels_rest() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+    let mut accounts = tracked(&env, &escrow.order, &[]);
+    accounts.insert(0, escrow.ata);
+
+    let results = track(&env, &accounts, async {
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT / 2)
+            .await
+            .unwrap();
+    })
+    .await;
+    assert_eq!(results, [-50, 15, 50, -15]);
+
+    let maker_src_ata = env.ata(&env.alice.pubkey(), &env.tokens[0]);
+    let results = track(&env, &[maker_src_ata], async {
+        env.cancel(&escrow.order).await.unwrap();
+    })
+    .await;
+    assert_eq!(results, [50]);
+}
+
+#[tokio::test]
+async fn creates_native_src_without_maker_src_ata() {
+    let env = TestEnv::new().await;
+    let order = native_src_order(&env);
+
+    create_with(
+        &env,
+        &order,
+        fusion_swap::accounts::Create {
+            maker_src_ata: None,
+            ..create_accounts(&order)
+        },
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn executes_trade_with_native_dst() {
+    let env = TestEnv::new().await;
+    let order = with_native_dst(env.order());
+
+    let results = track_lamports(&env, &[env.alice.pubkey()], async {
+        let escrow = env.create_escrow(order).await.unwrap();
+        env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+        assert!(env.account(&escrow.ata).await.is_none());
+    })
+    .await;
+    assert_eq!(results, [DEFAULT_DST_AMOUNT as i64]);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_without_maker_dst_ata() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.dst_mint = NATIVE_MINT;
+    let escrow = env.create_escrow(order).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            maker_dst_ata: None,
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, FusionError::MissingMakerDstAta);
+}
+
+#[tokio::test]
+async fn does_not_create_native_dst_order_with_non_native_dst_mint() {
+    let env = TestEnv::new().await;
+    let mut order = env.order();
+    order.config.dst_asset_is_native = true;
+
+    let result = env.create_escrow(order).await;
+    assert_error(result, FusionError::InconsistentNativeDstTrait);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_maker_dst_ata() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            maker_dst_ata: Some(env.ata(&env.charlie.pubkey(), &env.tokens[1])),
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintTokenOwner);
+}
+
+#[tokio::test]
+async fn does_not_execute_trade_with_wrong_src_mint() {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = fill_with(
+        &env,
+        &escrow.order,
+        fusion_swap::accounts::Fill {
+            src_mint: env.tokens[1],
+            ..env.fill_accounts(&escrow.order)
+        },
+        DEFAULT_SRC_AMOUNT,
+    )
+    .await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn fills_escrows_by_different_takers() {
+    let env = TestEnv::new().await;
+    let (first, second) = two_escrows(&env).await;
+    env.register(&env.charlie.pubkey()).await;
+    let mut accounts = tracked(&env, &first.order, &[]);
+    accounts.extend([
+        env.ata(&env.charlie.pubkey(), &env.tokens[0]),
+        env.ata(&env.charlie.pubkey(), &env.tokens[1]),
+    ]);
+
+    let results = track(&env, &accounts, async {
+        env.fill(&first.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+        env.send(
+            &[fusion_swap_ix::fill(
+                second.order.clone(),
+                env.charlie.pubkey(),
+                DEFAULT_SRC_AMOUNT,
+            )
+            .unwrap()],
+            &[&env.charlie],
+        )
+        .await
+        .unwrap();
+    })
+    .await;
+    assert_eq!(results, [60, 100, -30, 100, -30]);
+    assert!(env.account(&first.ata).await.is_none());
+    assert!(env.account(&second.ata).await.is_none());
+}
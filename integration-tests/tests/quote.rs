@@ -0,0 +1,149 @@
+use anchor_lang::{error::ErrorCode, prelude::Pubkey, AnchorDeserialize};
+use fusion_integration_tests::{
+    assert_error, deltas, Escrow, TestEnv, DEFAULT_EXPIRATION_TIME, DEFAULT_SRC_AMOUNT, START_TIME,
+};
+use fusion_swap::{
+    auction::{AuctionData, PiecewiseLinearCurve},
+    error::FusionError,
+    quote::quote,
+    OrderQuote,
+};
+use fusion_swap_client::fusion_swap::{self as fusion_swap_ix, Order};
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::Signer;
+
+/// Order with a running auction, a 1% protocol fee to charlie and a 2% integrator fee to dave
+fn order(env: &TestEnv) -> Order {
+    let mut order = env.order();
+    order.config.min_dst_amount = 3_000;
+    order.config.estimated_dst_amount = 3_000;
+    order.config.dutch_auction_data = AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+        start_time: START_TIME as u32,
+        duration: 32_000,
+        initial_rate_bump: 50_000,
+        points_and_time_deltas: vec![],
+    });
+    order.config.fee.protocol_fee = 1_000;
+    order.protocol_dst_acc = Some(env.ata(&env.charlie.pubkey(), &env.tokens[1]));
+    order.config.fee.integrator_fee = 2_000;
+    order.integrator_dst_acc = Some(env.ata(&env.dave.pubkey(), &env.tokens[1]));
+    order.config.fee.surplus_percentage = 50;
+    order
+}
+
+async fn setup() -> (TestEnv, Escrow) {
+    let env = TestEnv::new().await;
+    let escrow = env.create_escrow(order(&env)).await.unwrap();
+    env.set_time(START_TIME + 8_000);
+    (env, escrow)
+}
+
+async fn simulate_quote(
+    env: &TestEnv,
+    order: &Order,
+    amount: u64,
+) -> Result<OrderQuote, BanksClientError> {
+    let data = env
+        .simulate(
+            &[fusion_swap_ix::quote(order.clone(), amount).unwrap()],
+            &[],
+        )
+        .await?;
+    Ok(OrderQuote::try_from_slice(&data).unwrap())
+}
+
+#[tokio::test]
+async fn quotes_amounts_of_fill() {
+    let (env, escrow) = setup().await;
+    let amount = DEFAULT_SRC_AMOUNT * 3 / 5;
+
+    let order_quote = simulate_quote(&env, &escrow.order, amount).await.unwrap();
+    let expected = quote(&escrow.order.config, START_TIME as u64 + 8_000, amount).unwrap();
+    assert_eq!(order_quote.order_hash, escrow.order.hash().unwrap());
+    assert_eq!(order_quote.rate_bump, expected.rate_bump);
+    assert_eq!(order_quote.rate_bump, 37_500);
+    assert_eq!(order_quote.dst_amount, expected.dst_amount);
+
+    let fee_amounts = order_quote.fee_amounts;
+    let accounts: Vec<Pubkey> = [&env.alice, &env.charlie, &env.dave, &env.bob]
+        .map(|user| env.ata(&user.pubkey(), &env.tokens[1]))
+        .into();
+    let before = env.balances(&accounts).await;
+    env.fill(&escrow.order, amount).await.unwrap();
+    assert_eq!(
+        deltas(&before, &env.balances(&accounts).await),
+        [
+            fee_amounts.maker_amount as i64,
+            fee_amounts.protocol_fee as i64,
+            fee_amounts.integrator_fee as i64,
+            -((order_quote.dst_amount - fee_amounts.resolver_surplus) as i64),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn quotes_without_changing_accounts() {
+    let (env, escrow) = setup().await;
+    let accounts = [escrow.ata, env.ata(&env.alice.pubkey(), &env.tokens[1])];
+    let before = env.balances(&accounts).await;
+
+    env.send(
+        &[fusion_swap_ix::quote(escrow.order.clone(), DEFAULT_SRC_AMOUNT).unwrap()],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.balances(&accounts).await, before);
+}
+
+#[tokio::test]
+async fn does_not_quote_expired_order() {
+    let (env, escrow) = setup().await;
+    env.set_time(DEFAULT_EXPIRATION_TIME as i64);
+
+    let result = simulate_quote(&env, &escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::OrderExpired);
+}
+
+#[tokio::test]
+async fn does_not_quote_amount_above_escrow_balance() {
+    let (env, escrow) = setup().await;
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT / 2)
+        .await
+        .unwrap();
+
+    let result = simulate_quote(&env, &escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, FusionError::NotEnoughTokensInEscrow);
+}
+
+#[tokio::test]
+async fn does_not_quote_zero_amount() {
+    let (env, escrow) = setup().await;
+
+    let result = simulate_quote(&env, &escrow.order, 0).await;
+    assert_error(result, FusionError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn does_not_quote_order_with_other_config() {
+    let (env, escrow) = setup().await;
+    let mut order = escrow.order.clone();
+    order.config.min_dst_amount += 1;
+
+    // Accounts of the escrowed order with the changed config in the instruction data
+    let mut instruction = fusion_swap_ix::quote(escrow.order, DEFAULT_SRC_AMOUNT).unwrap();
+    instruction.data = fusion_swap_ix::quote(order, DEFAULT_SRC_AMOUNT)
+        .unwrap()
+        .data;
+    let result = env.simulate(&[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn does_not_quote_filled_order() {
+    let (env, escrow) = setup().await;
+    env.fill(&escrow.order, DEFAULT_SRC_AMOUNT).await.unwrap();
+
+    let result = simulate_quote(&env, &escrow.order, DEFAULT_SRC_AMOUNT).await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+}
@@ -0,0 +1,1604 @@
+use anchor_lang::{error::ErrorCode, prelude::Pubkey, AccountDeserialize, Discriminator, Space};
+use fusion_integration_tests::{
+    assert_error, events, registration, sbf, TestEnv, ACCESS_VALID_FROM, START_TIME,
+};
+use fusion_swap_client::{
+    pda, whitelist as whitelist_ix, Registration, ResolverAccess, WhitelistState,
+};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    instruction::AccountMeta,
+    signature::{Keypair, Signer},
+};
+use whitelist::{
+    error::WhitelistError,
+    migration::{RESOLVER_ACCESS_VERSION, WHITELIST_STATE_VERSION},
+    permissions,
+    registry::{ResolverPage, MAX_LABEL_LEN, RESOLVER_PAGE_SIZE},
+    role::{Role, MAX_ROLE_MEMBERS},
+    AuthorityTransferCancelled, AuthorityTransferProposed, AuthorityTransferred, DelegateAdded,
+    DelegateRemoved, PermissionsUpdated, ResolverDelegate, ResolverReinstated, ResolverRenewed,
+    ResolverRootUpdated, ResolverStatus, ResolverSuspended, RoleGranted, RoleRevoked,
+    RESOLVER_ACCESS_SEED,
+};
+
+async fn resolver_access(env: &TestEnv, user: &Pubkey) -> Option<ResolverAccess> {
+    env.account(&pda::resolver_access(user))
+        .await
+        .map(|account| ResolverAccess::try_deserialize(&mut &account.data[..]).unwrap())
+}
+
+async fn whitelist_state(env: &TestEnv) -> WhitelistState {
+    let account = env.account(&pda::whitelist_state()).await.unwrap();
+    WhitelistState::try_deserialize(&mut &account.data[..]).unwrap()
+}
+
+async fn resolver_page(env: &TestEnv, page: u32) -> Vec<Pubkey> {
+    let account = env.account(&pda::resolver_page(page)).await.unwrap();
+    ResolverPage::try_deserialize(&mut &account.data[..])
+        .unwrap()
+        .resolvers
+}
+
+async fn register_with_label(
+    env: &TestEnv,
+    user: &Pubkey,
+    label: &str,
+) -> Result<(), BanksClientError> {
+    env.send(
+        &[whitelist_ix::register(
+            env.payer().pubkey(),
+            env.payer().pubkey(),
+            Registration {
+                label: label.to_string(),
+                ..registration(*user)
+            },
+            env.current_page().await,
+        )],
+        &[],
+    )
+    .await
+}
+
+async fn propose_authority(env: &TestEnv, new_authority: &Pubkey) {
+    env.send(
+        &[whitelist_ix::propose_authority(
+            env.payer().pubkey(),
+            *new_authority,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+async fn grant_role(env: &TestEnv, authority: &Keypair, role: Role, user: &Pubkey) {
+    env.send(
+        &[whitelist_ix::grant_role(authority.pubkey(), role, *user)],
+        &[authority],
+    )
+    .await
+    .unwrap();
+}
+
+async fn revoke_role(env: &TestEnv, authority: &Keypair, role: Role, user: &Pubkey) {
+    env.send(
+        &[whitelist_ix::revoke_role(authority.pubkey(), role, *user)],
+        &[authority],
+    )
+    .await
+    .unwrap();
+}
+
+async fn suspend(
+    env: &TestEnv,
+    authority: &Keypair,
+    user: &Pubkey,
+) -> Result<(), BanksClientError> {
+    env.send(
+        &[whitelist_ix::suspend(authority.pubkey(), *user)],
+        &[authority],
+    )
+    .await
+}
+
+/// Proposes `new_authority` by the payer and accepts it
+async fn transfer_authority(env: &TestEnv, new_authority: &Keypair) {
+    propose_authority(env, &new_authority.pubkey()).await;
+    env.send(
+        &[whitelist_ix::accept_authority(new_authority.pubkey())],
+        &[new_authority],
+    )
+    .await
+    .unwrap();
+}
+
+/// Replaces the whitelist state with the one of the payer in the layout before versioning
+async fn set_whitelist_state_v0(env: &mut TestEnv) {
+    let mut data = WhitelistState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(env.payer().pubkey().as_ref());
+    env.set_account(&pda::whitelist_state(), &whitelist::ID, data)
+        .await;
+}
+
+/// Replaces the access of `user` with one in the layout before versioning
+async fn set_resolver_access_v0(env: &mut TestEnv, user: &Pubkey) {
+    let (_, bump) =
+        Pubkey::find_program_address(&[RESOLVER_ACCESS_SEED, user.as_ref()], &whitelist::ID);
+    let mut data = ResolverAccess::DISCRIMINATOR.to_vec();
+    data.push(bump);
+    env.set_account(&pda::resolver_access(user), &whitelist::ID, data)
+        .await;
+}
+
+async fn migrate_resolver_access(
+    env: &TestEnv,
+    authority: &Keypair,
+    user: &Pubkey,
+) -> Result<(), BanksClientError> {
+    env.send(
+        &[whitelist_ix::migrate_resolver_access(
+            authority.pubkey(),
+            env.payer().pubkey(),
+            *user,
+            env.current_page().await,
+        )],
+        &[authority],
+    )
+    .await
+}
+
+/// User to whitelist and the authority the whitelist is transferred to
+async fn setup() -> (TestEnv, Keypair, Keypair) {
+    let env = TestEnv::new().await;
+    let user = Keypair::new();
+    let new_authority = env.dave.insecure_clone();
+    (env, user, new_authority)
+}
+
+#[tokio::test]
+async fn registers_and_deregisters_user() {
+    let (env, user, _) = setup().await;
+
+    env.register(&user.pubkey()).await;
+    assert!(resolver_access(&env, &user.pubkey()).await.is_some());
+
+    env.deregister(&user.pubkey()).await;
+    assert!(resolver_access(&env, &user.pubkey()).await.is_none());
+}
+
+#[tokio::test]
+async fn stores_canonical_bump() {
+    let (env, user, _) = setup().await;
+    let (_, canonical_bump) = Pubkey::find_program_address(
+        &[RESOLVER_ACCESS_SEED, user.pubkey().as_ref()],
+        &whitelist::ID,
+    );
+
+    env.register(&user.pubkey()).await;
+    assert_eq!(
+        resolver_access(&env, &user.pubkey()).await.unwrap().bump,
+        canonical_bump
+    );
+}
+
+#[tokio::test]
+async fn stores_access_period() {
+    let (env, user, _) = setup().await;
+
+    env.send(
+        &[whitelist_ix::register(
+            env.payer().pubkey(),
+            env.payer().pubkey(),
+            Registration {
+                valid_from: START_TIME,
+                valid_until: START_TIME + 1_000,
+                ..registration(user.pubkey())
+            },
+            env.current_page().await,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.valid_from, START_TIME);
+    assert_eq!(access.valid_until, START_TIME + 1_000);
+    assert_eq!(access.status, ResolverStatus::Active);
+    assert_eq!(access.permissions, permissions::ALL);
+}
+
+#[tokio::test]
+async fn stores_registry_entry() {
+    let (env, user, _) = setup().await;
+
+    register_with_label(&env, &user.pubkey(), "resolver one")
+        .await
+        .unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.resolver, user.pubkey());
+    assert_eq!(access.label, "resolver one");
+    assert_eq!(access.registered_at, START_TIME);
+    assert_eq!(access.registry_page, 0);
+}
+
+#[tokio::test]
+async fn cannot_register_with_too_long_label() {
+    let (env, user, _) = setup().await;
+
+    let result = register_with_label(&env, &user.pubkey(), &"a".repeat(MAX_LABEL_LEN + 1)).await;
+    assert_error(result, WhitelistError::LabelTooLong);
+
+    register_with_label(&env, &user.pubkey(), &"a".repeat(MAX_LABEL_LEN))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn lists_registered_users() {
+    let (env, user, _) = setup().await;
+
+    env.register(&user.pubkey()).await;
+    assert_eq!(
+        resolver_page(&env, 0).await,
+        vec![env.bob.pubkey(), user.pubkey()]
+    );
+    assert_eq!(whitelist_state(&env).await.resolver_count, 2);
+}
+
+#[tokio::test]
+async fn deregister_removes_user_from_registry() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    env.deregister(&env.bob.pubkey()).await;
+    assert_eq!(resolver_page(&env, 0).await, vec![user.pubkey()]);
+    assert_eq!(whitelist_state(&env).await.resolver_count, 1);
+}
+
+#[tokio::test]
+async fn registers_users_to_next_page_once_full() {
+    let (env, user, _) = setup().await;
+    // The first page already holds bob
+    for _ in 1..RESOLVER_PAGE_SIZE {
+        env.register(&Pubkey::new_unique()).await;
+    }
+    assert_eq!(resolver_page(&env, 0).await.len(), RESOLVER_PAGE_SIZE);
+    assert_eq!(whitelist_state(&env).await.current_page, 1);
+
+    env.register(&user.pubkey()).await;
+    assert_eq!(resolver_page(&env, 1).await, vec![user.pubkey()]);
+    assert_eq!(env.registry_page(&user.pubkey()).await, 1);
+    assert_eq!(
+        whitelist_state(&env).await.resolver_count,
+        RESOLVER_PAGE_SIZE as u32 + 1
+    );
+
+    // Freed slots of a full page are not reused
+    env.deregister(&env.bob.pubkey()).await;
+    env.deregister(&user.pubkey()).await;
+    assert_eq!(resolver_page(&env, 0).await.len(), RESOLVER_PAGE_SIZE - 1);
+    assert!(resolver_page(&env, 1).await.is_empty());
+    assert_eq!(whitelist_state(&env).await.current_page, 1);
+}
+
+#[tokio::test]
+async fn refunds_rent_to_payer() {
+    let (env, user, _) = setup().await;
+    let payer_lamports = env.lamports(&env.charlie.pubkey()).await;
+
+    env.send(
+        &[whitelist_ix::register(
+            env.payer().pubkey(),
+            env.charlie.pubkey(),
+            registration(user.pubkey()),
+            env.current_page().await,
+        )],
+        &[&env.charlie],
+    )
+    .await
+    .unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.payer, env.charlie.pubkey());
+    assert!(env.lamports(&env.charlie.pubkey()).await < payer_lamports);
+
+    env.send(
+        &[whitelist_ix::deregister(
+            env.payer().pubkey(),
+            env.charlie.pubkey(),
+            user.pubkey(),
+            access.registry_page,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.lamports(&env.charlie.pubkey()).await, payer_lamports);
+}
+
+#[tokio::test]
+async fn cannot_refund_rent_to_other_account() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::deregister(
+                env.payer().pubkey(),
+                env.charlie.pubkey(),
+                user.pubkey(),
+                env.registry_page(&user.pubkey()).await,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidPayer);
+}
+
+#[tokio::test]
+async fn registers_and_deregisters_many_users() {
+    let (env, _, _) = setup().await;
+    // The batch fills up the first page, which already holds bob, and goes on to the next one
+    let users: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    for _ in 3..RESOLVER_PAGE_SIZE {
+        env.register(&Pubkey::new_unique()).await;
+    }
+
+    env.send(
+        &[whitelist_ix::register_many(
+            env.payer().pubkey(),
+            env.charlie.pubkey(),
+            users.iter().copied().map(registration).collect(),
+            env.current_page().await,
+            resolver_page(&env, env.current_page().await).await.len(),
+        )],
+        &[&env.charlie],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        resolver_page(&env, 0).await[RESOLVER_PAGE_SIZE - 2..],
+        users[..2]
+    );
+    assert_eq!(resolver_page(&env, 1).await, users[2..]);
+    let mut accesses = vec![];
+    for user in &users {
+        let access = resolver_access(&env, user).await.unwrap();
+        assert_eq!(access.payer, env.charlie.pubkey());
+        accesses.push(access);
+    }
+
+    let payer_lamports = env.lamports(&env.charlie.pubkey()).await;
+    let resolver_count = whitelist_state(&env).await.resolver_count;
+    env.send(
+        &[whitelist_ix::deregister_many(
+            env.payer().pubkey(),
+            &accesses,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    for user in &users {
+        assert!(resolver_access(&env, user).await.is_none());
+    }
+    assert!(resolver_page(&env, 1).await.is_empty());
+    assert_eq!(
+        whitelist_state(&env).await.resolver_count,
+        resolver_count - users.len() as u32
+    );
+    assert!(env.lamports(&env.charlie.pubkey()).await > payer_lamports);
+}
+
+#[tokio::test]
+async fn cannot_register_many_with_inconsistent_accounts() {
+    let (env, user, _) = setup().await;
+    let register_many = whitelist_ix::register_many(
+        env.payer().pubkey(),
+        env.payer().pubkey(),
+        vec![registration(user.pubkey())],
+        env.current_page().await,
+        resolver_page(&env, env.current_page().await).await.len(),
+    );
+
+    let mut missing_account = register_many.clone();
+    missing_account.accounts.pop();
+    let result = env.send(&[missing_account], &[]).await;
+    assert_error(result, WhitelistError::InconsistentBatchAccounts);
+
+    // The operator registers the user in the group, not the signer of the batch
+    let mut other_authority = register_many;
+    other_authority.accounts[0] = AccountMeta::new_readonly(env.charlie.pubkey(), true);
+    let result = env.send(&[other_authority], &[&env.charlie]).await;
+    assert_error(result, WhitelistError::InconsistentBatchAccounts);
+}
+
+#[tokio::test]
+async fn non_operator_cannot_deregister_many_users() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+
+    let result = env
+        .send(
+            &[whitelist_ix::deregister_many(
+                env.charlie.pubkey(),
+                &[access],
+            )],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_register_with_unknown_permissions() {
+    let (env, user, _) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::register(
+                env.payer().pubkey(),
+                env.payer().pubkey(),
+                Registration {
+                    permissions: permissions::ALL + 1,
+                    ..registration(user.pubkey())
+                },
+                env.current_page().await,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidPermissions);
+}
+
+#[tokio::test]
+async fn updates_permissions() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let logs = env
+        .send_with_logs(
+            &[whitelist_ix::update_permissions(
+                env.payer().pubkey(),
+                user.pubkey(),
+                permissions::CANCEL,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.permissions, permissions::CANCEL);
+
+    if sbf() {
+        let events = events::<PermissionsUpdated>(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].user, user.pubkey());
+        assert_eq!(events[0].permissions, permissions::CANCEL);
+    }
+}
+
+#[tokio::test]
+async fn cannot_update_to_unknown_permissions() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::update_permissions(
+                env.payer().pubkey(),
+                user.pubkey(),
+                1 << 7,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidPermissions);
+}
+
+#[tokio::test]
+async fn non_operator_cannot_update_permissions() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::update_permissions(
+                user.pubkey(),
+                user.pubkey(),
+                permissions::ALL,
+            )],
+            &[&user],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_register_with_empty_access_period() {
+    let (env, user, _) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::register(
+                env.payer().pubkey(),
+                env.payer().pubkey(),
+                Registration {
+                    valid_from: START_TIME,
+                    valid_until: START_TIME,
+                    ..registration(user.pubkey())
+                },
+                env.current_page().await,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidAccessPeriod);
+}
+
+#[tokio::test]
+async fn renews_access() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    env.renew(&user.pubkey(), START_TIME + 1_000).await;
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.valid_from, ACCESS_VALID_FROM);
+    assert_eq!(access.valid_until, START_TIME + 1_000);
+}
+
+#[tokio::test]
+async fn emits_renewal_event() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let logs = env
+        .send_with_logs(
+            &[whitelist_ix::renew(
+                env.payer().pubkey(),
+                user.pubkey(),
+                START_TIME + 1_000,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    if sbf() {
+        let renewed = events::<ResolverRenewed>(&logs);
+        assert_eq!(renewed.len(), 1);
+        assert_eq!(renewed[0].user, user.pubkey());
+        assert_eq!(renewed[0].expires_at, START_TIME + 1_000);
+    }
+}
+
+#[tokio::test]
+async fn cannot_renew_access_until_before_valid_from() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::renew(
+                env.payer().pubkey(),
+                user.pubkey(),
+                ACCESS_VALID_FROM,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::InvalidAccessPeriod);
+}
+
+#[tokio::test]
+async fn non_operator_cannot_renew_access() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::renew(
+                user.pubkey(),
+                user.pubkey(),
+                START_TIME + 1_000,
+            )],
+            &[&user],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn suspends_and_reinstates_user() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    suspend(&env, env.payer(), &user.pubkey()).await.unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.status, ResolverStatus::Suspended);
+
+    env.send(
+        &[whitelist_ix::reinstate(env.payer().pubkey(), user.pubkey())],
+        &[],
+    )
+    .await
+    .unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.status, ResolverStatus::Active);
+}
+
+#[tokio::test]
+async fn guardian_suspends_user() {
+    let (env, user, guardian) = setup().await;
+    env.register(&user.pubkey()).await;
+    grant_role(&env, env.payer(), Role::Guardian, &guardian.pubkey()).await;
+
+    suspend(&env, &guardian, &user.pubkey()).await.unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.status, ResolverStatus::Suspended);
+}
+
+#[tokio::test]
+async fn guardian_cannot_reinstate_user() {
+    let (env, user, guardian) = setup().await;
+    env.register(&user.pubkey()).await;
+    grant_role(&env, env.payer(), Role::Guardian, &guardian.pubkey()).await;
+    suspend(&env, &guardian, &user.pubkey()).await.unwrap();
+
+    let result = env
+        .send(
+            &[whitelist_ix::reinstate(guardian.pubkey(), user.pubkey())],
+            &[&guardian],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_suspend_without_role() {
+    let (env, user, random_user) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = suspend(&env, &random_user, &user.pubkey()).await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_suspend_suspended_user() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+    suspend(&env, env.payer(), &user.pubkey()).await.unwrap();
+
+    let result = suspend(&env, env.payer(), &user.pubkey()).await;
+    assert_error(result, WhitelistError::ResolverAlreadySuspended);
+}
+
+#[tokio::test]
+async fn cannot_reinstate_active_user() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::reinstate(env.payer().pubkey(), user.pubkey())],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::ResolverNotSuspended);
+}
+
+#[tokio::test]
+async fn emits_suspension_events() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let suspended = env
+        .send_with_logs(
+            &[whitelist_ix::suspend(env.payer().pubkey(), user.pubkey())],
+            &[],
+        )
+        .await
+        .unwrap();
+    let reinstated = env
+        .send_with_logs(
+            &[whitelist_ix::reinstate(env.payer().pubkey(), user.pubkey())],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    if sbf() {
+        let suspended = events::<ResolverSuspended>(&suspended);
+        assert_eq!(suspended.len(), 1);
+        assert_eq!(suspended[0].user, user.pubkey());
+
+        let reinstated = events::<ResolverReinstated>(&reinstated);
+        assert_eq!(reinstated.len(), 1);
+        assert_eq!(reinstated[0].user, user.pubkey());
+    }
+}
+
+#[tokio::test]
+async fn adds_and_removes_delegate() {
+    let (env, _, _) = setup().await;
+    let delegate = Keypair::new();
+
+    env.add_delegate(&delegate).await;
+    let account = env
+        .account(&pda::resolver_delegate(&delegate.pubkey()))
+        .await
+        .unwrap();
+    let resolver_delegate = ResolverDelegate::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(resolver_delegate.resolver, env.bob.pubkey());
+    let access = resolver_access(&env, &env.bob.pubkey()).await.unwrap();
+    assert_eq!(resolver_delegate.generation, access.generation);
+
+    env.send(
+        &[whitelist_ix::remove_delegate(
+            env.bob.pubkey(),
+            delegate.pubkey(),
+        )],
+        &[&env.bob],
+    )
+    .await
+    .unwrap();
+    assert!(env
+        .account(&pda::resolver_delegate(&delegate.pubkey()))
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn delegate_must_sign_to_be_added() {
+    let (env, _, _) = setup().await;
+    let delegate = Keypair::new();
+    let mut instruction = whitelist_ix::add_delegate(env.bob.pubkey(), delegate.pubkey());
+    instruction.accounts[1].is_signer = false;
+
+    let result = env.send(&[instruction], &[&env.bob]).await;
+    assert_error(result, ErrorCode::AccountNotSigner);
+}
+
+#[tokio::test]
+async fn unregistered_user_cannot_add_delegate() {
+    let (env, _, _) = setup().await;
+    let delegate = Keypair::new();
+
+    let result = env
+        .send(
+            &[whitelist_ix::add_delegate(
+                env.charlie.pubkey(),
+                delegate.pubkey(),
+            )],
+            &[&env.charlie, &delegate],
+        )
+        .await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+}
+
+#[tokio::test]
+async fn cannot_add_delegate_of_other_resolver() {
+    let (env, _, _) = setup().await;
+    let delegate = Keypair::new();
+    env.add_delegate(&delegate).await;
+    env.register(&env.charlie.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::add_delegate(
+                env.charlie.pubkey(),
+                delegate.pubkey(),
+            )],
+            &[&env.charlie, &delegate],
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn only_resolver_can_remove_delegate() {
+    let (env, _, _) = setup().await;
+    let delegate = Keypair::new();
+    env.add_delegate(&delegate).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::remove_delegate(
+                env.charlie.pubkey(),
+                delegate.pubkey(),
+            )],
+            &[&env.charlie],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn registers_every_access_as_new_generation() {
+    let (env, user, _) = setup().await;
+    let bob_generation = resolver_access(&env, &env.bob.pubkey())
+        .await
+        .unwrap()
+        .generation;
+
+    env.register(&user.pubkey()).await;
+    let first = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(first.generation, bob_generation + 1);
+
+    env.deregister(&user.pubkey()).await;
+    env.register(&user.pubkey()).await;
+    let second = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(second.generation, bob_generation + 2);
+    assert_eq!(
+        whitelist_state(&env).await.registrations,
+        bob_generation + 2
+    );
+}
+
+#[tokio::test]
+async fn sets_and_clears_resolver_root() {
+    let (env, _, _) = setup().await;
+    assert_eq!(whitelist_state(&env).await.resolver_root, None);
+
+    let logs = env
+        .send_with_logs(
+            &[whitelist_ix::set_resolver_root(
+                env.payer().pubkey(),
+                Some([1; 32]),
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(whitelist_state(&env).await.resolver_root, Some([1; 32]));
+    if sbf() {
+        let events = events::<ResolverRootUpdated>(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].root, Some([1; 32]));
+    }
+
+    env.set_resolver_root(None).await;
+    assert_eq!(whitelist_state(&env).await.resolver_root, None);
+}
+
+#[tokio::test]
+async fn only_authority_can_set_resolver_root() {
+    let (env, _, operator) = setup().await;
+    grant_role(&env, env.payer(), Role::Operator, &operator.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::set_resolver_root(
+                operator.pubkey(),
+                Some([1; 32]),
+            )],
+            &[&operator],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn emits_delegate_events() {
+    let (env, _, _) = setup().await;
+    let delegate = Keypair::new();
+
+    let added = env
+        .send_with_logs(
+            &[whitelist_ix::add_delegate(
+                env.bob.pubkey(),
+                delegate.pubkey(),
+            )],
+            &[&env.bob, &delegate],
+        )
+        .await
+        .unwrap();
+    let removed = env
+        .send_with_logs(
+            &[whitelist_ix::remove_delegate(
+                env.bob.pubkey(),
+                delegate.pubkey(),
+            )],
+            &[&env.bob],
+        )
+        .await
+        .unwrap();
+
+    if sbf() {
+        let added = events::<DelegateAdded>(&added);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].resolver, env.bob.pubkey());
+        assert_eq!(added[0].delegate, delegate.pubkey());
+
+        let removed = events::<DelegateRemoved>(&removed);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].resolver, env.bob.pubkey());
+        assert_eq!(removed[0].delegate, delegate.pubkey());
+    }
+}
+
+#[tokio::test]
+async fn cannot_register_same_user_twice() {
+    let (env, user, _) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::register(
+                env.payer().pubkey(),
+                env.payer().pubkey(),
+                registration(user.pubkey()),
+                env.current_page().await,
+            )],
+            &[],
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn proposes_authority_without_changing_it() {
+    let (env, _, new_authority) = setup().await;
+
+    propose_authority(&env, &new_authority.pubkey()).await;
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.authority, env.payer().pubkey());
+    assert_eq!(state.pending_authority, Some(new_authority.pubkey()));
+}
+
+#[tokio::test]
+async fn accepts_proposed_authority() {
+    let (env, _, new_authority) = setup().await;
+
+    transfer_authority(&env, &new_authority).await;
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.authority, new_authority.pubkey());
+    assert_eq!(state.pending_authority, None);
+}
+
+#[tokio::test]
+async fn new_authority_manages_roles() {
+    let (env, user, new_authority) = setup().await;
+    transfer_authority(&env, &new_authority).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::grant_role(
+                env.payer().pubkey(),
+                Role::Operator,
+                user.pubkey(),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+
+    grant_role(
+        &env,
+        &new_authority,
+        Role::Operator,
+        &new_authority.pubkey(),
+    )
+    .await;
+    env.send(
+        &[whitelist_ix::register(
+            new_authority.pubkey(),
+            env.payer().pubkey(),
+            registration(user.pubkey()),
+            env.current_page().await,
+        )],
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+    assert!(resolver_access(&env, &user.pubkey()).await.is_some());
+}
+
+#[tokio::test]
+async fn cannot_register_with_wrong_authority() {
+    let (env, user, new_authority) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::register(
+                new_authority.pubkey(),
+                env.payer().pubkey(),
+                registration(user.pubkey()),
+                env.current_page().await,
+            )],
+            &[&new_authority],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_deregister_with_wrong_authority() {
+    let (env, user, new_authority) = setup().await;
+    env.register(&user.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::deregister(
+                new_authority.pubkey(),
+                env.payer().pubkey(),
+                user.pubkey(),
+                env.registry_page(&user.pubkey()).await,
+            )],
+            &[&new_authority],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn initial_authority_is_operator() {
+    let (env, _, _) = setup().await;
+
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.operators, [env.payer().pubkey()]);
+    assert!(state.guardians.is_empty());
+}
+
+#[tokio::test]
+async fn grants_and_revokes_roles() {
+    let (env, user, _) = setup().await;
+    let payer = env.payer().insecure_clone();
+
+    grant_role(&env, &payer, Role::Guardian, &user.pubkey()).await;
+    grant_role(&env, &payer, Role::Operator, &user.pubkey()).await;
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.operators, [payer.pubkey(), user.pubkey()]);
+    assert_eq!(state.guardians, [user.pubkey()]);
+
+    revoke_role(&env, &payer, Role::Operator, &payer.pubkey()).await;
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.operators, [user.pubkey()]);
+    assert_eq!(state.guardians, [user.pubkey()]);
+}
+
+#[tokio::test]
+async fn operator_registers_and_deregisters_users() {
+    let (env, user, operator) = setup().await;
+    grant_role(&env, env.payer(), Role::Operator, &operator.pubkey()).await;
+
+    env.send(
+        &[whitelist_ix::register(
+            operator.pubkey(),
+            env.payer().pubkey(),
+            registration(user.pubkey()),
+            env.current_page().await,
+        )],
+        &[&operator],
+    )
+    .await
+    .unwrap();
+    assert!(resolver_access(&env, &user.pubkey()).await.is_some());
+
+    env.send(
+        &[whitelist_ix::deregister(
+            operator.pubkey(),
+            env.payer().pubkey(),
+            user.pubkey(),
+            env.registry_page(&user.pubkey()).await,
+        )],
+        &[&operator],
+    )
+    .await
+    .unwrap();
+    assert!(resolver_access(&env, &user.pubkey()).await.is_none());
+}
+
+#[tokio::test]
+async fn guardian_cannot_register_users() {
+    let (env, user, guardian) = setup().await;
+    grant_role(&env, env.payer(), Role::Guardian, &guardian.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::register(
+                guardian.pubkey(),
+                env.payer().pubkey(),
+                registration(user.pubkey()),
+                env.current_page().await,
+            )],
+            &[&guardian],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn revoked_operator_cannot_register_or_deregister_users() {
+    let (env, user, operator) = setup().await;
+    let payer = env.payer().insecure_clone();
+    grant_role(&env, &payer, Role::Operator, &operator.pubkey()).await;
+    revoke_role(&env, &payer, Role::Operator, &payer.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::register(
+                payer.pubkey(),
+                env.payer().pubkey(),
+                registration(user.pubkey()),
+                env.current_page().await,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+
+    env.send(
+        &[whitelist_ix::register(
+            operator.pubkey(),
+            env.payer().pubkey(),
+            registration(user.pubkey()),
+            env.current_page().await,
+        )],
+        &[&operator],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .send(
+            &[whitelist_ix::deregister(
+                payer.pubkey(),
+                env.payer().pubkey(),
+                user.pubkey(),
+                env.registry_page(&user.pubkey()).await,
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn operator_cannot_grant_or_revoke_roles() {
+    let (env, user, operator) = setup().await;
+    grant_role(&env, env.payer(), Role::Operator, &operator.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::grant_role(
+                operator.pubkey(),
+                Role::Operator,
+                user.pubkey(),
+            )],
+            &[&operator],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+
+    let result = env
+        .send(
+            &[whitelist_ix::revoke_role(
+                operator.pubkey(),
+                Role::Operator,
+                env.payer().pubkey(),
+            )],
+            &[&operator],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_grant_role_twice() {
+    let (env, _, _) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::grant_role(
+                env.payer().pubkey(),
+                Role::Operator,
+                env.payer().pubkey(),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::RoleAlreadyGranted);
+}
+
+#[tokio::test]
+async fn cannot_revoke_role_not_granted() {
+    let (env, user, _) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::revoke_role(
+                env.payer().pubkey(),
+                Role::Guardian,
+                user.pubkey(),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::RoleNotGranted);
+}
+
+#[tokio::test]
+async fn cannot_grant_role_to_more_than_max_members() {
+    let (env, _, _) = setup().await;
+    for _ in 0..MAX_ROLE_MEMBERS {
+        grant_role(&env, env.payer(), Role::Guardian, &Pubkey::new_unique()).await;
+    }
+
+    let result = env
+        .send(
+            &[whitelist_ix::grant_role(
+                env.payer().pubkey(),
+                Role::Guardian,
+                Pubkey::new_unique(),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::TooManyRoleMembers);
+}
+
+#[tokio::test]
+async fn emits_role_events() {
+    let (env, user, _) = setup().await;
+    let authority = env.payer().pubkey();
+
+    let granted = env
+        .send_with_logs(
+            &[whitelist_ix::grant_role(
+                authority,
+                Role::Guardian,
+                user.pubkey(),
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    let revoked = env
+        .send_with_logs(
+            &[whitelist_ix::revoke_role(
+                authority,
+                Role::Guardian,
+                user.pubkey(),
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    if sbf() {
+        let granted = events::<RoleGranted>(&granted);
+        assert_eq!(granted.len(), 1);
+        assert_eq!(granted[0].role, Role::Guardian);
+        assert_eq!(granted[0].user, user.pubkey());
+
+        let revoked = events::<RoleRevoked>(&revoked);
+        assert_eq!(revoked.len(), 1);
+        assert_eq!(revoked[0].role, Role::Guardian);
+        assert_eq!(revoked[0].user, user.pubkey());
+    }
+}
+
+#[tokio::test]
+async fn previous_authority_cannot_propose_authority() {
+    let (env, _, new_authority) = setup().await;
+    transfer_authority(&env, &new_authority).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::propose_authority(
+                env.payer().pubkey(),
+                env.payer().pubkey(),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn non_authority_cannot_propose_authority() {
+    let (env, _, new_authority) = setup().await;
+    let random_user = env.charlie.insecure_clone();
+
+    let result = env
+        .send(
+            &[whitelist_ix::propose_authority(
+                random_user.pubkey(),
+                new_authority.pubkey(),
+            )],
+            &[&random_user],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn only_proposed_authority_can_accept() {
+    let (env, _, new_authority) = setup().await;
+    let random_user = env.charlie.insecure_clone();
+    propose_authority(&env, &new_authority.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::accept_authority(random_user.pubkey())],
+            &[&random_user],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_accept_without_proposal() {
+    let (env, _, new_authority) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::accept_authority(new_authority.pubkey())],
+            &[&new_authority],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn new_proposal_replaces_pending_authority() {
+    let (env, _, new_authority) = setup().await;
+    let other_authority = env.charlie.insecure_clone();
+    propose_authority(&env, &new_authority.pubkey()).await;
+    propose_authority(&env, &other_authority.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::accept_authority(new_authority.pubkey())],
+            &[&new_authority],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+
+    env.send(
+        &[whitelist_ix::accept_authority(other_authority.pubkey())],
+        &[&other_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        whitelist_state(&env).await.authority,
+        other_authority.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn cancels_authority_transfer() {
+    let (env, _, new_authority) = setup().await;
+    propose_authority(&env, &new_authority.pubkey()).await;
+
+    env.send(
+        &[whitelist_ix::cancel_authority_transfer(
+            env.payer().pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.authority, env.payer().pubkey());
+    assert_eq!(state.pending_authority, None);
+
+    let result = env
+        .send(
+            &[whitelist_ix::accept_authority(new_authority.pubkey())],
+            &[&new_authority],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_cancel_without_pending_authority() {
+    let (env, _, _) = setup().await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::cancel_authority_transfer(
+                env.payer().pubkey(),
+            )],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::NoPendingAuthority);
+}
+
+#[tokio::test]
+async fn non_authority_cannot_cancel_authority_transfer() {
+    let (env, _, new_authority) = setup().await;
+    propose_authority(&env, &new_authority.pubkey()).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::cancel_authority_transfer(
+                new_authority.pubkey(),
+            )],
+            &[&new_authority],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn emits_authority_transfer_events() {
+    let (env, _, new_authority) = setup().await;
+    let authority = env.payer().pubkey();
+    let propose = whitelist_ix::propose_authority(authority, new_authority.pubkey());
+
+    let proposed = env.send_with_logs(&[propose.clone()], &[]).await.unwrap();
+    let cancelled = env
+        .send_with_logs(&[whitelist_ix::cancel_authority_transfer(authority)], &[])
+        .await
+        .unwrap();
+    env.send(&[propose], &[]).await.unwrap();
+    let transferred = env
+        .send_with_logs(
+            &[whitelist_ix::accept_authority(new_authority.pubkey())],
+            &[&new_authority],
+        )
+        .await
+        .unwrap();
+
+    if sbf() {
+        let proposed = events::<AuthorityTransferProposed>(&proposed);
+        assert_eq!(proposed.len(), 1);
+        assert_eq!(proposed[0].authority, authority);
+        assert_eq!(proposed[0].pending_authority, new_authority.pubkey());
+
+        let cancelled = events::<AuthorityTransferCancelled>(&cancelled);
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].authority, authority);
+        assert_eq!(cancelled[0].pending_authority, new_authority.pubkey());
+
+        let transferred = events::<AuthorityTransferred>(&transferred);
+        assert_eq!(transferred.len(), 1);
+        assert_eq!(transferred[0].previous_authority, authority);
+        assert_eq!(transferred[0].new_authority, new_authority.pubkey());
+    }
+}
+
+#[tokio::test]
+async fn migrates_whitelist_state_from_layout_before_versioning() {
+    let (mut env, user, _) = setup().await;
+    set_whitelist_state_v0(&mut env).await;
+    let authority = env.payer().pubkey();
+
+    env.send(&[whitelist_ix::migrate_whitelist_state(authority)], &[])
+        .await
+        .unwrap();
+    let state = whitelist_state(&env).await;
+    assert_eq!(state.version, WHITELIST_STATE_VERSION);
+    assert_eq!(state.authority, authority);
+    assert_eq!(state.pending_authority, None);
+    assert_eq!(state.operators, vec![authority]);
+    assert!(state.guardians.is_empty());
+    assert_eq!(state.resolver_root, None);
+    assert_eq!(state.resolver_count, 0);
+    assert_eq!(state.current_page, 0);
+    assert_eq!(state.bond_mint, None);
+    assert_eq!(state.min_bond, 0);
+    assert_eq!(state.unbonding_period, 0);
+
+    let space = WhitelistState::DISCRIMINATOR.len() + WhitelistState::INIT_SPACE;
+    let account = env.account(&pda::whitelist_state()).await.unwrap();
+    assert_eq!(account.data.len(), space);
+    assert_eq!(account.lamports, env.minimum_balance(space).await);
+
+    env.register(&user.pubkey()).await;
+    assert!(resolver_access(&env, &user.pubkey()).await.is_some());
+}
+
+#[tokio::test]
+async fn only_authority_migrates_whitelist_state() {
+    let (mut env, _, _) = setup().await;
+    set_whitelist_state_v0(&mut env).await;
+
+    let result = env
+        .send(
+            &[whitelist_ix::migrate_whitelist_state(env.alice.pubkey())],
+            &[&env.alice],
+        )
+        .await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_migrate_whitelist_state_twice() {
+    let (env, _, _) = setup().await;
+    assert_eq!(whitelist_state(&env).await.version, WHITELIST_STATE_VERSION);
+
+    let result = env
+        .send(
+            &[whitelist_ix::migrate_whitelist_state(env.payer().pubkey())],
+            &[],
+        )
+        .await;
+    assert_error(result, WhitelistError::AlreadyMigrated);
+}
+
+#[tokio::test]
+async fn migrates_resolver_access_from_layout_before_versioning() {
+    let (mut env, user, _) = setup().await;
+    set_resolver_access_v0(&mut env, &user.pubkey()).await;
+    let (_, canonical_bump) = Pubkey::find_program_address(
+        &[RESOLVER_ACCESS_SEED, user.pubkey().as_ref()],
+        &whitelist::ID,
+    );
+    let payer = env.payer().insecure_clone();
+
+    migrate_resolver_access(&env, &payer, &user.pubkey())
+        .await
+        .unwrap();
+    let access = resolver_access(&env, &user.pubkey()).await.unwrap();
+    assert_eq!(access.version, RESOLVER_ACCESS_VERSION);
+    assert_eq!(access.bump, canonical_bump);
+    assert_eq!(access.resolver, user.pubkey());
+    assert_eq!(access.payer, payer.pubkey());
+    assert_eq!(access.registered_at, START_TIME);
+    assert_eq!(access.generation, whitelist_state(&env).await.registrations);
+    assert_eq!(access.label, "");
+    assert_eq!(access.registry_page, 0);
+    assert_eq!(access.valid_from, 0);
+    assert_eq!(access.valid_until, i64::MAX);
+    assert_eq!(access.status, ResolverStatus::Active);
+    assert_eq!(access.permissions, permissions::ALL);
+    assert_eq!(
+        resolver_page(&env, 0).await,
+        vec![env.bob.pubkey(), user.pubkey()]
+    );
+    assert_eq!(whitelist_state(&env).await.resolver_count, 2);
+
+    let space = ResolverAccess::DISCRIMINATOR.len() + ResolverAccess::INIT_SPACE;
+    let account = env
+        .account(&pda::resolver_access(&user.pubkey()))
+        .await
+        .unwrap();
+    assert_eq!(account.data.len(), space);
+    assert_eq!(account.lamports, env.minimum_balance(space).await);
+
+    env.deregister(&user.pubkey()).await;
+    assert!(resolver_access(&env, &user.pubkey()).await.is_none());
+    assert_eq!(resolver_page(&env, 0).await, vec![env.bob.pubkey()]);
+}
+
+#[tokio::test]
+async fn resolver_fills_once_access_is_migrated() {
+    let (mut env, _, _) = setup().await;
+    let bob = env.bob.pubkey();
+    env.deregister(&bob).await;
+    set_resolver_access_v0(&mut env, &bob).await;
+    let escrow = env.create_escrow(env.order()).await.unwrap();
+
+    let result = env.fill(&escrow.order, 1).await;
+    assert_error(result, ErrorCode::AccountDidNotDeserialize);
+
+    let payer = env.payer().insecure_clone();
+    migrate_resolver_access(&env, &payer, &bob).await.unwrap();
+    env.fill(&escrow.order, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_operators_migrate_resolver_access() {
+    let (mut env, user, _) = setup().await;
+    set_resolver_access_v0(&mut env, &user.pubkey()).await;
+
+    let result = migrate_resolver_access(&env, &env.alice, &user.pubkey()).await;
+    assert_error(result, WhitelistError::Unauthorized);
+}
+
+#[tokio::test]
+async fn cannot_migrate_resolver_access_twice() {
+    let (env, _, _) = setup().await;
+    let payer = env.payer().insecure_clone();
+    let access = resolver_access(&env, &env.bob.pubkey()).await.unwrap();
+    assert_eq!(access.version, RESOLVER_ACCESS_VERSION);
+
+    let result = migrate_resolver_access(&env, &payer, &env.bob.pubkey()).await;
+    assert_error(result, WhitelistError::AlreadyMigrated);
+}
@@ -1,56 +1,258 @@
+use crate::error::FusionError;
 use anchor_lang::prelude::*;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+/// Maximum number of points in a single auction curve, bounds the compute used at fill time
+pub const MAX_AUCTION_POINTS: usize = 16;
+
+/// Rate bump of an auction curve at a given timestamp
+pub trait AuctionCurve {
+    fn rate_bump(&self, timestamp: u64) -> u64;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct PointAndTimeDelta {
-    rate_bump: u16,
-    time_delta: u16,
+    pub rate_bump: u16,
+    pub time_delta: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum AuctionData {
+    PiecewiseLinear(PiecewiseLinearCurve),
+    ExponentialDecay(ExponentialDecayCurve),
+    Step(StepCurve),
+    LinearWithPlateau(LinearWithPlateauCurve),
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct AuctionData {
+impl AuctionData {
+    /// Checks that the curve is well-formed and starts before the order expires
+    pub fn validate(&self, expiration_time: u32) -> Result<()> {
+        let start_time = match self {
+            AuctionData::PiecewiseLinear(curve) => {
+                validate_points(
+                    curve.initial_rate_bump,
+                    curve.duration,
+                    &curve.points_and_time_deltas,
+                )?;
+                curve.start_time
+            }
+            AuctionData::ExponentialDecay(curve) => curve.start_time,
+            AuctionData::Step(curve) => {
+                validate_points(
+                    curve.initial_rate_bump,
+                    curve.duration,
+                    &curve.points_and_time_deltas,
+                )?;
+                curve.start_time
+            }
+            AuctionData::LinearWithPlateau(curve) => {
+                require!(
+                    curve.plateau_duration <= curve.duration,
+                    FusionError::AuctionPointsExceedDuration
+                );
+                curve.start_time
+            }
+        };
+
+        require!(
+            start_time <= expiration_time,
+            FusionError::AuctionStartsAfterExpiration
+        );
+        Ok(())
+    }
+}
+
+fn validate_points(
+    initial_rate_bump: u16,
+    duration: u32,
+    points_and_time_deltas: &[PointAndTimeDelta],
+) -> Result<()> {
+    require!(
+        points_and_time_deltas.len() <= MAX_AUCTION_POINTS,
+        FusionError::TooManyAuctionPoints
+    );
+
+    let mut current_rate_bump = initial_rate_bump;
+    // Sum of at most MAX_AUCTION_POINTS u16 deltas always fits into u32
+    let mut total_time_delta: u32 = 0;
+    for point_and_time_delta in points_and_time_deltas.iter() {
+        require!(
+            point_and_time_delta.rate_bump <= current_rate_bump,
+            FusionError::IncreasingAuctionRateBump
+        );
+        current_rate_bump = point_and_time_delta.rate_bump;
+        total_time_delta += point_and_time_delta.time_delta as u32;
+    }
+
+    require!(
+        total_time_delta <= duration,
+        FusionError::AuctionPointsExceedDuration
+    );
+    Ok(())
+}
+
+impl AuctionCurve for AuctionData {
+    fn rate_bump(&self, timestamp: u64) -> u64 {
+        match self {
+            AuctionData::PiecewiseLinear(curve) => curve.rate_bump(timestamp),
+            AuctionData::ExponentialDecay(curve) => curve.rate_bump(timestamp),
+            AuctionData::Step(curve) => curve.rate_bump(timestamp),
+            AuctionData::LinearWithPlateau(curve) => curve.rate_bump(timestamp),
+        }
+    }
+}
+
+/// Linear interpolation between points, decreasing to zero at the end of the auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PiecewiseLinearCurve {
     pub start_time: u32,
     pub duration: u32,
     pub initial_rate_bump: u16,
     pub points_and_time_deltas: Vec<PointAndTimeDelta>,
 }
 
-pub fn calculate_rate_bump(timestamp: u64, data: &AuctionData) -> u64 {
-    if timestamp <= data.start_time as u64 {
-        return data.initial_rate_bump as u64;
+impl AuctionCurve for PiecewiseLinearCurve {
+    fn rate_bump(&self, timestamp: u64) -> u64 {
+        if timestamp <= self.start_time as u64 {
+            return self.initial_rate_bump as u64;
+        }
+        let auction_finish_time = self.start_time as u64 + self.duration as u64;
+        if timestamp >= auction_finish_time {
+            return 0;
+        }
+
+        let mut current_rate_bump = self.initial_rate_bump as u64;
+        let mut current_point_time = self.start_time as u64;
+
+        for point_and_time_delta in self.points_and_time_deltas.iter() {
+            let next_rate_bump = point_and_time_delta.rate_bump as u64;
+            let point_time_delta = point_and_time_delta.time_delta as u64;
+            let next_point_time = current_point_time + point_time_delta;
+
+            if timestamp <= next_point_time {
+                // Overflow is not possible because:
+                // 1. current_point_time < timestamp <= next_point_time
+                // 2. timestamp * rate_bump < 2^64
+                // 3. point_time_delta != 0 as this would contradict point 1
+                return ((timestamp - current_point_time) * next_rate_bump
+                    + (next_point_time - timestamp) * current_rate_bump)
+                    / point_time_delta;
+            }
+
+            current_rate_bump = next_rate_bump;
+            current_point_time = next_point_time;
+        }
+
+        // Overflow is not possible because:
+        // 1. timestamp < auction_finish_time
+        // 2. rate_bump * timestamp < 2^64
+        // 3. current_point_time < auction_finish_time as we know that current_point_time < timestamp
+        current_rate_bump * (auction_finish_time - timestamp)
+            / (auction_finish_time - current_point_time)
     }
-    let auction_finish_time = data.start_time as u64 + data.duration as u64;
-    if timestamp >= auction_finish_time {
-        return 0;
+}
+
+/// Rate bump halves every `half_life` seconds, linearly interpolated within each half-life,
+/// and drops to zero at the end of the auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ExponentialDecayCurve {
+    pub start_time: u32,
+    pub duration: u32,
+    pub initial_rate_bump: u16,
+    pub half_life: u32,
+}
+
+impl AuctionCurve for ExponentialDecayCurve {
+    fn rate_bump(&self, timestamp: u64) -> u64 {
+        if timestamp <= self.start_time as u64 {
+            return self.initial_rate_bump as u64;
+        }
+        let auction_finish_time = self.start_time as u64 + self.duration as u64;
+        if timestamp >= auction_finish_time || self.half_life == 0 {
+            return 0;
+        }
+
+        let time_elapsed = timestamp - self.start_time as u64;
+        let half_life = self.half_life as u64;
+        let halvings = time_elapsed / half_life;
+        if halvings >= u16::BITS as u64 {
+            return 0;
+        }
+
+        // Overflow is not possible because:
+        // 1. rate_bump <= initial_rate_bump < 2^16
+        // 2. time_elapsed % half_life < half_life < 2^32
+        // 3. so the product is below 2^48 and 2 * half_life < 2^33 is non-zero
+        let rate_bump = (self.initial_rate_bump as u64) >> halvings;
+        rate_bump - rate_bump * (time_elapsed % half_life) / (2 * half_life)
     }
+}
 
-    let mut current_rate_bump = data.initial_rate_bump as u64;
-    let mut current_point_time = data.start_time as u64;
+/// Rate bump stays constant between points and jumps to the next value at each point,
+/// then drops to zero at the end of the auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct StepCurve {
+    pub start_time: u32,
+    pub duration: u32,
+    pub initial_rate_bump: u16,
+    pub points_and_time_deltas: Vec<PointAndTimeDelta>,
+}
+
+impl AuctionCurve for StepCurve {
+    fn rate_bump(&self, timestamp: u64) -> u64 {
+        if timestamp <= self.start_time as u64 {
+            return self.initial_rate_bump as u64;
+        }
+        let auction_finish_time = self.start_time as u64 + self.duration as u64;
+        if timestamp >= auction_finish_time {
+            return 0;
+        }
 
-    for point_and_time_delta in data.points_and_time_deltas.iter() {
-        let next_rate_bump = point_and_time_delta.rate_bump as u64;
-        let point_time_delta = point_and_time_delta.time_delta as u64;
-        let next_point_time = current_point_time + point_time_delta;
+        let mut current_rate_bump = self.initial_rate_bump as u64;
+        let mut current_point_time = self.start_time as u64;
 
-        if timestamp <= next_point_time {
+        for point_and_time_delta in self.points_and_time_deltas.iter() {
             // Overflow is not possible because:
-            // 1. current_point_time < timestamp <= next_point_time
-            // 2. timestamp * rate_bump < 2^64
-            // 3. point_time_delta != 0 as this would contradict point 1
-            return ((timestamp - current_point_time) * next_rate_bump
-                + (next_point_time - timestamp) * current_rate_bump)
-                / point_time_delta;
+            // 1. current_point_time <= start_time + len * u16::MAX
+            // 2. both start_time and the sum of deltas fit far below 2^64
+            current_point_time += point_and_time_delta.time_delta as u64;
+            if timestamp < current_point_time {
+                break;
+            }
+            current_rate_bump = point_and_time_delta.rate_bump as u64;
         }
 
-        current_rate_bump = next_rate_bump;
-        current_point_time = next_point_time;
+        current_rate_bump
     }
+}
 
-    // Overflow is not possible because:
-    // 1. timestamp < auction_finish_time
-    // 2. rate_bump * timestamp < 2^64
-    // 3. current_point_time < auction_finish_time as we know that current_point_time < timestamp
-    current_rate_bump * (auction_finish_time - timestamp)
-        / (auction_finish_time - current_point_time)
+/// Rate bump stays at the initial value during the exclusive period of `plateau_duration`
+/// seconds, then decreases linearly to zero at the end of the auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LinearWithPlateauCurve {
+    pub start_time: u32,
+    pub duration: u32,
+    pub initial_rate_bump: u16,
+    pub plateau_duration: u32,
+}
+
+impl AuctionCurve for LinearWithPlateauCurve {
+    fn rate_bump(&self, timestamp: u64) -> u64 {
+        let plateau_finish_time = self.start_time as u64 + self.plateau_duration as u64;
+        if timestamp <= plateau_finish_time {
+            return self.initial_rate_bump as u64;
+        }
+        let auction_finish_time = self.start_time as u64 + self.duration as u64;
+        if timestamp >= auction_finish_time {
+            return 0;
+        }
+
+        // Overflow is not possible because:
+        // 1. plateau_finish_time < timestamp < auction_finish_time
+        // 2. auction_finish_time - timestamp < 2^33 and initial_rate_bump < 2^16
+        // 3. auction_finish_time - plateau_finish_time != 0 as this would contradict point 1
+        self.initial_rate_bump as u64 * (auction_finish_time - timestamp)
+            / (auction_finish_time - plateau_finish_time)
+    }
 }
 
 pub fn calculate_premium(
@@ -68,5 +270,282 @@ pub fn calculate_premium(
         return max_cancellation_premium;
     }
 
-    (time_elapsed as u64 * max_cancellation_premium) / auction_duration as u64
+    // Overflow is not possible because:
+    // 1. the product of u32 and u64 values fits into u128
+    // 2. time_elapsed < auction_duration, so the result is below max_cancellation_premium
+    (time_elapsed as u128 * max_cancellation_premium as u128 / auction_duration as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u32 = 1_000;
+
+    fn point(rate_bump: u16, time_delta: u16) -> PointAndTimeDelta {
+        PointAndTimeDelta {
+            rate_bump,
+            time_delta,
+        }
+    }
+
+    fn piecewise_linear(points: Vec<PointAndTimeDelta>) -> AuctionData {
+        AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+            start_time: START,
+            duration: 1_000,
+            initial_rate_bump: 10_000,
+            points_and_time_deltas: points,
+        })
+    }
+
+    fn exponential_decay(half_life: u32) -> AuctionData {
+        AuctionData::ExponentialDecay(ExponentialDecayCurve {
+            start_time: START,
+            duration: 1_000,
+            initial_rate_bump: 10_000,
+            half_life,
+        })
+    }
+
+    fn step(points: Vec<PointAndTimeDelta>) -> AuctionData {
+        AuctionData::Step(StepCurve {
+            start_time: START,
+            duration: 1_000,
+            initial_rate_bump: 10_000,
+            points_and_time_deltas: points,
+        })
+    }
+
+    fn linear_with_plateau(plateau_duration: u32) -> AuctionData {
+        AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+            start_time: START,
+            duration: 1_000,
+            initial_rate_bump: 10_000,
+            plateau_duration,
+        })
+    }
+
+    fn at(curve: &AuctionData, offset: i64) -> u64 {
+        curve.rate_bump((START as i64 + offset) as u64)
+    }
+
+    #[test]
+    fn piecewise_linear_without_points_is_linear() {
+        let curve = piecewise_linear(vec![]);
+        assert_eq!(at(&curve, -1), 10_000);
+        assert_eq!(at(&curve, 0), 10_000);
+        assert_eq!(at(&curve, 250), 7_500);
+        assert_eq!(at(&curve, 500), 5_000);
+        assert_eq!(at(&curve, 999), 10);
+        assert_eq!(at(&curve, 1_000), 0);
+        assert_eq!(at(&curve, 2_000), 0);
+    }
+
+    #[test]
+    fn piecewise_linear_interpolates_between_points() {
+        let curve = piecewise_linear(vec![point(6_000, 200), point(2_000, 400)]);
+        assert_eq!(at(&curve, 100), 8_000);
+        assert_eq!(at(&curve, 200), 6_000);
+        assert_eq!(at(&curve, 400), 4_000);
+        assert_eq!(at(&curve, 600), 2_000);
+        assert_eq!(at(&curve, 800), 1_000);
+        assert_eq!(at(&curve, 1_000), 0);
+    }
+
+    #[test]
+    fn piecewise_linear_ignores_points_past_duration() {
+        let curve = piecewise_linear(vec![point(5_000, 500), point(4_000, u16::MAX)]);
+        assert_eq!(at(&curve, 500), 5_000);
+        assert_eq!(at(&curve, 999), 4_992);
+        assert_eq!(at(&curve, 1_000), 0);
+    }
+
+    #[test]
+    fn exponential_decay_halves_every_half_life() {
+        let curve = exponential_decay(100);
+        assert_eq!(at(&curve, 0), 10_000);
+        assert_eq!(at(&curve, 50), 7_500);
+        assert_eq!(at(&curve, 100), 5_000);
+        assert_eq!(at(&curve, 200), 2_500);
+        assert_eq!(at(&curve, 300), 1_250);
+        assert_eq!(at(&curve, 999), 10);
+        assert_eq!(at(&curve, 1_000), 0);
+    }
+
+    #[test]
+    fn exponential_decay_handles_degenerate_half_life() {
+        assert_eq!(at(&exponential_decay(0), 0), 10_000);
+        assert_eq!(at(&exponential_decay(0), 1), 0);
+        assert_eq!(at(&exponential_decay(1), 16), 0);
+        assert_eq!(at(&exponential_decay(u32::MAX), 500), 10_000);
+    }
+
+    #[test]
+    fn exponential_decay_is_non_increasing() {
+        let curve = exponential_decay(37);
+        let mut previous = at(&curve, 0);
+        for offset in 1..=1_000 {
+            let current = at(&curve, offset);
+            assert!(current <= previous);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn step_holds_rate_between_points() {
+        let curve = step(vec![point(6_000, 200), point(2_000, 400)]);
+        assert_eq!(at(&curve, -1), 10_000);
+        assert_eq!(at(&curve, 0), 10_000);
+        assert_eq!(at(&curve, 199), 10_000);
+        assert_eq!(at(&curve, 200), 6_000);
+        assert_eq!(at(&curve, 599), 6_000);
+        assert_eq!(at(&curve, 600), 2_000);
+        assert_eq!(at(&curve, 999), 2_000);
+        assert_eq!(at(&curve, 1_000), 0);
+    }
+
+    #[test]
+    fn step_without_points_holds_initial_rate() {
+        let curve = step(vec![]);
+        assert_eq!(at(&curve, 999), 10_000);
+        assert_eq!(at(&curve, 1_000), 0);
+    }
+
+    #[test]
+    fn linear_with_plateau_holds_then_decreases() {
+        let curve = linear_with_plateau(200);
+        assert_eq!(at(&curve, -1), 10_000);
+        assert_eq!(at(&curve, 200), 10_000);
+        assert_eq!(at(&curve, 600), 5_000);
+        assert_eq!(at(&curve, 999), 12);
+        assert_eq!(at(&curve, 1_000), 0);
+    }
+
+    #[test]
+    fn linear_with_plateau_over_whole_duration() {
+        let curve = linear_with_plateau(1_000);
+        assert_eq!(at(&curve, 1_000), 10_000);
+        assert_eq!(at(&curve, 1_001), 0);
+        assert_eq!(at(&linear_with_plateau(0), 500), 5_000);
+    }
+
+    #[test]
+    fn curves_do_not_overflow_on_extreme_values() {
+        let curves = [
+            AuctionData::PiecewiseLinear(PiecewiseLinearCurve {
+                start_time: u32::MAX,
+                duration: u32::MAX,
+                initial_rate_bump: u16::MAX,
+                points_and_time_deltas: vec![point(u16::MAX, u16::MAX); 8],
+            }),
+            AuctionData::ExponentialDecay(ExponentialDecayCurve {
+                start_time: u32::MAX,
+                duration: u32::MAX,
+                initial_rate_bump: u16::MAX,
+                half_life: u32::MAX,
+            }),
+            AuctionData::Step(StepCurve {
+                start_time: u32::MAX,
+                duration: u32::MAX,
+                initial_rate_bump: u16::MAX,
+                points_and_time_deltas: vec![point(u16::MAX, u16::MAX); 8],
+            }),
+            AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                start_time: u32::MAX,
+                duration: u32::MAX,
+                initial_rate_bump: u16::MAX,
+                plateau_duration: u32::MAX - 1,
+            }),
+        ];
+        for curve in curves.iter() {
+            for timestamp in [0, u32::MAX as u64, u32::MAX as u64 * 2 - 1, u64::MAX] {
+                assert!(curve.rate_bump(timestamp) <= u16::MAX as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn validate_accepts_well_formed_curves() {
+        let expiration = START + 1_000;
+        assert!(piecewise_linear(vec![point(6_000, 200), point(2_000, 800)])
+            .validate(expiration)
+            .is_ok());
+        assert!(step(vec![point(10_000, 0)]).validate(expiration).is_ok());
+        assert!(exponential_decay(0).validate(expiration).is_ok());
+        assert!(linear_with_plateau(1_000).validate(START).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_too_many_points() {
+        let points = vec![point(0, 1); MAX_AUCTION_POINTS + 1];
+        assert_eq!(
+            piecewise_linear(points.clone()).validate(u32::MAX),
+            Err(FusionError::TooManyAuctionPoints.into())
+        );
+        assert_eq!(
+            step(points).validate(u32::MAX),
+            Err(FusionError::TooManyAuctionPoints.into())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_increasing_rate_bumps() {
+        assert_eq!(
+            piecewise_linear(vec![point(10_001, 100)]).validate(u32::MAX),
+            Err(FusionError::IncreasingAuctionRateBump.into())
+        );
+        assert_eq!(
+            step(vec![point(5_000, 100), point(6_000, 100)]).validate(u32::MAX),
+            Err(FusionError::IncreasingAuctionRateBump.into())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_points_past_duration() {
+        assert_eq!(
+            piecewise_linear(vec![point(5_000, 500), point(0, 501)]).validate(u32::MAX),
+            Err(FusionError::AuctionPointsExceedDuration.into())
+        );
+        assert_eq!(
+            step(vec![point(0, 1_001)]).validate(u32::MAX),
+            Err(FusionError::AuctionPointsExceedDuration.into())
+        );
+        assert_eq!(
+            linear_with_plateau(1_001).validate(u32::MAX),
+            Err(FusionError::AuctionPointsExceedDuration.into())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_start_after_expiration() {
+        let curves = [
+            piecewise_linear(vec![]),
+            exponential_decay(100),
+            step(vec![]),
+            linear_with_plateau(0),
+        ];
+        for curve in curves.iter() {
+            assert!(curve.validate(START).is_ok());
+            assert_eq!(
+                curve.validate(START - 1),
+                Err(FusionError::AuctionStartsAfterExpiration.into())
+            );
+        }
+    }
+
+    #[test]
+    fn premium_grows_linearly_to_max() {
+        assert_eq!(calculate_premium(START, START, 1_000, 500), 0);
+        assert_eq!(calculate_premium(START + 500, START, 1_000, 500), 250);
+        assert_eq!(calculate_premium(START + 1_000, START, 1_000, 500), 500);
+        assert_eq!(calculate_premium(START + 2_000, START, 1_000, 500), 500);
+    }
+
+    #[test]
+    fn premium_does_not_overflow() {
+        assert_eq!(
+            calculate_premium(u32::MAX - 1, 0, u32::MAX, u64::MAX),
+            u64::MAX - u64::MAX / u32::MAX as u64
+        );
+    }
 }
@@ -1,4 +1,5 @@
-use anchor_lang::error_code;
+use crate::quote::QuoteError;
+use anchor_lang::{error_code, prelude::ProgramError};
 
 #[error_code]
 pub enum FusionError {
@@ -32,4 +33,54 @@ pub enum FusionError {
     MissingTakerDstAta,
     #[msg("Missing maker src ata")]
     MissingMakerSrcAta,
+    #[msg("Too many integrator fee shares")]
+    TooManyIntegratorFeeShares,
+    #[msg("Invalid integrator fee share")]
+    InvalidIntegratorFeeShare,
+    #[msg("Inconsistent integrator fee shares")]
+    InconsistentIntegratorFeeShares,
+    #[msg("Total fee too high")]
+    InvalidTotalFee,
+    #[msg("No fees to claim")]
+    NoFeesToClaim,
+    #[msg("Invalid surplus split")]
+    InvalidSurplusSplit,
+    #[msg("Missing resolver src ata")]
+    MissingResolverSrcAta,
+    #[msg("Inconsistent batch accounts")]
+    InconsistentBatchAccounts,
+    #[msg("Too many auction points")]
+    TooManyAuctionPoints,
+    #[msg("Auction rate bumps must not increase")]
+    IncreasingAuctionRateBump,
+    #[msg("Auction points exceed auction duration")]
+    AuctionPointsExceedDuration,
+    #[msg("Auction starts after order expiration")]
+    AuctionStartsAfterExpiration,
+    #[msg("Resolver access is not valid at the current time")]
+    ResolverAccessNotValid,
+    #[msg("Resolver is suspended")]
+    ResolverSuspended,
+    #[msg("Resolver is not permitted to call the instruction")]
+    MissingResolverPermission,
+    #[msg("Missing resolver access")]
+    MissingResolverAccess,
+    #[msg("Missing whitelist state")]
+    MissingWhitelistState,
+    #[msg("Resolver root is not set")]
+    ResolverRootNotSet,
+    #[msg("Invalid resolver proof")]
+    InvalidResolverProof,
+    #[msg("Resolver bond is below the minimum")]
+    InsufficientResolverBond,
+    #[msg("Delegate was not added under the current registration of the resolver")]
+    InvalidResolverDelegate,
+}
+
+impl From<QuoteError> for anchor_lang::error::Error {
+    fn from(error: QuoteError) -> Self {
+        match error {
+            QuoteError::ArithmeticOverflow => ProgramError::ArithmeticOverflow.into(),
+        }
+    }
 }
@@ -8,17 +8,29 @@ use anchor_spl::{
         TransferChecked,
     },
 };
-use auction::{calculate_premium, calculate_rate_bump, AuctionData};
+use auction::{calculate_premium, AuctionData};
 use common::constants::*;
-use muldiv::MulDiv;
 
 pub mod auction;
 pub mod error;
+pub mod quote;
 
 use error::FusionError;
+use quote::{FeeAmounts, Quote};
 
 declare_id!("HNarfxC3kYMMhFkxUFeYb8wHVdPzY5t9pupqW5fL2meM");
 
+/// Maximum number of integrator fee shares in a single order
+pub const MAX_INTEGRATOR_FEE_SHARES: usize = 3;
+
+/// Seed of the authority of a beneficiary's fee vaults. The vaults are plain fee destinations,
+/// `fill` transfers to them like to any other account
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+
+/// Number of accounts per order in `cancel_by_resolver_batch`, same as in `CancelByResolver`
+pub const CANCEL_BY_RESOLVER_ACCOUNTS_LEN: usize = 15;
+const CANCEL_BY_RESOLVER_ESCROW_SRC_ATA_INDEX: usize = 8;
+
 enum UniTransferParams<'info> {
     NativeTransfer {
         from: AccountInfo<'info>,
@@ -68,6 +80,42 @@ pub mod fusion_swap {
             FusionError::InvalidProtocolSurplusFee
         );
 
+        require!(
+            order.fee.surplus_percentage as u64
+                + order.fee.integrator_surplus_percentage as u64
+                + order.fee.resolver_surplus_percentage as u64
+                <= BASE_1E2,
+            FusionError::InvalidSurplusSplit
+        );
+
+        require!(
+            order.fee.integrator_fee_shares.len() <= MAX_INTEGRATOR_FEE_SHARES,
+            FusionError::TooManyIntegratorFeeShares
+        );
+
+        require!(
+            order
+                .fee
+                .integrator_fee_shares
+                .iter()
+                .all(|share| share.fee > 0),
+            FusionError::InvalidIntegratorFeeShare
+        );
+
+        // Sum of all fees must not exceed the filled amount
+        require!(
+            order.fee.protocol_fee as u64
+                + order.fee.integrator_fee as u64
+                + order
+                    .fee
+                    .integrator_fee_shares
+                    .iter()
+                    .map(|share| share.fee as u64)
+                    .sum::<u64>()
+                <= BASE_1E5,
+            FusionError::InvalidTotalFee
+        );
+
         require!(
             order.estimated_dst_amount >= order.min_dst_amount,
             FusionError::InvalidEstimatedTakingAmount
@@ -80,9 +128,10 @@ pub mod fusion_swap {
             FusionError::InconsistentProtocolFeeConfig
         );
 
-        // Iff integrator fee is positive, integrator_dst_acc must be set
+        // Iff integrator fee or surplus is positive, integrator_dst_acc must be set
         require!(
-            (order.fee.integrator_fee > 0) == ctx.accounts.integrator_dst_acc.is_some(),
+            (order.fee.integrator_fee > 0 || order.fee.integrator_surplus_percentage > 0)
+                == ctx.accounts.integrator_dst_acc.is_some(),
             FusionError::InconsistentIntegratorFeeConfig
         );
 
@@ -92,11 +141,23 @@ pub mod fusion_swap {
             FusionError::InvalidCancellationFee
         );
 
+        // Premium in src tokens is only available for non-native src orders
+        // and excludes the premium in lamports
+        require!(
+            order.fee.max_src_cancellation_premium == 0
+                || (!order.src_asset_is_native
+                    && order.fee.max_cancellation_premium == 0
+                    && order.fee.max_src_cancellation_premium <= order.src_amount),
+            FusionError::InvalidCancellationFee
+        );
+
         require!(
             order.src_asset_is_native == ctx.accounts.maker_src_ata.is_none(),
             FusionError::InconsistentNativeSrcTrait
         );
 
+        order.dutch_auction_data.validate(order.expiration_time)?;
+
         // Maker => Escrow
         if order.src_asset_is_native {
             // Wrap SOL to wSOL
@@ -130,9 +191,61 @@ pub mod fusion_swap {
         }
     }
 
-    pub fn fill(ctx: Context<Fill>, order: OrderConfig, amount: u64) -> Result<()> {
+    pub fn fill<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Fill<'info>>,
+        order: OrderConfig,
+        amount: u64,
+    ) -> Result<()> {
         require!(
-            Clock::get()?.unix_timestamp < order.expiration_time as i64,
+            ctx.accounts.resolver_access.is_some(),
+            FusionError::MissingResolverAccess
+        );
+        fill_order(ctx, order, amount)
+    }
+
+    /// Fills the order by a resolver proven to be in the Merkle tree of
+    /// `WhitelistState::resolver_root` instead of holding a `ResolverAccess` account.
+    /// A delegate still needs the access of the resolver that added it
+    pub fn fill_with_proof<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Fill<'info>>,
+        order: OrderConfig,
+        amount: u64,
+        proof: whitelist::merkle::ResolverProof,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.resolver_delegate.is_none() || ctx.accounts.resolver_access.is_some(),
+            FusionError::MissingResolverAccess
+        );
+        let root = ctx
+            .accounts
+            .whitelist_state
+            .as_ref()
+            .ok_or(FusionError::MissingWhitelistState)?
+            .resolver_root
+            .ok_or(FusionError::ResolverRootNotSet)?;
+        let resolver = ctx
+            .accounts
+            .taker
+            .key()
+            .acting_for(&ctx.accounts.resolver_delegate);
+        require!(
+            proof.verify(&root, &resolver),
+            FusionError::InvalidResolverProof
+        );
+        require!(
+            Clock::get()?.unix_timestamp < proof.valid_until,
+            FusionError::ResolverAccessNotValid
+        );
+        fill_order(ctx, order, amount)
+    }
+
+    /// Quotes a fill of `amount` src tokens at the current time without changing any account.
+    /// The quote is the return data of the instruction, so that simulating it gives the exact
+    /// amounts `fill` would transfer
+    pub fn quote(ctx: Context<QuoteOrder>, order: OrderConfig, amount: u64) -> Result<OrderQuote> {
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            timestamp < order.expiration_time as i64,
             FusionError::OrderExpired
         );
 
@@ -143,144 +256,29 @@ pub mod fusion_swap {
 
         require!(amount != 0, FusionError::InvalidAmount);
 
-        let order_src_mint = ctx.accounts.src_mint.key();
-        let order_dst_mint = ctx.accounts.dst_mint.key();
-        let order_receiver = ctx.accounts.maker_receiver.key();
-        let protocol_dst_acc = ctx.accounts.protocol_dst_acc.as_ref().map(|acc| acc.key());
-        let integrator_dst_acc = ctx
-            .accounts
-            .integrator_dst_acc
-            .as_ref()
-            .map(|acc| acc.key());
-
-        let order_hash = &order_hash(
+        let order_hash = order_hash(
             &order,
-            protocol_dst_acc,
-            integrator_dst_acc,
-            order_src_mint,
-            order_dst_mint,
-            order_receiver,
-        )?;
-
-        // Escrow => Taker
-        transfer_checked(
-            CpiContext::new_with_signer(
-                ctx.accounts.src_token_program.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.escrow_src_ata.to_account_info(),
-                    mint: ctx.accounts.src_mint.to_account_info(),
-                    to: ctx.accounts.taker_src_ata.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                &[&[
-                    "escrow".as_bytes(),
-                    ctx.accounts.maker.key().as_ref(),
-                    order_hash,
-                    &[ctx.bumps.escrow],
-                ]],
-            ),
-            amount,
-            ctx.accounts.src_mint.decimals,
-        )?;
-
-        let dst_amount = get_dst_amount(
-            order.src_amount,
-            order.min_dst_amount,
-            amount,
-            Some(&order.dutch_auction_data),
+            ctx.accounts.protocol_dst_acc.as_ref().map(|acc| acc.key()),
+            ctx.accounts
+                .integrator_dst_acc
+                .as_ref()
+                .map(|acc| acc.key()),
+            ctx.accounts.src_mint.key(),
+            ctx.accounts.dst_mint.key(),
+            ctx.accounts.maker_receiver.key(),
         )?;
-
-        let (protocol_fee_amount, integrator_fee_amount, maker_dst_amount) = get_fee_amounts(
-            order.fee.integrator_fee,
-            order.fee.protocol_fee,
-            order.fee.surplus_percentage,
+        let Quote {
+            rate_bump,
             dst_amount,
-            get_dst_amount(order.src_amount, order.estimated_dst_amount, amount, None)?,
-        )?;
-
-        // Taker => Maker
-        let mut params = if order.dst_asset_is_native {
-            UniTransferParams::NativeTransfer {
-                from: ctx.accounts.taker.to_account_info(),
-                to: ctx.accounts.maker_receiver.to_account_info(),
-                amount: maker_dst_amount,
-                program: ctx.accounts.system_program.clone(),
-            }
-        } else {
-            UniTransferParams::TokenTransfer {
-                from: ctx
-                    .accounts
-                    .taker_dst_ata
-                    .as_ref()
-                    .ok_or(FusionError::MissingTakerDstAta)?
-                    .to_account_info(),
-                authority: ctx.accounts.taker.to_account_info(),
-                to: ctx
-                    .accounts
-                    .maker_dst_ata
-                    .as_ref()
-                    .ok_or(FusionError::MissingMakerDstAta)?
-                    .to_account_info(),
-                mint: *ctx.accounts.dst_mint.clone(),
-                amount: maker_dst_amount,
-                program: ctx.accounts.dst_token_program.clone(),
-            }
-        };
-        uni_transfer(&params)?;
+            fee_amounts,
+        } = quote::quote(&order, timestamp as u64, amount)?;
 
-        // Take protocol fee
-        if protocol_fee_amount > 0 {
-            match &mut params {
-                UniTransferParams::NativeTransfer { amount, to, .. }
-                | UniTransferParams::TokenTransfer { amount, to, .. } => {
-                    *amount = protocol_fee_amount;
-                    *to = ctx
-                        .accounts
-                        .protocol_dst_acc
-                        .as_ref()
-                        .ok_or(FusionError::InconsistentProtocolFeeConfig)?
-                        .to_account_info();
-                }
-            }
-            uni_transfer(&params)?;
-        }
-
-        // Take integrator fee
-        if integrator_fee_amount > 0 {
-            match &mut params {
-                UniTransferParams::NativeTransfer { amount, to, .. }
-                | UniTransferParams::TokenTransfer { amount, to, .. } => {
-                    *amount = integrator_fee_amount;
-                    *to = ctx
-                        .accounts
-                        .integrator_dst_acc
-                        .as_ref()
-                        .ok_or(FusionError::InconsistentIntegratorFeeConfig)?
-                        .to_account_info();
-                }
-            }
-            uni_transfer(&params)?;
-        }
-
-        // Close escrow if all tokens are filled
-        if ctx.accounts.escrow_src_ata.amount == amount {
-            close_account(CpiContext::new_with_signer(
-                ctx.accounts.src_token_program.to_account_info(),
-                CloseAccount {
-                    account: ctx.accounts.escrow_src_ata.to_account_info(),
-                    destination: ctx.accounts.maker.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                &[&[
-                    "escrow".as_bytes(),
-                    ctx.accounts.maker.key().as_ref(),
-                    order_hash,
-                    &[ctx.bumps.escrow],
-                ]],
-            ))?;
-        }
-
-        Ok(())
+        Ok(OrderQuote {
+            order_hash,
+            rate_bump,
+            dst_amount,
+            fee_amounts,
+        })
     }
 
     pub fn cancel(
@@ -346,93 +344,88 @@ pub mod fusion_swap {
         ctx: Context<CancelByResolver>,
         order: OrderConfig,
         reward_limit: u64,
+    ) -> Result<()> {
+        cancel_escrow_by_resolver(ctx.accounts, ctx.bumps.escrow, order, reward_limit)
+    }
+
+    /// Cancels several expired orders in one instruction. Every order takes a group of
+    /// `CANCEL_BY_RESOLVER_ACCOUNTS_LEN` remaining accounts laid out as in `cancel_by_resolver`.
+    /// Orders whose escrow ATA is already closed are skipped
+    pub fn cancel_by_resolver_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelByResolverBatch<'info>>,
+        orders: Vec<OrderConfig>,
+        reward_limit: u64,
     ) -> Result<()> {
         require!(
-            order.fee.max_cancellation_premium > 0,
-            FusionError::CancelOrderByResolverIsForbidden
-        );
-        let current_timestamp = Clock::get()?.unix_timestamp;
-        require!(
-            current_timestamp >= order.expiration_time as i64,
-            FusionError::OrderNotExpired
-        );
-        require!(
-            order.src_asset_is_native == ctx.accounts.maker_src_ata.is_none(),
-            FusionError::InconsistentNativeSrcTrait
+            ctx.remaining_accounts.len() == orders.len() * CANCEL_BY_RESOLVER_ACCOUNTS_LEN,
+            FusionError::InconsistentBatchAccounts
         );
 
-        let order_hash = order_hash(
-            &order,
-            ctx.accounts.protocol_dst_acc.as_ref().map(|acc| acc.key()),
-            ctx.accounts
-                .integrator_dst_acc
-                .as_ref()
-                .map(|acc| acc.key()),
-            ctx.accounts.src_mint.key(),
-            ctx.accounts.dst_mint.key(),
-            ctx.accounts.maker_receiver.key(),
-        )?;
+        for (order, group) in orders.into_iter().zip(
+            ctx.remaining_accounts
+                .chunks(CANCEL_BY_RESOLVER_ACCOUNTS_LEN),
+        ) {
+            // Escrow ATA of an order that was already filled or cancelled has been closed
+            if group[CANCEL_BY_RESOLVER_ESCROW_SRC_ATA_INDEX].data_is_empty() {
+                continue;
+            }
 
-        // Return remaining src tokens back to maker
-        if !order.src_asset_is_native {
-            transfer_checked(
-                CpiContext::new_with_signer(
-                    ctx.accounts.src_token_program.to_account_info(),
-                    TransferChecked {
-                        from: ctx.accounts.escrow_src_ata.to_account_info(),
-                        mint: ctx.accounts.src_mint.to_account_info(),
-                        to: ctx
-                            .accounts
-                            .maker_src_ata
-                            .as_ref()
-                            .ok_or(FusionError::MissingMakerSrcAta)?
-                            .to_account_info(),
-                        authority: ctx.accounts.escrow.to_account_info(),
-                    },
-                    &[&[
-                        "escrow".as_bytes(),
-                        ctx.accounts.maker.key().as_ref(),
-                        &order_hash,
-                        &[ctx.bumps.escrow],
-                    ]],
-                ),
-                ctx.accounts.escrow_src_ata.amount,
-                ctx.accounts.src_mint.decimals,
+            let mut accounts = group;
+            let mut bumps = CancelByResolverBumps::default();
+            let order_accounts = CancelByResolver::try_accounts(
+                ctx.program_id,
+                &mut accounts,
+                &order.try_to_vec()?,
+                &mut bumps,
+                &mut Default::default(),
             )?;
-        };
+            require_keys_eq!(
+                order_accounts.resolver.key(),
+                ctx.accounts.resolver.key(),
+                FusionError::InconsistentBatchAccounts
+            );
+
+            cancel_escrow_by_resolver(&order_accounts, bumps.escrow, order, reward_limit)?;
+            order_accounts.exit(ctx.program_id)?;
+        }
 
-        let cancellation_premium = calculate_premium(
-            current_timestamp as u32,
-            order.expiration_time,
-            order.cancellation_auction_duration,
-            order.fee.max_cancellation_premium,
-        );
-        let maker_amount = ctx.accounts.escrow_src_ata.to_account_info().lamports()
-            - std::cmp::min(cancellation_premium, reward_limit);
+        Ok(())
+    }
 
-        // Transfer all the remaining lamports to the resolver first
-        close_account(CpiContext::new_with_signer(
-            ctx.accounts.src_token_program.to_account_info(),
-            CloseAccount {
-                account: ctx.accounts.escrow_src_ata.to_account_info(),
-                destination: ctx.accounts.resolver.to_account_info(),
-                authority: ctx.accounts.escrow.to_account_info(),
-            },
-            &[&[
-                "escrow".as_bytes(),
-                ctx.accounts.maker.key().as_ref(),
-                &order_hash,
-                &[ctx.bumps.escrow],
-            ]],
-        ))?;
+    /// Sweeps the fees accumulated in the beneficiary's fee vault for the given mint
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        // Native fees are credited to the wrapped SOL vault as lamports
+        if ctx.accounts.mint.key() == native_mint::id() {
+            anchor_spl::token::sync_native(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::SyncNative {
+                    account: ctx.accounts.fee_vault.to_account_info(),
+                },
+            ))?;
+            ctx.accounts.fee_vault.reload()?;
+        }
 
-        // Transfer all lamports from the closed account, minus the cancellation premium, to the maker
-        uni_transfer(&UniTransferParams::NativeTransfer {
-            from: ctx.accounts.resolver.to_account_info(),
-            to: ctx.accounts.maker.to_account_info(),
-            amount: maker_amount,
-            program: ctx.accounts.system_program.clone(),
-        })
+        let amount = ctx.accounts.fee_vault.amount;
+        require!(amount != 0, FusionError::NoFeesToClaim);
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.fee_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.beneficiary_dst_acc.to_account_info(),
+                    authority: ctx.accounts.fee_vault_authority.to_account_info(),
+                },
+                &[&[
+                    FEE_VAULT_SEED,
+                    ctx.accounts.beneficiary.key().as_ref(),
+                    &[ctx.bumps.fee_vault_authority],
+                ]],
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )
     }
 }
 
@@ -507,13 +500,42 @@ pub struct Fill<'info> {
     /// `taker`, who buys `src_mint` for `dst_mint`
     #[account(mut, signer)]
     taker: Signer<'info>,
-    /// Account allowed to fill the order
+    /// Delegate record of `taker` when it signs on behalf of a whitelisted resolver
+    #[account(
+        seeds = [whitelist::RESOLVER_DELEGATE_SEED, taker.key().as_ref()],
+        bump = resolver_delegate.bump,
+        seeds::program = whitelist::ID,
+    )]
+    resolver_delegate: Option<Account<'info, whitelist::ResolverDelegate>>,
+
+    /// Access of the resolver allowed to fill the order, required by `fill`
     #[account(
-        seeds = [whitelist::RESOLVER_ACCESS_SEED, taker.key().as_ref()],
+        seeds = [whitelist::RESOLVER_ACCESS_SEED, taker.key().acting_for(&resolver_delegate).as_ref()],
         bump = resolver_access.bump,
         seeds::program = whitelist::ID,
+        constraint = resolver_access.is_valid_at(Clock::get()?.unix_timestamp) @ FusionError::ResolverAccessNotValid,
+        constraint = resolver_access.status == whitelist::ResolverStatus::Active @ FusionError::ResolverSuspended,
+        constraint = resolver_access.has_permission(whitelist::permissions::FILL) @ FusionError::MissingResolverPermission,
+        constraint = resolver_delegate.iter().all(|delegate| delegate.is_valid_for(resolver_access)) @ FusionError::InvalidResolverDelegate,
     )]
-    resolver_access: Account<'info, whitelist::ResolverAccess>,
+    resolver_access: Option<Account<'info, whitelist::ResolverAccess>>,
+
+    /// Whitelist state holding the resolver root and the minimum bond
+    #[account(
+        seeds = [whitelist::WHITELIST_STATE_SEED],
+        bump,
+        seeds::program = whitelist::ID,
+    )]
+    whitelist_state: Option<Account<'info, whitelist::WhitelistState>>,
+
+    /// Bond of the resolver, which does not exist if the resolver has never bonded
+    #[account(
+        seeds = [whitelist::RESOLVER_BOND_SEED, taker.key().acting_for(&resolver_delegate).as_ref()],
+        bump,
+        seeds::program = whitelist::ID,
+    )]
+    /// CHECK: deserialized by `fill_order` when `WhitelistState::min_bond` requires a bond
+    resolver_bond: UncheckedAccount<'info>,
 
     /// CHECK: check is not necessary as maker is not spending any funds
     #[account(mut)]
@@ -594,6 +616,54 @@ pub struct Fill<'info> {
     integrator_dst_acc: Option<UncheckedAccount<'info>>,
 }
 
+#[derive(Accounts)]
+#[instruction(order: OrderConfig)]
+pub struct QuoteOrder<'info> {
+    /// CHECK: maker only has to be equal to escrow parameter
+    maker: UncheckedAccount<'info>,
+
+    /// CHECK: maker_receiver only has to be equal to escrow parameter
+    maker_receiver: UncheckedAccount<'info>,
+
+    /// Maker asset
+    src_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Taker asset
+    dst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// PDA derived from order details, acting as the authority for the escrow ATA
+    #[account(
+        seeds = [
+            "escrow".as_bytes(),
+            maker.key().as_ref(),
+            &order_hash(
+                &order,
+                protocol_dst_acc.clone().map(|acc| acc.key()),
+                integrator_dst_acc.clone().map(|acc| acc.key()),
+                src_mint.key(),
+                dst_mint.key(),
+                maker_receiver.key(),
+            )?,
+        ],
+        bump,
+    )]
+    /// CHECK: check is not needed here as we never initialize the account
+    escrow: UncheckedAccount<'info>,
+
+    /// ATA of src_mint to store escrowed tokens
+    #[account(
+        associated_token::mint = src_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = src_token_program,
+    )]
+    escrow_src_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    src_token_program: Interface<'info, TokenInterface>,
+
+    protocol_dst_acc: Option<UncheckedAccount<'info>>,
+
+    integrator_dst_acc: Option<UncheckedAccount<'info>>,
+}
+
 #[derive(Accounts)]
 #[instruction(order_hash: [u8; 32])]
 pub struct Cancel<'info> {
@@ -644,11 +714,23 @@ pub struct CancelByResolver<'info> {
     #[account(mut, signer)]
     resolver: Signer<'info>,
 
-    /// Account allowed to cancel the order
+    /// Delegate record of `resolver` when it signs on behalf of a whitelisted resolver
     #[account(
-        seeds = [whitelist::RESOLVER_ACCESS_SEED, resolver.key().as_ref()],
+        seeds = [whitelist::RESOLVER_DELEGATE_SEED, resolver.key().as_ref()],
+        bump = resolver_delegate.bump,
+        seeds::program = whitelist::ID,
+    )]
+    resolver_delegate: Option<Account<'info, whitelist::ResolverDelegate>>,
+
+    /// Access of the resolver allowed to cancel the order
+    #[account(
+        seeds = [whitelist::RESOLVER_ACCESS_SEED, resolver.key().acting_for(&resolver_delegate).as_ref()],
         bump = resolver_access.bump,
         seeds::program = whitelist::ID,
+        constraint = resolver_access.is_valid_at(Clock::get()?.unix_timestamp) @ FusionError::ResolverAccessNotValid,
+        constraint = resolver_access.status == whitelist::ResolverStatus::Active @ FusionError::ResolverSuspended,
+        constraint = resolver_access.has_permission(whitelist::permissions::CANCEL) @ FusionError::MissingResolverPermission,
+        constraint = resolver_delegate.iter().all(|delegate| delegate.is_valid_for(&resolver_access)) @ FusionError::InvalidResolverDelegate,
     )]
     resolver_access: Account<'info, whitelist::ResolverAccess>,
 
@@ -702,6 +784,14 @@ pub struct CancelByResolver<'info> {
     )]
     maker_src_ata: Option<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Resolver's account of src_mint to receive the cancellation premium in src tokens
+    #[account(
+        mut,
+        token::mint = src_mint,
+        token::token_program = src_token_program,
+    )]
+    resolver_src_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
     src_token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 
@@ -710,39 +800,182 @@ pub struct CancelByResolver<'info> {
     integrator_dst_acc: Option<UncheckedAccount<'info>>,
 }
 
+#[derive(Accounts)]
+pub struct CancelByResolverBatch<'info> {
+    /// Account that cancels the escrows
+    resolver: Signer<'info>,
+
+    /// Delegate record of `resolver` when it signs on behalf of a whitelisted resolver
+    #[account(
+        seeds = [whitelist::RESOLVER_DELEGATE_SEED, resolver.key().as_ref()],
+        bump = resolver_delegate.bump,
+        seeds::program = whitelist::ID,
+    )]
+    resolver_delegate: Option<Account<'info, whitelist::ResolverDelegate>>,
+
+    /// Access of the resolver allowed to cancel the orders
+    #[account(
+        seeds = [whitelist::RESOLVER_ACCESS_SEED, resolver.key().acting_for(&resolver_delegate).as_ref()],
+        bump = resolver_access.bump,
+        seeds::program = whitelist::ID,
+        constraint = resolver_access.is_valid_at(Clock::get()?.unix_timestamp) @ FusionError::ResolverAccessNotValid,
+        constraint = resolver_access.status == whitelist::ResolverStatus::Active @ FusionError::ResolverSuspended,
+        constraint = resolver_access.has_permission(whitelist::permissions::CANCEL) @ FusionError::MissingResolverPermission,
+        constraint = resolver_delegate.iter().all(|delegate| delegate.is_valid_for(&resolver_access)) @ FusionError::InvalidResolverDelegate,
+    )]
+    resolver_access: Account<'info, whitelist::ResolverAccess>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    /// Beneficiary of the fees accumulated in the vault
+    beneficiary: Signer<'info>,
+
+    /// PDA derived from the beneficiary, acting as the authority for its fee vaults
+    #[account(
+        seeds = [FEE_VAULT_SEED, beneficiary.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: check is not needed here as we never initialize the account
+    fee_vault_authority: UncheckedAccount<'info>,
+
+    /// Fee asset
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// ATA of mint to accumulate fees, used as a fee destination in orders
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fee_vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Account to receive the claimed fees
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    beneficiary_dst_acc: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    token_program: Interface<'info, TokenInterface>,
+}
+
+/// Share of the integrator fee paid to an additional recipient
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct IntegratorFeeShare {
+    /// Account receiving the share, passed to `fill` in `remaining_accounts`
+    pub recipient: Pubkey,
+
+    /// Share fee in basis points where `BASE_1E5` = 100%
+    pub fee: u16,
+}
+
 /// Configuration for fees applied to the escrow
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
 pub struct FeeConfig {
     /// Protocol fee in basis points where `BASE_1E5` = 100%
-    protocol_fee: u16,
+    pub protocol_fee: u16,
 
     /// Integrator fee in basis points where `BASE_1E5` = 100%
-    integrator_fee: u16,
+    pub integrator_fee: u16,
+
+    /// Integrator fee shares paid to additional recipients, e.g. wallet, aggregator and referrer
+    #[max_len(MAX_INTEGRATOR_FEE_SHARES)]
+    pub integrator_fee_shares: Vec<IntegratorFeeShare>,
 
     /// Percentage of positive slippage taken by the protocol as an additional fee.
     /// Value in basis points where `BASE_1E2` = 100%
-    surplus_percentage: u8,
+    pub surplus_percentage: u8,
+
+    /// Percentage of positive slippage taken by the integrator as an additional fee.
+    /// Value in basis points where `BASE_1E2` = 100%
+    pub integrator_surplus_percentage: u8,
+
+    /// Percentage of positive slippage left to the filling resolver.
+    /// Value in basis points where `BASE_1E2` = 100%
+    pub resolver_surplus_percentage: u8,
 
     /// Maximum cancellation premium
     /// Value in absolute lamports amount
-    max_cancellation_premium: u64,
+    pub max_cancellation_premium: u64,
+
+    /// Maximum cancellation premium taken from the escrowed src tokens, for non-native src orders
+    /// Value in absolute src tokens amount
+    pub max_src_cancellation_premium: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct OrderConfig {
-    id: u32,
-    src_amount: u64,
-    min_dst_amount: u64,
-    estimated_dst_amount: u64,
-    expiration_time: u32,
-    src_asset_is_native: bool,
-    dst_asset_is_native: bool,
-    fee: FeeConfig,
-    dutch_auction_data: AuctionData,
-    cancellation_auction_duration: u32,
+    pub id: u32,
+    pub src_amount: u64,
+    pub min_dst_amount: u64,
+    pub estimated_dst_amount: u64,
+    pub expiration_time: u32,
+    pub src_asset_is_native: bool,
+    pub dst_asset_is_native: bool,
+    pub fee: FeeConfig,
+    pub dutch_auction_data: AuctionData,
+    pub cancellation_auction_duration: u32,
+}
+
+/// Amounts of a fill at the current time, returned by `quote`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OrderQuote {
+    pub order_hash: [u8; 32],
+    /// Rate bump of the auction curve, in basis points where `BASE_1E5` = 100%
+    pub rate_bump: u64,
+    /// Amount of `dst_mint` tokens the taker pays, including all fees
+    pub dst_amount: u64,
+    pub fee_amounts: FeeAmounts,
 }
 
-fn order_hash(
+/// Emitted on every fill with the split of the filled dst amount.
+/// Surplus amounts are parts of the maker amount and fees respectively, except for
+/// `resolver_surplus` which is not paid by the taker at all
+#[event]
+pub struct OrderFilled {
+    pub order_hash: [u8; 32],
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Whitelisted resolver the fill is attributed to, `taker` unless it signs as a delegate
+    pub resolver: Pubkey,
+    pub src_amount: u64,
+    pub remaining_src_amount: u64,
+    pub dst_amount: u64,
+    pub maker_amount: u64,
+    pub protocol_fee: u64,
+    pub integrator_fee: u64,
+    pub integrator_fee_shares: Vec<u64>,
+    pub maker_surplus: u64,
+    pub protocol_surplus: u64,
+    pub integrator_surplus: u64,
+    pub resolver_surplus: u64,
+}
+
+/// Whitelisted resolver a signer acts for, itself unless it signs as a delegate.
+///
+/// Written as a method on the signer key so that the IDL still derives `resolver_access`
+/// from the signer for clients that fill without a delegate.
+trait ActingFor {
+    fn acting_for(self, resolver_delegate: &Option<Account<whitelist::ResolverDelegate>>)
+        -> Pubkey;
+}
+
+impl ActingFor for Pubkey {
+    fn acting_for(
+        self,
+        resolver_delegate: &Option<Account<whitelist::ResolverDelegate>>,
+    ) -> Pubkey {
+        resolver_delegate
+            .as_ref()
+            .map_or(self, |delegate| delegate.resolver)
+    }
+}
+
+/// Hash of the order config together with the order accounts, used as the escrow PDA seed
+pub fn order_hash(
     order: &OrderConfig,
     protocol_dst_acc: Option<Pubkey>,
     integrator_dst_acc: Option<Pubkey>,
@@ -761,56 +994,350 @@ fn order_hash(
     .to_bytes())
 }
 
-// Function to get amount of `dst_mint` tokens that the taker should pay to the maker using default or the dutch auction formula
-fn get_dst_amount(
-    initial_src_amount: u64,
-    initial_dst_amount: u64,
-    src_amount: u64,
-    opt_data: Option<&AuctionData>,
-) -> Result<u64> {
-    let mut result = initial_dst_amount
-        .mul_div_ceil(src_amount, initial_src_amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    if let Some(data) = opt_data {
-        let rate_bump = calculate_rate_bump(Clock::get()?.unix_timestamp as u64, data);
-        result = result
-            .mul_div_ceil(BASE_1E5 + rate_bump, BASE_1E5)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+/// Bond of the resolver, `None` if the resolver has never bonded
+fn resolver_bond(account: &AccountInfo) -> Option<whitelist::bond::ResolverBond> {
+    if account.owner != &whitelist::ID {
+        return None;
+    }
+    let data = account.try_borrow_data().ok()?;
+    whitelist::bond::ResolverBond::try_deserialize(&mut &data[..]).ok()
+}
+
+/// Fills the order once the taker is checked against the whitelist, and its bond against the
+/// minimum bond
+fn fill_order<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Fill<'info>>,
+    order: OrderConfig,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp < order.expiration_time as i64,
+        FusionError::OrderExpired
+    );
+
+    require!(
+        amount <= ctx.accounts.escrow_src_ata.amount,
+        FusionError::NotEnoughTokensInEscrow
+    );
+
+    require!(amount != 0, FusionError::InvalidAmount);
+
+    let whitelist_state = ctx
+        .accounts
+        .whitelist_state
+        .as_ref()
+        .ok_or(FusionError::MissingWhitelistState)?;
+    require!(
+        whitelist_state.is_bonded(resolver_bond(&ctx.accounts.resolver_bond).as_ref()),
+        FusionError::InsufficientResolverBond
+    );
+
+    let order_src_mint = ctx.accounts.src_mint.key();
+    let order_dst_mint = ctx.accounts.dst_mint.key();
+    let order_receiver = ctx.accounts.maker_receiver.key();
+    let protocol_dst_acc = ctx.accounts.protocol_dst_acc.as_ref().map(|acc| acc.key());
+    let integrator_dst_acc = ctx
+        .accounts
+        .integrator_dst_acc
+        .as_ref()
+        .map(|acc| acc.key());
+
+    let order_hash = &order_hash(
+        &order,
+        protocol_dst_acc,
+        integrator_dst_acc,
+        order_src_mint,
+        order_dst_mint,
+        order_receiver,
+    )?;
+
+    // Escrow => Taker
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.src_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_src_ata.to_account_info(),
+                mint: ctx.accounts.src_mint.to_account_info(),
+                to: ctx.accounts.taker_src_ata.to_account_info(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            &[&[
+                "escrow".as_bytes(),
+                ctx.accounts.maker.key().as_ref(),
+                order_hash,
+                &[ctx.bumps.escrow],
+            ]],
+        ),
+        amount,
+        ctx.accounts.src_mint.decimals,
+    )?;
+
+    let Quote {
+        dst_amount,
+        fee_amounts,
+        ..
+    } = quote::quote(&order, Clock::get()?.unix_timestamp as u64, amount)?;
+
+    // Taker => Maker
+    let mut params = if order.dst_asset_is_native {
+        UniTransferParams::NativeTransfer {
+            from: ctx.accounts.taker.to_account_info(),
+            to: ctx.accounts.maker_receiver.to_account_info(),
+            amount: fee_amounts.maker_amount,
+            program: ctx.accounts.system_program.clone(),
+        }
+    } else {
+        UniTransferParams::TokenTransfer {
+            from: ctx
+                .accounts
+                .taker_dst_ata
+                .as_ref()
+                .ok_or(FusionError::MissingTakerDstAta)?
+                .to_account_info(),
+            authority: ctx.accounts.taker.to_account_info(),
+            to: ctx
+                .accounts
+                .maker_dst_ata
+                .as_ref()
+                .ok_or(FusionError::MissingMakerDstAta)?
+                .to_account_info(),
+            mint: *ctx.accounts.dst_mint.clone(),
+            amount: fee_amounts.maker_amount,
+            program: ctx.accounts.dst_token_program.clone(),
+        }
+    };
+    uni_transfer(&params)?;
+
+    // Take protocol fee
+    if fee_amounts.protocol_fee > 0 {
+        match &mut params {
+            UniTransferParams::NativeTransfer { amount, to, .. }
+            | UniTransferParams::TokenTransfer { amount, to, .. } => {
+                *amount = fee_amounts.protocol_fee;
+                *to = ctx
+                    .accounts
+                    .protocol_dst_acc
+                    .as_ref()
+                    .ok_or(FusionError::InconsistentProtocolFeeConfig)?
+                    .to_account_info();
+            }
+        }
+        uni_transfer(&params)?;
+    }
+
+    // Take integrator fee
+    if fee_amounts.integrator_fee > 0 {
+        match &mut params {
+            UniTransferParams::NativeTransfer { amount, to, .. }
+            | UniTransferParams::TokenTransfer { amount, to, .. } => {
+                *amount = fee_amounts.integrator_fee;
+                *to = ctx
+                    .accounts
+                    .integrator_dst_acc
+                    .as_ref()
+                    .ok_or(FusionError::InconsistentIntegratorFeeConfig)?
+                    .to_account_info();
+            }
+        }
+        uni_transfer(&params)?;
+    }
+
+    // Take integrator fee shares, recipients are passed in `remaining_accounts` in the order
+    // they are listed in the order config
+    require!(
+        ctx.remaining_accounts.len() >= order.fee.integrator_fee_shares.len(),
+        FusionError::InconsistentIntegratorFeeShares
+    );
+    for ((share, share_amount), recipient) in order
+        .fee
+        .integrator_fee_shares
+        .iter()
+        .zip(fee_amounts.integrator_fee_shares.iter().copied())
+        .zip(ctx.remaining_accounts.iter())
+    {
+        require!(
+            recipient.key() == share.recipient,
+            FusionError::InconsistentIntegratorFeeShares
+        );
+        if share_amount > 0 {
+            match &mut params {
+                UniTransferParams::NativeTransfer { amount, to, .. }
+                | UniTransferParams::TokenTransfer { amount, to, .. } => {
+                    *amount = share_amount;
+                    *to = recipient.to_account_info();
+                }
+            }
+            uni_transfer(&params)?;
+        }
+    }
+
+    emit!(OrderFilled {
+        order_hash: *order_hash,
+        maker: ctx.accounts.maker.key(),
+        taker: ctx.accounts.taker.key(),
+        resolver: ctx
+            .accounts
+            .taker
+            .key()
+            .acting_for(&ctx.accounts.resolver_delegate),
+        src_amount: amount,
+        remaining_src_amount: ctx.accounts.escrow_src_ata.amount - amount,
+        dst_amount,
+        maker_amount: fee_amounts.maker_amount,
+        protocol_fee: fee_amounts.protocol_fee,
+        integrator_fee: fee_amounts.integrator_fee,
+        integrator_fee_shares: fee_amounts.integrator_fee_shares,
+        maker_surplus: fee_amounts.maker_surplus,
+        protocol_surplus: fee_amounts.protocol_surplus,
+        integrator_surplus: fee_amounts.integrator_surplus,
+        resolver_surplus: fee_amounts.resolver_surplus,
+    });
+
+    // Close escrow if all tokens are filled
+    if ctx.accounts.escrow_src_ata.amount == amount {
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.src_token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_src_ata.to_account_info(),
+                destination: ctx.accounts.maker.to_account_info(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            &[&[
+                "escrow".as_bytes(),
+                ctx.accounts.maker.key().as_ref(),
+                order_hash,
+                &[ctx.bumps.escrow],
+            ]],
+        ))?;
     }
-    Ok(result)
+
+    Ok(())
 }
 
-fn get_fee_amounts(
-    integrator_fee: u16,
-    protocol_fee: u16,
-    surplus_percentage: u8,
-    dst_amount: u64,
-    estimated_dst_amount: u64,
-) -> Result<(u64, u64, u64)> {
-    let integrator_fee_amount = dst_amount
-        .mul_div_floor(integrator_fee as u64, BASE_1E5)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    let mut protocol_fee_amount = dst_amount
-        .mul_div_floor(protocol_fee as u64, BASE_1E5)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    let actual_dst_amount = (dst_amount - protocol_fee_amount)
-        .checked_sub(integrator_fee_amount)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-
-    if actual_dst_amount > estimated_dst_amount {
-        protocol_fee_amount += (actual_dst_amount - estimated_dst_amount)
-            .mul_div_floor(surplus_percentage as u64, BASE_1E2)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+fn cancel_escrow_by_resolver(
+    accounts: &CancelByResolver,
+    escrow_bump: u8,
+    order: OrderConfig,
+    reward_limit: u64,
+) -> Result<()> {
+    require!(
+        order.fee.max_cancellation_premium > 0 || order.fee.max_src_cancellation_premium > 0,
+        FusionError::CancelOrderByResolverIsForbidden
+    );
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    require!(
+        current_timestamp >= order.expiration_time as i64,
+        FusionError::OrderNotExpired
+    );
+    require!(
+        order.src_asset_is_native == accounts.maker_src_ata.is_none(),
+        FusionError::InconsistentNativeSrcTrait
+    );
+
+    let order_hash = order_hash(
+        &order,
+        accounts.protocol_dst_acc.as_ref().map(|acc| acc.key()),
+        accounts.integrator_dst_acc.as_ref().map(|acc| acc.key()),
+        accounts.src_mint.key(),
+        accounts.dst_mint.key(),
+        accounts.maker_receiver.key(),
+    )?;
+
+    // Pay the cancellation premium in src tokens from the escrow
+    let src_cancellation_premium = std::cmp::min(
+        calculate_premium(
+            current_timestamp as u32,
+            order.expiration_time,
+            order.cancellation_auction_duration,
+            order.fee.max_src_cancellation_premium,
+        ),
+        std::cmp::min(reward_limit, accounts.escrow_src_ata.amount),
+    );
+    if src_cancellation_premium > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.src_token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.escrow_src_ata.to_account_info(),
+                    mint: accounts.src_mint.to_account_info(),
+                    to: accounts
+                        .resolver_src_ata
+                        .as_ref()
+                        .ok_or(FusionError::MissingResolverSrcAta)?
+                        .to_account_info(),
+                    authority: accounts.escrow.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    accounts.maker.key().as_ref(),
+                    &order_hash,
+                    &[escrow_bump],
+                ]],
+            ),
+            src_cancellation_premium,
+            accounts.src_mint.decimals,
+        )?;
     }
 
-    Ok((
-        protocol_fee_amount,
-        integrator_fee_amount,
-        dst_amount - integrator_fee_amount - protocol_fee_amount,
-    ))
+    // Return remaining src tokens back to maker
+    if !order.src_asset_is_native {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.src_token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.escrow_src_ata.to_account_info(),
+                    mint: accounts.src_mint.to_account_info(),
+                    to: accounts
+                        .maker_src_ata
+                        .as_ref()
+                        .ok_or(FusionError::MissingMakerSrcAta)?
+                        .to_account_info(),
+                    authority: accounts.escrow.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    accounts.maker.key().as_ref(),
+                    &order_hash,
+                    &[escrow_bump],
+                ]],
+            ),
+            accounts.escrow_src_ata.amount - src_cancellation_premium,
+            accounts.src_mint.decimals,
+        )?;
+    };
+
+    let cancellation_premium = calculate_premium(
+        current_timestamp as u32,
+        order.expiration_time,
+        order.cancellation_auction_duration,
+        order.fee.max_cancellation_premium,
+    );
+    let maker_amount = accounts.escrow_src_ata.to_account_info().lamports()
+        - std::cmp::min(cancellation_premium, reward_limit);
+
+    // Transfer all the remaining lamports to the resolver first
+    close_account(CpiContext::new_with_signer(
+        accounts.src_token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.escrow_src_ata.to_account_info(),
+            destination: accounts.resolver.to_account_info(),
+            authority: accounts.escrow.to_account_info(),
+        },
+        &[&[
+            "escrow".as_bytes(),
+            accounts.maker.key().as_ref(),
+            &order_hash,
+            &[escrow_bump],
+        ]],
+    ))?;
+
+    // Transfer all lamports from the closed account, minus the cancellation premium, to the maker
+    uni_transfer(&UniTransferParams::NativeTransfer {
+        from: accounts.resolver.to_account_info(),
+        to: accounts.maker.to_account_info(),
+        amount: maker_amount,
+        program: accounts.system_program.clone(),
+    })
 }
 
 fn uni_transfer(params: &UniTransferParams<'_>) -> Result<()> {
@@ -0,0 +1,281 @@
+//! Auction and fee math of a fill. Everything here is pure: time is passed in explicitly
+//! and nothing reads accounts or sysvars, so off-chain code gets the exact on-chain amounts.
+//! The math does not use the Anchor runtime: it fails with `QuoteError`, which the program
+//! converts to its own error, and `FeeAmounts` derives Borsh directly.
+
+use crate::{auction::AuctionCurve, FeeConfig, OrderConfig};
+use common::constants::*;
+use core::fmt;
+use muldiv::MulDiv;
+
+/// Error of the quote math
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteError {
+    /// An amount does not fit into `u64`
+    ArithmeticOverflow,
+}
+
+impl fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteError::ArithmeticOverflow => f.write_str("arithmetic overflow"),
+        }
+    }
+}
+
+impl core::error::Error for QuoteError {}
+
+/// Amounts of a fill of `src_amount` at a given timestamp
+pub struct Quote {
+    /// Rate bump of the auction curve, in basis points where `BASE_1E5` = 100%
+    pub rate_bump: u64,
+    /// Amount of `dst_mint` tokens the taker pays, including all fees
+    pub dst_amount: u64,
+    /// Split of `dst_amount` between the maker and the fee recipients
+    pub fee_amounts: FeeAmounts,
+}
+
+/// Amounts of `dst_mint` tokens the filled amount is split into
+// Building the IDL needs the type description generated by the Anchor derives, which implement
+// the same Borsh traits
+#[cfg_attr(
+    not(feature = "idl-build"),
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[cfg_attr(
+    feature = "idl-build",
+    derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)
+)]
+#[derive(Clone, Debug)]
+pub struct FeeAmounts {
+    /// Protocol fee including the protocol surplus
+    pub protocol_fee: u64,
+    /// Integrator fee including the integrator surplus
+    pub integrator_fee: u64,
+    /// Amounts paid to the integrator fee share recipients, in the order config order
+    pub integrator_fee_shares: Vec<u64>,
+    /// Maker amount including the maker surplus
+    pub maker_amount: u64,
+    pub maker_surplus: u64,
+    pub protocol_surplus: u64,
+    pub integrator_surplus: u64,
+    /// Part of the surplus the taker keeps, i.e. does not pay at all
+    pub resolver_surplus: u64,
+}
+
+/// Quotes a fill of `src_amount` of the order at `timestamp`
+pub fn quote(order: &OrderConfig, timestamp: u64, src_amount: u64) -> Result<Quote, QuoteError> {
+    let rate_bump = order.dutch_auction_data.rate_bump(timestamp);
+    let dst_amount = get_dst_amount(
+        order.src_amount,
+        order.min_dst_amount,
+        src_amount,
+        rate_bump,
+    )?;
+    let fee_base = get_fee_base(
+        order.src_amount,
+        order.min_dst_amount,
+        src_amount,
+        rate_bump,
+    )?;
+    let estimated_dst_amount =
+        get_dst_amount(order.src_amount, order.estimated_dst_amount, src_amount, 0)?;
+
+    Ok(Quote {
+        rate_bump,
+        dst_amount,
+        fee_amounts: get_fee_amounts(&order.fee, dst_amount, fee_base, estimated_dst_amount)?,
+    })
+}
+
+// Function to get amount of `dst_mint` tokens that the taker should pay to the maker using default or the dutch auction formula
+pub fn get_dst_amount(
+    initial_src_amount: u64,
+    initial_dst_amount: u64,
+    src_amount: u64,
+    rate_bump: u64,
+) -> Result<u64, QuoteError> {
+    let mut result = initial_dst_amount
+        .mul_div_ceil(src_amount, initial_src_amount)
+        .ok_or(QuoteError::ArithmeticOverflow)?;
+
+    if rate_bump > 0 {
+        result = result
+            .mul_div_ceil(BASE_1E5 + rate_bump, BASE_1E5)
+            .ok_or(QuoteError::ArithmeticOverflow)?;
+    }
+    Ok(result)
+}
+
+/// Same as `get_dst_amount`, rounded down instead of up. Fees are taken from this amount, so
+/// the unit `dst_amount` is rounded up by always goes to the maker. Otherwise the rounded up
+/// units of a split fill could raise several fees at once, and the parts of the fill would pay
+/// the maker less than the whole of it
+fn get_fee_base(
+    initial_src_amount: u64,
+    initial_dst_amount: u64,
+    src_amount: u64,
+    rate_bump: u64,
+) -> Result<u64, QuoteError> {
+    initial_dst_amount
+        .mul_div_floor(src_amount, initial_src_amount)
+        .and_then(|amount| amount.mul_div_floor(BASE_1E5 + rate_bump, BASE_1E5))
+        .ok_or(QuoteError::ArithmeticOverflow)
+}
+
+/// Splits `dst_amount` between the maker and the fee recipients. Every fee is rounded down on
+/// its own part of `fee_base`, which is at most `dst_amount`, and the maker gets the rest
+pub fn get_fee_amounts(
+    fee: &FeeConfig,
+    dst_amount: u64,
+    fee_base: u64,
+    estimated_dst_amount: u64,
+) -> Result<FeeAmounts, QuoteError> {
+    let integrator_fee_amount = fee_base
+        .mul_div_floor(fee.integrator_fee as u64, BASE_1E5)
+        .ok_or(QuoteError::ArithmeticOverflow)?;
+
+    let protocol_fee_amount = fee_base
+        .mul_div_floor(fee.protocol_fee as u64, BASE_1E5)
+        .ok_or(QuoteError::ArithmeticOverflow)?;
+
+    let integrator_fee_share_amounts = fee
+        .integrator_fee_shares
+        .iter()
+        .map(|share| {
+            fee_base
+                .mul_div_floor(share.fee as u64, BASE_1E5)
+                .ok_or(QuoteError::ArithmeticOverflow)
+        })
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    // Sum of share fees is bounded by `BASE_1E5` at creation, so the total is at most `fee_base`
+    let integrator_fee_shares_amount: u64 = integrator_fee_share_amounts.iter().sum();
+
+    let actual_dst_amount = dst_amount
+        .checked_sub(protocol_fee_amount)
+        .and_then(|amount| amount.checked_sub(integrator_fee_amount))
+        .and_then(|amount| amount.checked_sub(integrator_fee_shares_amount))
+        .ok_or(QuoteError::ArithmeticOverflow)?;
+
+    let surplus = actual_dst_amount.saturating_sub(estimated_dst_amount);
+    let surplus_part = |percentage: u8| {
+        surplus
+            .mul_div_floor(percentage as u64, BASE_1E2)
+            .ok_or(QuoteError::ArithmeticOverflow)
+    };
+    let protocol_surplus = surplus_part(fee.surplus_percentage)?;
+    let integrator_surplus = surplus_part(fee.integrator_surplus_percentage)?;
+    let resolver_surplus = surplus_part(fee.resolver_surplus_percentage)?;
+
+    // Surplus percentages sum up to at most `BASE_1E2`, so the parts never exceed the surplus
+    let maker_surplus = surplus - protocol_surplus - integrator_surplus - resolver_surplus;
+
+    Ok(FeeAmounts {
+        protocol_fee: protocol_fee_amount + protocol_surplus,
+        integrator_fee: integrator_fee_amount + integrator_surplus,
+        integrator_fee_shares: integrator_fee_share_amounts,
+        maker_amount: actual_dst_amount - surplus + maker_surplus,
+        maker_surplus,
+        protocol_surplus,
+        integrator_surplus,
+        resolver_surplus,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auction::{AuctionData, LinearWithPlateauCurve};
+    use crate::IntegratorFeeShare;
+    use anchor_lang::prelude::Pubkey;
+
+    fn order(fee: FeeConfig) -> OrderConfig {
+        OrderConfig {
+            id: 0,
+            src_amount: 1_000,
+            min_dst_amount: 3_000,
+            estimated_dst_amount: 3_300,
+            expiration_time: 10_000,
+            src_asset_is_native: false,
+            dst_asset_is_native: false,
+            fee,
+            dutch_auction_data: AuctionData::LinearWithPlateau(LinearWithPlateauCurve {
+                start_time: 1_000,
+                duration: 1_000,
+                initial_rate_bump: 20_000,
+                plateau_duration: 0,
+            }),
+            cancellation_auction_duration: 0,
+        }
+    }
+
+    fn fee() -> FeeConfig {
+        FeeConfig {
+            protocol_fee: 0,
+            integrator_fee: 0,
+            integrator_fee_shares: vec![],
+            surplus_percentage: 0,
+            integrator_surplus_percentage: 0,
+            resolver_surplus_percentage: 0,
+            max_cancellation_premium: 0,
+            max_src_cancellation_premium: 0,
+        }
+    }
+
+    #[test]
+    fn quote_follows_auction_curve() {
+        let order = order(fee());
+
+        let amounts = quote(&order, 1_000, 1_000).unwrap();
+        assert_eq!(amounts.rate_bump, 20_000);
+        assert_eq!(amounts.dst_amount, 3_600);
+        assert_eq!(amounts.fee_amounts.maker_amount, 3_600);
+
+        let amounts = quote(&order, 1_500, 500).unwrap();
+        assert_eq!(amounts.rate_bump, 10_000);
+        assert_eq!(amounts.dst_amount, 1_650);
+
+        let amounts = quote(&order, 2_000, 1).unwrap();
+        assert_eq!(amounts.rate_bump, 0);
+        // Partial amounts are rounded up in favour of the maker
+        assert_eq!(amounts.dst_amount, 3);
+    }
+
+    #[test]
+    fn quote_splits_fees_and_surplus() {
+        let order = order(FeeConfig {
+            protocol_fee: 1_000,
+            integrator_fee: 2_000,
+            integrator_fee_shares: vec![IntegratorFeeShare {
+                recipient: Pubkey::default(),
+                fee: 1_000,
+            }],
+            surplus_percentage: 50,
+            integrator_surplus_percentage: 20,
+            resolver_surplus_percentage: 10,
+            ..fee()
+        });
+
+        let amounts = quote(&order, 0, 1_000).unwrap();
+        let fees = &amounts.fee_amounts;
+        assert_eq!(amounts.dst_amount, 3_600);
+        // 3_600 - 36 - 72 - 36 = 3_456 is 156 above the estimated amount
+        assert_eq!(fees.integrator_fee_shares, vec![36]);
+        assert_eq!(fees.protocol_surplus, 78);
+        assert_eq!(fees.integrator_surplus, 31);
+        assert_eq!(fees.resolver_surplus, 15);
+        assert_eq!(fees.maker_surplus, 32);
+        assert_eq!(fees.protocol_fee, 36 + 78);
+        assert_eq!(fees.integrator_fee, 72 + 31);
+        assert_eq!(fees.maker_amount, 3_300 + 32);
+        assert_eq!(
+            fees.maker_amount
+                + fees.protocol_fee
+                + fees.integrator_fee
+                + fees.integrator_fee_shares.iter().sum::<u64>()
+                + fees.resolver_surplus,
+            amounts.dst_amount
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Bonds of resolvers. A resolver deposits `WhitelistState::bond_mint` into a vault owned by its
+//! `ResolverBond`, and `fill` requires at least `WhitelistState::min_bond` in the bond.
+//! Bonded tokens leave through `unbond`, and stay slashable until the unbonding period passes.
+
+use crate::{error::WhitelistError, WhitelistState};
+use anchor_lang::prelude::*;
+
+/// Longest unbonding period `set_bond_config` accepts, a year
+pub const MAX_UNBONDING_PERIOD: i64 = 365 * 24 * 60 * 60;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ResolverBond {
+    pub resolver: Pubkey,
+    pub bump: u8,
+    /// Bonded amount, counted towards `WhitelistState::min_bond`
+    pub amount: u64,
+    /// Amount leaving the bond, withdrawable from `withdrawable_at`
+    pub unbonding_amount: u64,
+    /// Unix timestamp from which `unbonding_amount` can be withdrawn
+    pub withdrawable_at: i64,
+}
+
+impl ResolverBond {
+    /// Moves `amount` out of the bond, withdrawable at `withdrawable_at`. The amount already
+    /// unbonding waits for the new timestamp as well
+    pub fn unbond(&mut self, amount: u64, withdrawable_at: i64) -> Result<()> {
+        require!(amount != 0, WhitelistError::InvalidBondAmount);
+        require!(amount <= self.amount, WhitelistError::InsufficientBond);
+        self.amount -= amount;
+        self.unbonding_amount += amount;
+        self.withdrawable_at = withdrawable_at;
+        Ok(())
+    }
+
+    /// Takes `amount` from the bond, and from the unbonding amount once the bond is exhausted
+    pub fn slash(&mut self, amount: u64) -> Result<()> {
+        require!(amount != 0, WhitelistError::InvalidBondAmount);
+        let from_bond = amount.min(self.amount);
+        let from_unbonding = amount - from_bond;
+        require!(
+            from_unbonding <= self.unbonding_amount,
+            WhitelistError::InsufficientBond
+        );
+        self.amount -= from_bond;
+        self.unbonding_amount -= from_unbonding;
+        Ok(())
+    }
+}
+
+impl WhitelistState {
+    /// Whether `bond` allows the resolver to fill, no bond is required while `min_bond` is zero
+    pub fn is_bonded(&self, bond: Option<&ResolverBond>) -> bool {
+        self.min_bond == 0 || bond.is_some_and(|bond| bond.amount >= self.min_bond)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bond(amount: u64, unbonding_amount: u64) -> ResolverBond {
+        ResolverBond {
+            resolver: Pubkey::new_unique(),
+            bump: 0,
+            amount,
+            unbonding_amount,
+            withdrawable_at: 0,
+        }
+    }
+
+    #[test]
+    fn slashes_bond_before_unbonding_amount() {
+        let mut resolver_bond = bond(100, 50);
+        resolver_bond.slash(80).unwrap();
+        assert_eq!(
+            (resolver_bond.amount, resolver_bond.unbonding_amount),
+            (20, 50)
+        );
+        resolver_bond.slash(40).unwrap();
+        assert_eq!(
+            (resolver_bond.amount, resolver_bond.unbonding_amount),
+            (0, 30)
+        );
+    }
+
+    #[test]
+    fn cannot_slash_more_than_bond() {
+        let mut resolver_bond = bond(100, 50);
+        assert!(resolver_bond.slash(151).is_err());
+        assert_eq!(
+            (resolver_bond.amount, resolver_bond.unbonding_amount),
+            (100, 50)
+        );
+    }
+
+    #[test]
+    fn unbond_restarts_delay() {
+        let mut resolver_bond = bond(100, 0);
+        resolver_bond.unbond(30, 10).unwrap();
+        resolver_bond.unbond(20, 20).unwrap();
+        assert_eq!(
+            (resolver_bond.amount, resolver_bond.unbonding_amount),
+            (50, 50)
+        );
+        assert_eq!(resolver_bond.withdrawable_at, 20);
+        assert!(resolver_bond.unbond(51, 30).is_err());
+    }
+}
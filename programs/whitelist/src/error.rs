@@ -4,4 +4,40 @@ use anchor_lang::error_code;
 pub enum WhitelistError {
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+    #[msg("Role already granted")]
+    RoleAlreadyGranted,
+    #[msg("Role not granted")]
+    RoleNotGranted,
+    #[msg("Too many role members")]
+    TooManyRoleMembers,
+    #[msg("Access must be valid from before it is valid until")]
+    InvalidAccessPeriod,
+    #[msg("Resolver is already suspended")]
+    ResolverAlreadySuspended,
+    #[msg("Resolver is not suspended")]
+    ResolverNotSuspended,
+    #[msg("Unknown permission bits")]
+    InvalidPermissions,
+    #[msg("Label is too long")]
+    LabelTooLong,
+    #[msg("Payer does not match the resolver access")]
+    InvalidPayer,
+    #[msg("Inconsistent batch accounts")]
+    InconsistentBatchAccounts,
+    #[msg("Unbonding period must be between zero and a year")]
+    InvalidUnbondingPeriod,
+    #[msg("Bond mint can not be changed")]
+    BondMintChanged,
+    #[msg("Mint is not the bond mint")]
+    InvalidBondMint,
+    #[msg("Bond amount must be positive")]
+    InvalidBondAmount,
+    #[msg("Not enough tokens in the bond")]
+    InsufficientBond,
+    #[msg("Unbonding period has not passed")]
+    UnbondingNotElapsed,
+    #[msg("Account is already migrated")]
+    AlreadyMigrated,
 }
@@ -1,41 +1,485 @@
 use anchor_lang::prelude::*;
 use common::constants::DISCRIMINATOR;
 
+pub mod bond;
 pub mod error;
+pub mod merkle;
+pub mod migration;
+pub mod permissions;
+pub mod registry;
+pub mod role;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+use bond::{ResolverBond, MAX_UNBONDING_PERIOD};
 use error::WhitelistError;
+use migration::{
+    ResolverAccessV0, WhitelistStateV0, RESOLVER_ACCESS_VERSION, WHITELIST_STATE_VERSION,
+};
+use registry::{ResolverPage, MAX_LABEL_LEN};
+use role::{Role, MAX_ROLE_MEMBERS};
 
 declare_id!("5jzZhrzqkbdwp5d3J1XbmaXMRnqeXimM1mDMoGHyvR7S");
 
 pub const WHITELIST_STATE_SEED: &[u8] = b"whitelist_state";
 pub const RESOLVER_ACCESS_SEED: &[u8] = b"resolver_access";
+pub const RESOLVER_DELEGATE_SEED: &[u8] = b"resolver_delegate";
+pub const RESOLVER_PAGE_SEED: &[u8] = b"resolver_page";
+pub const RESOLVER_BOND_SEED: &[u8] = b"resolver_bond";
+
+/// Number of accounts per user in `register_many`, same as in `Register`
+pub const REGISTER_ACCOUNTS_LEN: usize = 6;
+/// Number of accounts per user in `deregister_many`, same as in `Deregister`
+pub const DEREGISTER_ACCOUNTS_LEN: usize = 6;
 
 /// Program for managing whitelisted users for the Fusion Swap
 #[program]
 pub mod whitelist {
     use super::*;
 
-    /// Initializes the whitelist with the authority
+    /// Initializes the whitelist with the authority, which is also granted the operator role
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let whitelist_state = &mut ctx.accounts.whitelist_state;
+        whitelist_state.version = WHITELIST_STATE_VERSION;
         whitelist_state.authority = ctx.accounts.authority.key();
+        whitelist_state.grant_role(Role::Operator, ctx.accounts.authority.key())
+    }
+
+    /// Migrates the whitelist state initialized before its layout was versioned to the current
+    /// layout, by its authority, who pays for the extra rent. The authority is granted the
+    /// operator role as on `initialize`, all other settings start at their defaults
+    pub fn migrate_whitelist_state(ctx: Context<MigrateWhitelistState>) -> Result<()> {
+        let whitelist_state = ctx.accounts.whitelist_state.to_account_info();
+        let old = migration::read_v0::<WhitelistState, WhitelistStateV0>(&whitelist_state)?;
+        require_keys_eq!(
+            old.authority,
+            ctx.accounts.authority.key(),
+            WhitelistError::Unauthorized
+        );
+
+        migration::write_migrated(
+            &whitelist_state,
+            DISCRIMINATOR + WhitelistState::INIT_SPACE,
+            &WhitelistState::migrate(old),
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+        )
+    }
+
+    /// Suspends the user access without closing its account
+    pub fn suspend(ctx: Context<Suspend>, user: Pubkey) -> Result<()> {
+        let resolver_access = &mut ctx.accounts.resolver_access;
+        require!(
+            resolver_access.status == ResolverStatus::Active,
+            WhitelistError::ResolverAlreadySuspended
+        );
+        resolver_access.status = ResolverStatus::Suspended;
+
+        emit!(ResolverSuspended { user });
+        Ok(())
+    }
+
+    /// Reinstates the suspended user access
+    pub fn reinstate(ctx: Context<Reinstate>, user: Pubkey) -> Result<()> {
+        let resolver_access = &mut ctx.accounts.resolver_access;
+        require!(
+            resolver_access.status == ResolverStatus::Suspended,
+            WhitelistError::ResolverNotSuspended
+        );
+        resolver_access.status = ResolverStatus::Active;
+
+        emit!(ResolverReinstated { user });
+        Ok(())
+    }
+
+    /// Migrates the access of `user` registered before its layout was versioned to the current
+    /// layout by an operator and adds the user to the resolver registry. The access keeps all
+    /// permissions without an expiry, `payer` pays the extra rent and gets the whole rent back on
+    /// `deregister`
+    pub fn migrate_resolver_access(
+        ctx: Context<MigrateResolverAccess>,
+        user: Pubkey,
+    ) -> Result<()> {
+        let resolver_access = ctx.accounts.resolver_access.to_account_info();
+        let old = migration::read_v0::<ResolverAccess, ResolverAccessV0>(&resolver_access)?;
+        let registry_page = ctx
+            .accounts
+            .whitelist_state
+            .add_to_registry(&mut ctx.accounts.resolver_page, user);
+
+        migration::write_migrated(
+            &resolver_access,
+            DISCRIMINATOR + ResolverAccess::INIT_SPACE,
+            &ResolverAccess::migrate(
+                old,
+                user,
+                ctx.accounts.payer.key(),
+                Clock::get()?.unix_timestamp,
+                ctx.accounts.whitelist_state.next_generation(),
+                registry_page,
+            ),
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )
+    }
+
+    /// Grants `role` to `user`
+    pub fn grant_role(ctx: Context<ManageRole>, role: Role, user: Pubkey) -> Result<()> {
+        ctx.accounts.whitelist_state.grant_role(role, user)?;
+
+        emit!(RoleGranted { role, user });
+        Ok(())
+    }
+
+    /// Revokes `role` from `user`
+    pub fn revoke_role(ctx: Context<ManageRole>, role: Role, user: Pubkey) -> Result<()> {
+        ctx.accounts.whitelist_state.revoke_role(role, &user)?;
+
+        emit!(RoleRevoked { role, user });
+        Ok(())
+    }
+
+    /// Registers a new user to the whitelist with access from `valid_from`
+    /// until `valid_until`, both unix timestamps, to the instructions allowed by `permissions`.
+    /// The user is added to the current page of the resolver registry under `label`.
+    /// `payer` pays the rent of the access and gets it back on `deregister`
+    pub fn register(
+        ctx: Context<Register>,
+        user: Pubkey,
+        valid_from: i64,
+        valid_until: i64,
+        permissions: u8,
+        label: String,
+    ) -> Result<()> {
+        register_resolver(
+            ctx.accounts,
+            &ctx.bumps,
+            Registration {
+                user,
+                valid_from,
+                valid_until,
+                permissions,
+                label,
+            },
+        )
+    }
+
+    /// Registers several users in one instruction. Every user takes a group of
+    /// `REGISTER_ACCOUNTS_LEN` remaining accounts laid out as in `register`, with the registry
+    /// page that is current once the previous users of the batch are added
+    pub fn register_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterMany<'info>>,
+        registrations: Vec<Registration>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == registrations.len() * REGISTER_ACCOUNTS_LEN,
+            WhitelistError::InconsistentBatchAccounts
+        );
+
+        for (registration, group) in registrations
+            .into_iter()
+            .zip(ctx.remaining_accounts.chunks(REGISTER_ACCOUNTS_LEN))
+        {
+            let mut accounts = group;
+            let mut bumps = RegisterBumps::default();
+            let mut register_accounts = Register::try_accounts(
+                ctx.program_id,
+                &mut accounts,
+                &registration.try_to_vec()?,
+                &mut bumps,
+                &mut Default::default(),
+            )?;
+            require_keys_eq!(
+                register_accounts.authority.key(),
+                ctx.accounts.authority.key(),
+                WhitelistError::InconsistentBatchAccounts
+            );
+
+            register_resolver(&mut register_accounts, &bumps, registration)?;
+            register_accounts.exit(ctx.program_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the permissions of the user
+    pub fn update_permissions(
+        ctx: Context<UpdatePermissions>,
+        user: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        require!(
+            permissions & !permissions::ALL == 0,
+            WhitelistError::InvalidPermissions
+        );
+        ctx.accounts.resolver_access.permissions = permissions;
+
+        emit!(PermissionsUpdated { user, permissions });
+        Ok(())
+    }
+
+    /// Moves the end of the user access to `valid_until`, e.g. on KYC renewal
+    pub fn renew(ctx: Context<Renew>, user: Pubkey, valid_until: i64) -> Result<()> {
+        let resolver_access = &mut ctx.accounts.resolver_access;
+        require!(
+            resolver_access.valid_from < valid_until,
+            WhitelistError::InvalidAccessPeriod
+        );
+        resolver_access.valid_until = valid_until;
+
+        emit!(ResolverRenewed {
+            user,
+            expires_at: valid_until,
+        });
+        Ok(())
+    }
+
+    /// Removes a user from the whitelist and the resolver registry, refunding the rent of the
+    /// access to its payer
+    pub fn deregister(ctx: Context<Deregister>, user: Pubkey) -> Result<()> {
+        ctx.accounts
+            .whitelist_state
+            .remove_from_registry(&mut ctx.accounts.resolver_page, &user);
+        Ok(())
+    }
+
+    /// Removes several users in one instruction. Every user takes a group of
+    /// `DEREGISTER_ACCOUNTS_LEN` remaining accounts laid out as in `deregister`
+    pub fn deregister_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeregisterMany<'info>>,
+        users: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == users.len() * DEREGISTER_ACCOUNTS_LEN,
+            WhitelistError::InconsistentBatchAccounts
+        );
+
+        for (user, group) in users
+            .into_iter()
+            .zip(ctx.remaining_accounts.chunks(DEREGISTER_ACCOUNTS_LEN))
+        {
+            let mut accounts = group;
+            let mut bumps = DeregisterBumps::default();
+            let mut deregister_accounts = Deregister::try_accounts(
+                ctx.program_id,
+                &mut accounts,
+                &user.try_to_vec()?,
+                &mut bumps,
+                &mut Default::default(),
+            )?;
+            require_keys_eq!(
+                deregister_accounts.authority.key(),
+                ctx.accounts.authority.key(),
+                WhitelistError::InconsistentBatchAccounts
+            );
+
+            deregister_accounts
+                .whitelist_state
+                .remove_from_registry(&mut deregister_accounts.resolver_page, &user);
+            deregister_accounts.exit(ctx.program_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Authorizes `delegate` to sign `fill` and `cancel_by_resolver` on behalf of the
+    /// registered resolver, which pays for the delegate account. Both of them sign, and the
+    /// delegate is only valid for the current registration of the resolver
+    pub fn add_delegate(ctx: Context<AddDelegate>) -> Result<()> {
+        let resolver_delegate = &mut ctx.accounts.resolver_delegate;
+        resolver_delegate.resolver = ctx.accounts.resolver.key();
+        resolver_delegate.bump = ctx.bumps.resolver_delegate;
+        resolver_delegate.generation = ctx.accounts.resolver_access.generation;
+
+        emit!(DelegateAdded {
+            resolver: resolver_delegate.resolver,
+            delegate: ctx.accounts.delegate.key(),
+        });
+        Ok(())
+    }
+
+    /// Revokes `delegate` of the resolver and returns the rent to the resolver
+    pub fn remove_delegate(ctx: Context<RemoveDelegate>, delegate: Pubkey) -> Result<()> {
+        emit!(DelegateRemoved {
+            resolver: ctx.accounts.resolver.key(),
+            delegate,
+        });
+        Ok(())
+    }
+
+    /// Replaces the Merkle root of resolvers allowed to fill with a proof instead of a
+    /// `ResolverAccess` account, `None` disables filling with a proof
+    pub fn set_resolver_root(ctx: Context<SetResolverRoot>, root: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.whitelist_state.resolver_root = root;
+
+        emit!(ResolverRootUpdated { root });
+        Ok(())
+    }
+
+    /// Requires resolvers to bond at least `min_bond` of `bond_mint` to fill, the native mint
+    /// for SOL bonds. A zero `min_bond` makes the bond optional. Unbonded tokens are withdrawable
+    /// `unbonding_period` seconds after `unbond`, at most `MAX_UNBONDING_PERIOD`. The mint can
+    /// not be changed once set
+    pub fn set_bond_config(
+        ctx: Context<SetBondConfig>,
+        min_bond: u64,
+        unbonding_period: i64,
+    ) -> Result<()> {
+        require!(
+            (0..=MAX_UNBONDING_PERIOD).contains(&unbonding_period),
+            WhitelistError::InvalidUnbondingPeriod
+        );
+        let whitelist_state = &mut ctx.accounts.whitelist_state;
+        let bond_mint = ctx.accounts.bond_mint.key();
+        require!(
+            whitelist_state.bond_mint.is_none() || whitelist_state.bond_mint == Some(bond_mint),
+            WhitelistError::BondMintChanged
+        );
+        whitelist_state.bond_mint = Some(bond_mint);
+        whitelist_state.min_bond = min_bond;
+        whitelist_state.unbonding_period = unbonding_period;
+
+        emit!(BondConfigUpdated {
+            bond_mint,
+            min_bond,
+            unbonding_period,
+        });
+        Ok(())
+    }
+
+    /// Deposits `amount` of the bond mint to the bond of the resolver
+    pub fn bond(ctx: Context<Bond>, amount: u64) -> Result<()> {
+        require!(amount != 0, WhitelistError::InvalidBondAmount);
+
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.resolver_src_ata.to_account_info(),
+                    mint: ctx.accounts.bond_mint.to_account_info(),
+                    to: ctx.accounts.bond_vault.to_account_info(),
+                    authority: ctx.accounts.resolver.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.bond_mint.decimals,
+        )?;
+
+        let resolver_bond = &mut ctx.accounts.resolver_bond;
+        resolver_bond.resolver = ctx.accounts.resolver.key();
+        resolver_bond.bump = ctx.bumps.resolver_bond;
+        resolver_bond.amount += amount;
+
+        emit!(Bonded {
+            resolver: resolver_bond.resolver,
+            amount,
+        });
         Ok(())
     }
 
-    /// Registers a new user to the whitelist
-    pub fn register(ctx: Context<Register>, _user: Pubkey) -> Result<()> {
-        ctx.accounts.resolver_access.bump = ctx.bumps.resolver_access;
+    /// Starts unbonding `amount` of the bond, `withdraw_bond` releases it once the unbonding
+    /// period has passed. Until then the amount can still be slashed
+    pub fn unbond(ctx: Context<Unbond>, amount: u64) -> Result<()> {
+        let withdrawable_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(ctx.accounts.whitelist_state.unbonding_period)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        ctx.accounts.resolver_bond.unbond(amount, withdrawable_at)?;
+
+        emit!(UnbondingStarted {
+            resolver: ctx.accounts.resolver.key(),
+            amount,
+            withdrawable_at,
+        });
+        Ok(())
+    }
+
+    /// Withdraws the whole unbonding amount to the resolver once the unbonding period has passed
+    pub fn withdraw_bond(ctx: Context<WithdrawBond>) -> Result<()> {
+        let resolver_bond = &mut ctx.accounts.resolver_bond;
+        let amount = resolver_bond.unbonding_amount;
+        require!(amount != 0, WhitelistError::InvalidBondAmount);
+        require!(
+            Clock::get()?.unix_timestamp >= resolver_bond.withdrawable_at,
+            WhitelistError::UnbondingNotElapsed
+        );
+        resolver_bond.unbonding_amount = 0;
+
+        transfer_from_vault(
+            &ctx.accounts.resolver_bond,
+            &ctx.accounts.bond_vault,
+            &ctx.accounts.bond_mint,
+            ctx.accounts.resolver_dst_ata.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        emit!(BondWithdrawn {
+            resolver: ctx.accounts.resolver.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Slashes `amount` of the bond of `resolver` to `slash_dst_ata`. The unbonding amount is
+    /// slashed once the bond is exhausted
+    pub fn slash(ctx: Context<Slash>, resolver: Pubkey, amount: u64) -> Result<()> {
+        ctx.accounts.resolver_bond.slash(amount)?;
+
+        transfer_from_vault(
+            &ctx.accounts.resolver_bond,
+            &ctx.accounts.bond_vault,
+            &ctx.accounts.bond_mint,
+            ctx.accounts.slash_dst_ata.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        emit!(Slashed { resolver, amount });
         Ok(())
     }
 
-    /// Removes a user from the whitelist
-    pub fn deregister(_ctx: Context<Deregister>, _user: Pubkey) -> Result<()> {
+    /// Proposes `new_authority` as the next whitelist authority.
+    /// The authority is not changed until the proposed account accepts it,
+    /// a new proposal replaces the pending one
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let whitelist_state = &mut ctx.accounts.whitelist_state;
+        whitelist_state.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            authority: whitelist_state.authority,
+            pending_authority: new_authority,
+        });
         Ok(())
     }
 
-    /// Sets the new whitelist authority
-    pub fn set_authority(ctx: Context<SetAuthority>, new_authority: Pubkey) -> Result<()> {
+    /// Accepts the pending authority transfer by the proposed authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
         let whitelist_state = &mut ctx.accounts.whitelist_state;
-        whitelist_state.authority = new_authority;
+        let previous_authority = whitelist_state.authority;
+        whitelist_state.authority = ctx.accounts.pending_authority.key();
+        whitelist_state.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            new_authority: whitelist_state.authority,
+        });
+        Ok(())
+    }
+
+    /// Cancels the pending authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        let whitelist_state = &mut ctx.accounts.whitelist_state;
+        let pending_authority = whitelist_state
+            .pending_authority
+            .take()
+            .ok_or(WhitelistError::NoPendingAuthority)?;
+
+        emit!(AuthorityTransferCancelled {
+            authority: whitelist_state.authority,
+            pending_authority,
+        });
         Ok(())
     }
 }
@@ -57,66 +501,512 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateWhitelistState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Whitelist state in the layout before versioning
+    #[account(
+        mut,
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: deserialized from the layout before versioning and checked to be held by `authority`
+    pub whitelist_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(user: Pubkey)]
-pub struct Register<'info> {
+pub struct MigrateResolverAccess<'info> {
+    pub authority: Signer<'info>,
+
+    /// Account paying the extra rent of the resolver access and the registry page
     #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+      mut,
+      seeds = [WHITELIST_STATE_SEED],
+      bump,
+      // Ensures only operators can migrate users
+      constraint = whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    /// Resolver access in the layout before versioning
+    #[account(
+        mut,
+        seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: deserialized from the layout before versioning
+    pub resolver_access: UncheckedAccount<'info>,
+
+    /// Current page of the resolver registry, created by the first registration to it
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = DISCRIMINATOR + ResolverPage::INIT_SPACE,
+        seeds = [RESOLVER_PAGE_SEED, whitelist_state.current_page.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub resolver_page: Account<'info, ResolverPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Register<'info> {
     pub authority: Signer<'info>,
 
+    /// Account paying the rent of the resolver access and the registry page
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
+      mut,
       seeds = [WHITELIST_STATE_SEED],
       bump,
-      // Ensures only the whitelist authority can register new users
-      constraint = whitelist_state.authority == authority.key() @ WhitelistError::Unauthorized
+      // Ensures only operators can register new users
+      constraint = whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
     )]
     pub whitelist_state: Account<'info, WhitelistState>,
 
     #[account(
         init,
-        payer = authority,
+        payer = payer,
         space = DISCRIMINATOR + ResolverAccess::INIT_SPACE,
         seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
         bump,
     )]
     pub resolver_access: Account<'info, ResolverAccess>,
 
+    /// Current page of the resolver registry, created by the first registration to it
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = DISCRIMINATOR + ResolverPage::INIT_SPACE,
+        seeds = [RESOLVER_PAGE_SEED, whitelist_state.current_page.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub resolver_page: Account<'info, ResolverPage>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RegisterMany<'info> {
+    /// Operator registering the users, the same in every group of accounts
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(user: Pubkey)]
 pub struct Deregister<'info> {
-    #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Payer of the resolver access, receiving its rent back
+    #[account(mut)]
+    /// CHECK: checked to be the payer recorded in the resolver access
+    pub payer: UncheckedAccount<'info>,
+
     #[account(
+      mut,
       seeds = [WHITELIST_STATE_SEED],
       bump,
-      // Ensures only the whitelist authority can deregister users from the whitelist
-      constraint = whitelist_state.authority == authority.key() @ WhitelistError::Unauthorized
+      // Ensures only operators can deregister users from the whitelist
+      constraint = whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
     )]
     pub whitelist_state: Account<'info, WhitelistState>,
 
     #[account(
         mut,
-        close = authority,
+        close = payer,
+        has_one = payer @ WhitelistError::InvalidPayer,
         seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
         bump,
     )]
     pub resolver_access: Account<'info, ResolverAccess>,
 
+    /// Page of the resolver registry holding the user
+    #[account(
+        mut,
+        seeds = [RESOLVER_PAGE_SEED, resolver_access.registry_page.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub resolver_page: Account<'info, ResolverPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterMany<'info> {
+    /// Operator deregistering the users, the same in every group of accounts
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddDelegate<'info> {
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+
+    /// Key signing on behalf of the resolver, which proves it accepts the delegation
+    pub delegate: Signer<'info>,
+
+    // Ensures only registered resolvers can add delegates
+    #[account(
+        seeds = [RESOLVER_ACCESS_SEED, resolver.key().as_ref()],
+        bump = resolver_access.bump,
+    )]
+    pub resolver_access: Account<'info, ResolverAccess>,
+
+    #[account(
+        init,
+        payer = resolver,
+        space = DISCRIMINATOR + ResolverDelegate::INIT_SPACE,
+        seeds = [RESOLVER_DELEGATE_SEED, delegate.key().as_ref()],
+        bump,
+    )]
+    pub resolver_delegate: Account<'info, ResolverDelegate>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetAuthority<'info> {
+#[instruction(delegate: Pubkey)]
+pub struct RemoveDelegate<'info> {
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+
+    #[account(
+        mut,
+        close = resolver,
+        seeds = [RESOLVER_DELEGATE_SEED, delegate.key().as_ref()],
+        bump = resolver_delegate.bump,
+        // Ensures only the resolver that added the delegate can remove it
+        constraint = resolver_delegate.resolver == resolver.key() @ WhitelistError::Unauthorized
+    )]
+    pub resolver_delegate: Account<'info, ResolverDelegate>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Renew<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      seeds = [WHITELIST_STATE_SEED],
+      bump,
+      // Ensures only operators can renew user access
+      constraint = whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
+        bump = resolver_access.bump,
+    )]
+    pub resolver_access: Account<'info, ResolverAccess>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct UpdatePermissions<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      seeds = [WHITELIST_STATE_SEED],
+      bump,
+      // Ensures only operators can update permissions of users
+      constraint = whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
+        bump = resolver_access.bump,
+    )]
+    pub resolver_access: Account<'info, ResolverAccess>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Suspend<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      seeds = [WHITELIST_STATE_SEED],
+      bump,
+      // Ensures only guardians and operators can suspend users
+      constraint = whitelist_state.has_role(Role::Guardian, &authority.key())
+          || whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
+        bump = resolver_access.bump,
+    )]
+    pub resolver_access: Account<'info, ResolverAccess>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct Reinstate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      seeds = [WHITELIST_STATE_SEED],
+      bump,
+      // Ensures only operators can reinstate users
+      constraint = whitelist_state.has_role(Role::Operator, &authority.key()) @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_ACCESS_SEED, user.key().as_ref()],
+        bump = resolver_access.bump,
+    )]
+    pub resolver_access: Account<'info, ResolverAccess>,
+}
+
+#[derive(Accounts)]
+pub struct ManageRole<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        // Ensures only the whitelist authority can manage roles
+        constraint = whitelist_state.authority == authority.key() @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+}
+
+#[derive(Accounts)]
+pub struct SetResolverRoot<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        // Ensures only the authority can replace the resolver set
+        constraint = whitelist_state.authority == authority.key() @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+}
+
+#[derive(Accounts)]
+pub struct SetBondConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        // Ensures only the authority can change the bond requirement
+        constraint = whitelist_state.authority == authority.key() @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    /// Asset resolvers bond
+    pub bond_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct Bond<'info> {
     #[account(mut)]
+    pub resolver: Signer<'info>,
+
+    #[account(
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        space = DISCRIMINATOR + ResolverBond::INIT_SPACE,
+        seeds = [RESOLVER_BOND_SEED, resolver.key().as_ref()],
+        bump,
+    )]
+    pub resolver_bond: Account<'info, ResolverBond>,
+
+    #[account(
+        constraint = whitelist_state.bond_mint == Some(bond_mint.key()) @ WhitelistError::InvalidBondMint,
+    )]
+    pub bond_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = bond_mint,
+        token::authority = resolver,
+        token::token_program = token_program,
+    )]
+    pub resolver_src_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// ATA of the bond mint owned by the resolver bond, holding the bonded and unbonding amounts
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        associated_token::mint = bond_mint,
+        associated_token::authority = resolver_bond,
+        associated_token::token_program = token_program,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unbond<'info> {
+    pub resolver: Signer<'info>,
+
+    #[account(
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_BOND_SEED, resolver.key().as_ref()],
+        bump = resolver_bond.bump,
+    )]
+    pub resolver_bond: Account<'info, ResolverBond>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBond<'info> {
+    pub resolver: Signer<'info>,
+
+    #[account(
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_BOND_SEED, resolver.key().as_ref()],
+        bump = resolver_bond.bump,
+    )]
+    pub resolver_bond: Account<'info, ResolverBond>,
+
+    #[account(
+        constraint = whitelist_state.bond_mint == Some(bond_mint.key()) @ WhitelistError::InvalidBondMint,
+    )]
+    pub bond_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = bond_mint,
+        associated_token::authority = resolver_bond,
+        associated_token::token_program = token_program,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = bond_mint,
+        token::authority = resolver,
+        token::token_program = token_program,
+    )]
+    pub resolver_dst_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(resolver: Pubkey)]
+pub struct Slash<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        // Ensures only the authority can slash bonds
+        constraint = whitelist_state.authority == authority.key() @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+
+    #[account(
+        mut,
+        seeds = [RESOLVER_BOND_SEED, resolver.key().as_ref()],
+        bump = resolver_bond.bump,
+    )]
+    pub resolver_bond: Account<'info, ResolverBond>,
+
+    #[account(
+        constraint = whitelist_state.bond_mint == Some(bond_mint.key()) @ WhitelistError::InvalidBondMint,
+    )]
+    pub bond_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = bond_mint,
+        associated_token::authority = resolver_bond,
+        associated_token::token_program = token_program,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Account receiving the slashed tokens
+    #[account(
+        mut,
+        token::mint = bond_mint,
+        token::token_program = token_program,
+    )]
+    pub slash_dst_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
     pub current_authority: Signer<'info>,
     #[account(
         mut,
         seeds = [WHITELIST_STATE_SEED],
         bump,
-        // Ensures only the current authority can set new authority
+        // Ensures only the current authority can propose new authority
+        constraint = whitelist_state.authority == current_authority.key() @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        // Ensures only the proposed authority can accept the transfer
+        constraint = whitelist_state.pending_authority == Some(pending_authority.key()) @ WhitelistError::Unauthorized
+    )]
+    pub whitelist_state: Account<'info, WhitelistState>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    pub current_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [WHITELIST_STATE_SEED],
+        bump,
+        // Ensures only the current authority can cancel the transfer
         constraint = whitelist_state.authority == current_authority.key() @ WhitelistError::Unauthorized
     )]
     pub whitelist_state: Account<'info, WhitelistState>,
@@ -125,11 +1015,263 @@ pub struct SetAuthority<'info> {
 #[account]
 #[derive(InitSpace)]
 pub struct WhitelistState {
+    /// Version of the layout, `migration::WHITELIST_STATE_VERSION`
+    pub version: u8,
     pub authority: Pubkey,
+    /// Authority proposed by `propose_authority`, waiting to accept the transfer
+    pub pending_authority: Option<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub operators: Vec<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub guardians: Vec<Pubkey>,
+    /// Merkle root of the `merkle` tree of resolvers allowed to fill with a proof
+    pub resolver_root: Option<[u8; 32]>,
+    /// Number of registered resolvers
+    pub resolver_count: u32,
+    /// Page of the resolver registry new resolvers are added to
+    pub current_page: u32,
+    /// Number of registrations so far, including deregistered ones
+    pub registrations: u64,
+    /// Asset of the resolver bonds, set by `set_bond_config`
+    pub bond_mint: Option<Pubkey>,
+    /// Bonded amount required to fill, zero if no bond is required
+    pub min_bond: u64,
+    /// Seconds between `unbond` and the withdrawal of the unbonded amount
+    pub unbonding_period: i64,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct ResolverAccess {
+    /// Version of the layout, `migration::RESOLVER_ACCESS_VERSION`
+    pub version: u8,
+    pub bump: u8,
+    pub resolver: Pubkey,
+    /// Account that paid the rent of the access, refunded on deregistration
+    pub payer: Pubkey,
+    /// Unix timestamp of the registration
+    pub registered_at: i64,
+    /// Number of the registration among all registrations, see `WhitelistState::registrations`
+    pub generation: u64,
+    #[max_len(MAX_LABEL_LEN)]
+    pub label: String,
+    /// Page of the resolver registry holding the resolver
+    pub registry_page: u32,
+    /// Unix timestamp from which the access is valid
+    pub valid_from: i64,
+    /// Unix timestamp at which the access lapses
+    pub valid_until: i64,
+    pub status: ResolverStatus,
+    /// Bitmask of the `permissions` bits
+    pub permissions: u8,
+}
+
+/// Key allowed to sign for `resolver`, derived from the delegate key
+#[account]
+#[derive(InitSpace)]
+pub struct ResolverDelegate {
+    pub resolver: Pubkey,
     pub bump: u8,
+    /// `ResolverAccess::generation` of the resolver when the delegate was added. The delegate
+    /// is not valid for a later registration of the resolver
+    pub generation: u64,
+}
+
+impl ResolverDelegate {
+    pub fn is_valid_for(&self, resolver_access: &ResolverAccess) -> bool {
+        self.resolver == resolver_access.resolver && self.generation == resolver_access.generation
+    }
+}
+
+/// Arguments of `register` for one user of `register_many`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Registration {
+    pub user: Pubkey,
+    pub valid_from: i64,
+    pub valid_until: i64,
+    pub permissions: u8,
+    pub label: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum ResolverStatus {
+    Active,
+    /// Set by `suspend`, the resolver can not fill or cancel orders until reinstated
+    Suspended,
+}
+
+impl ResolverAccess {
+    pub fn is_valid_at(&self, timestamp: i64) -> bool {
+        self.valid_from <= timestamp && timestamp < self.valid_until
+    }
+
+    pub fn has_permission(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
+}
+
+fn register_resolver(
+    accounts: &mut Register,
+    bumps: &RegisterBumps,
+    registration: Registration,
+) -> Result<()> {
+    require!(
+        registration.valid_from < registration.valid_until,
+        WhitelistError::InvalidAccessPeriod
+    );
+    require!(
+        registration.permissions & !permissions::ALL == 0,
+        WhitelistError::InvalidPermissions
+    );
+    require!(
+        registration.label.len() <= MAX_LABEL_LEN,
+        WhitelistError::LabelTooLong
+    );
+
+    let resolver_access = &mut accounts.resolver_access;
+    resolver_access.version = RESOLVER_ACCESS_VERSION;
+    resolver_access.bump = bumps.resolver_access;
+    resolver_access.resolver = registration.user;
+    resolver_access.payer = accounts.payer.key();
+    resolver_access.registered_at = Clock::get()?.unix_timestamp;
+    resolver_access.generation = accounts.whitelist_state.next_generation();
+    resolver_access.label = registration.label;
+    resolver_access.registry_page = accounts
+        .whitelist_state
+        .add_to_registry(&mut accounts.resolver_page, registration.user);
+    resolver_access.valid_from = registration.valid_from;
+    resolver_access.valid_until = registration.valid_until;
+    resolver_access.status = ResolverStatus::Active;
+    resolver_access.permissions = registration.permissions;
+    Ok(())
+}
+
+fn transfer_from_vault<'info>(
+    resolver_bond: &Account<'info, ResolverBond>,
+    bond_vault: &InterfaceAccount<'info, TokenAccount>,
+    bond_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: bond_vault.to_account_info(),
+                mint: bond_mint.to_account_info(),
+                to,
+                authority: resolver_bond.to_account_info(),
+            },
+            &[&[
+                RESOLVER_BOND_SEED,
+                resolver_bond.resolver.as_ref(),
+                &[resolver_bond.bump],
+            ]],
+        ),
+        amount,
+        bond_mint.decimals,
+    )
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct BondConfigUpdated {
+    pub bond_mint: Pubkey,
+    pub min_bond: u64,
+    pub unbonding_period: i64,
+}
+
+#[event]
+pub struct Bonded {
+    pub resolver: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BondWithdrawn {
+    pub resolver: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DelegateAdded {
+    pub resolver: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct DelegateRemoved {
+    pub resolver: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct PermissionsUpdated {
+    pub user: Pubkey,
+    pub permissions: u8,
+}
+
+#[event]
+pub struct ResolverSuspended {
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct ResolverReinstated {
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct ResolverRenewed {
+    pub user: Pubkey,
+    /// Unix timestamp at which the renewed access lapses
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct ResolverRootUpdated {
+    pub root: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct Slashed {
+    pub resolver: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnbondingStarted {
+    pub resolver: Pubkey,
+    pub amount: u64,
+    pub withdrawable_at: i64,
 }
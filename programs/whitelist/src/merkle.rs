@@ -0,0 +1,185 @@
+//! Merkle tree of resolvers allowed to fill without a `ResolverAccess` account.
+//!
+//! A leaf commits to the resolver and the end of its access. Leaves and inner nodes are hashed
+//! with different prefixes, so that an inner node can not be passed off as a leaf. The children
+//! of a node are sorted before hashing, so a proof is just the list of sibling hashes.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf of `resolver` whose access lapses at `valid_until`, `i64::MAX` for access without expiry
+pub fn leaf(resolver: &Pubkey, valid_until: i64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, resolver.as_ref(), &valid_until.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two nodes, independent of their order
+pub fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Root of the tree with `leaves`, a node without a sibling is carried to the next level as is.
+/// Returns `None` for an empty tree
+pub fn root(leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.first().copied()
+}
+
+/// Sibling hashes from the leaf at `index` up to the root of the tree with `leaves`
+pub fn proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = vec![];
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => node(a, b),
+            [a] => *a,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Proof that a resolver is in the tree of `WhitelistState::resolver_root`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolverProof {
+    /// Unix timestamp at which the access lapses, as committed in the leaf
+    pub valid_until: i64,
+    /// Sibling hashes from the leaf up to the root
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl ResolverProof {
+    /// Whether the leaf of `resolver` and `valid_until` leads to `root`
+    pub fn verify(&self, root: &[u8; 32], resolver: &Pubkey) -> bool {
+        self.proof
+            .iter()
+            .fold(leaf(resolver, self.valid_until), |hash, sibling| {
+                node(&hash, sibling)
+            })
+            == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: usize) -> (Vec<Pubkey>, Vec<[u8; 32]>) {
+        let resolvers: Vec<_> = (0..count).map(|_| Pubkey::new_unique()).collect();
+        let leaves = resolvers
+            .iter()
+            .enumerate()
+            .map(|(i, resolver)| leaf(resolver, i as i64))
+            .collect();
+        (resolvers, leaves)
+    }
+
+    #[test]
+    fn proves_every_leaf() {
+        for count in 1..=9 {
+            let (resolvers, leaves) = leaves(count);
+            let root = root(&leaves).unwrap();
+            for (i, resolver) in resolvers.iter().enumerate() {
+                let proof = ResolverProof {
+                    valid_until: i as i64,
+                    proof: proof(&leaves, i),
+                };
+                assert!(proof.verify(&root, resolver), "leaf {i} of {count}");
+            }
+        }
+    }
+
+    #[test]
+    fn root_of_single_leaf_is_leaf() {
+        let (_, leaves) = leaves(1);
+        assert_eq!(root(&leaves), Some(leaves[0]));
+        assert!(proof(&leaves, 0).is_empty());
+    }
+
+    #[test]
+    fn proves_single_leaf_only_with_empty_proof() {
+        let (resolvers, leaves) = leaves(1);
+        let root = root(&leaves).unwrap();
+        let empty = ResolverProof {
+            valid_until: 0,
+            proof: vec![],
+        };
+        assert!(empty.verify(&root, &resolvers[0]));
+        assert!(!empty.verify(&root, &Pubkey::new_unique()));
+
+        for len in 1..=3 {
+            let padded = ResolverProof {
+                valid_until: 0,
+                proof: vec![root; len],
+            };
+            assert!(!padded.verify(&root, &resolvers[0]), "proof of {len}");
+        }
+    }
+
+    #[test]
+    fn rejects_proof_of_other_length() {
+        let (resolvers, leaves) = leaves(3);
+        let root = root(&leaves).unwrap();
+        // The third leaf has no sibling on the first level, so its proof is shorter
+        let mut proof = proof(&leaves, 2);
+        assert_eq!(proof.len(), 1);
+
+        proof.push(root);
+        let longer = ResolverProof {
+            valid_until: 2,
+            proof: proof.clone(),
+        };
+        assert!(!longer.verify(&root, &resolvers[2]));
+
+        let shorter = ResolverProof {
+            valid_until: 0,
+            proof: proof[..1].to_vec(),
+        };
+        assert!(!shorter.verify(&root, &resolvers[0]));
+    }
+
+    #[test]
+    fn root_of_empty_tree_is_none() {
+        assert_eq!(root(&[]), None);
+    }
+
+    #[test]
+    fn rejects_other_resolver() {
+        let (resolvers, leaves) = leaves(4);
+        let root = root(&leaves).unwrap();
+        let proof = ResolverProof {
+            valid_until: 0,
+            proof: proof(&leaves, 0),
+        };
+        assert!(!proof.verify(&root, &resolvers[1]));
+        assert!(!proof.verify(&root, &Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn rejects_other_expiry() {
+        let (resolvers, leaves) = leaves(4);
+        let root = root(&leaves).unwrap();
+        let proof = ResolverProof {
+            valid_until: i64::MAX,
+            proof: proof(&leaves, 0),
+        };
+        assert!(!proof.verify(&root, &resolvers[0]));
+    }
+}
@@ -0,0 +1,120 @@
+//! Migration of the accounts created before their layouts were versioned. Those accounts are
+//! smaller than the current layouts, so they are grown in place and filled with defaults
+
+use crate::{error::WhitelistError, permissions, ResolverAccess, ResolverStatus, WhitelistState};
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use common::constants::DISCRIMINATOR;
+
+/// Version of the `WhitelistState` layout
+pub const WHITELIST_STATE_VERSION: u8 = 1;
+
+/// Version of the `ResolverAccess` layout
+pub const RESOLVER_ACCESS_VERSION: u8 = 1;
+
+/// Layout of `WhitelistState` before versioning
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct WhitelistStateV0 {
+    pub authority: Pubkey,
+}
+
+/// Layout of `ResolverAccess` before versioning
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct ResolverAccessV0 {
+    pub bump: u8,
+}
+
+/// Reads `account` in its layout before versioning, which has the discriminator of `T`
+pub fn read_v0<T: Discriminator, V: AnchorDeserialize + Space>(account: &AccountInfo) -> Result<V> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() == DISCRIMINATOR + V::INIT_SPACE,
+        WhitelistError::AlreadyMigrated
+    );
+    require!(
+        data.starts_with(T::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    V::deserialize(&mut &data[DISCRIMINATOR..])
+        .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+}
+
+/// Grows `account` to `space` bytes and writes `state` to it, topping up its rent from `payer`
+pub fn write_migrated<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    state: &T,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up != 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.realloc(space, false)?;
+    state.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
+
+impl WhitelistState {
+    /// State of the whitelist held by `authority` before versioning. The authority is granted
+    /// the operator role, as by `initialize`, and everything else is left at its default
+    pub fn migrate(old: WhitelistStateV0) -> Self {
+        Self {
+            version: WHITELIST_STATE_VERSION,
+            authority: old.authority,
+            pending_authority: None,
+            operators: vec![old.authority],
+            guardians: vec![],
+            resolver_root: None,
+            resolver_count: 0,
+            current_page: 0,
+            registrations: 0,
+            bond_mint: None,
+            min_bond: 0,
+            unbonding_period: 0,
+        }
+    }
+}
+
+impl ResolverAccess {
+    /// Access of `resolver` registered before versioning. Such accesses were not limited, so the
+    /// resolver keeps all permissions for an unlimited period. The access is recorded as paid by
+    /// `payer` and registered at `registered_at` as `generation` on `registry_page`, without a
+    /// label
+    pub fn migrate(
+        old: ResolverAccessV0,
+        resolver: Pubkey,
+        payer: Pubkey,
+        registered_at: i64,
+        generation: u64,
+        registry_page: u32,
+    ) -> Self {
+        Self {
+            version: RESOLVER_ACCESS_VERSION,
+            bump: old.bump,
+            resolver,
+            payer,
+            registered_at,
+            generation,
+            label: String::new(),
+            registry_page,
+            valid_from: 0,
+            valid_until: i64::MAX,
+            status: ResolverStatus::Active,
+            permissions: permissions::ALL,
+        }
+    }
+}
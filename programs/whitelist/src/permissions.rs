@@ -0,0 +1,7 @@
+//! Bits of `ResolverAccess::permissions`, one for every Fusion Swap instruction gated by the whitelist
+
+/// Allows `fill`
+pub const FILL: u8 = 1 << 0;
+/// Allows `cancel_by_resolver` and `cancel_by_resolver_batch`
+pub const CANCEL: u8 = 1 << 1;
+pub const ALL: u8 = FILL | CANCEL;
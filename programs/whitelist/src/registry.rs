@@ -0,0 +1,47 @@
+//! Registry of the registered resolvers. Resolvers are appended to `ResolverPage` accounts,
+//! so clients can enumerate the whitelist by reading pages `0..=WhitelistState::current_page`
+
+use crate::WhitelistState;
+use anchor_lang::prelude::*;
+
+/// Maximum number of resolvers in a page
+pub const RESOLVER_PAGE_SIZE: usize = 32;
+
+/// Maximum length of a resolver label in bytes
+pub const MAX_LABEL_LEN: usize = 32;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ResolverPage {
+    #[max_len(RESOLVER_PAGE_SIZE)]
+    pub resolvers: Vec<Pubkey>,
+}
+
+impl WhitelistState {
+    /// Appends `resolver` to `page`, which must be the current page, and moves on to the next
+    /// page once it is full. Returns the index of the page
+    pub fn add_to_registry(&mut self, page: &mut ResolverPage, resolver: Pubkey) -> u32 {
+        let index = self.current_page;
+        page.resolvers.push(resolver);
+        if page.resolvers.len() == RESOLVER_PAGE_SIZE {
+            self.current_page += 1;
+        }
+        self.resolver_count += 1;
+        index
+    }
+
+    /// Counts a new registration and returns its number, the generation of its resolver access
+    pub fn next_generation(&mut self) -> u64 {
+        self.registrations += 1;
+        self.registrations
+    }
+
+    /// Removes `resolver` from `page`. The freed slot is not reused, only the current page is
+    /// appended to
+    pub fn remove_from_registry(&mut self, page: &mut ResolverPage, resolver: &Pubkey) {
+        if let Some(position) = page.resolvers.iter().position(|key| key == resolver) {
+            page.resolvers.swap_remove(position);
+        }
+        self.resolver_count -= 1;
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{error::WhitelistError, WhitelistState};
+use anchor_lang::prelude::*;
+
+/// Maximum number of accounts holding each role
+pub const MAX_ROLE_MEMBERS: usize = 8;
+
+/// Roles granted by the whitelist authority, which acts as the admin managing them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Registers and deregisters resolvers
+    Operator,
+    /// Suspends resolvers, which operators can also do
+    Guardian,
+}
+
+impl WhitelistState {
+    pub fn members(&self, role: Role) -> &[Pubkey] {
+        match role {
+            Role::Operator => &self.operators,
+            Role::Guardian => &self.guardians,
+        }
+    }
+
+    pub fn has_role(&self, role: Role, user: &Pubkey) -> bool {
+        self.members(role).contains(user)
+    }
+
+    pub fn grant_role(&mut self, role: Role, user: Pubkey) -> Result<()> {
+        let members = self.members_mut(role);
+        require!(!members.contains(&user), WhitelistError::RoleAlreadyGranted);
+        require!(
+            members.len() < MAX_ROLE_MEMBERS,
+            WhitelistError::TooManyRoleMembers
+        );
+        members.push(user);
+        Ok(())
+    }
+
+    pub fn revoke_role(&mut self, role: Role, user: &Pubkey) -> Result<()> {
+        let members = self.members_mut(role);
+        let index = members
+            .iter()
+            .position(|member| member == user)
+            .ok_or(WhitelistError::RoleNotGranted)?;
+        members.swap_remove(index);
+        Ok(())
+    }
+
+    fn members_mut(&mut self, role: Role) -> &mut Vec<Pubkey> {
+        match role {
+            Role::Operator => &mut self.operators,
+            Role::Guardian => &mut self.guardians,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+import {
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { Program } from "@coral-xyz/anchor";
+import * as splToken from "@solana/spl-token";
+
+import FUSION_IDL from "../../target/idl/fusion_swap.json";
+import { FusionSwap } from "../../target/types/fusion_swap";
+import {
+  findFeeVaultAuthorityAddress,
+  getClusterUrlEnv,
+  loadKeypairFromFile,
+  prompt_,
+} from "../utils";
+
+async function claimFees(
+  connection: Connection,
+  program: Program<FusionSwap>,
+  beneficiaryKeypair: Keypair,
+  mint: PublicKey
+): Promise<void> {
+  const feeVaultAuthority = findFeeVaultAuthorityAddress(
+    program.programId,
+    beneficiaryKeypair.publicKey
+  );
+
+  const feeVault = await splToken.getAssociatedTokenAddress(
+    mint,
+    feeVaultAuthority,
+    true
+  );
+
+  const beneficiaryDstAcc = await splToken.getAssociatedTokenAddress(
+    mint,
+    beneficiaryKeypair.publicKey
+  );
+
+  const claimFeesIx = await program.methods
+    .claimFees()
+    .accountsPartial({
+      beneficiary: beneficiaryKeypair.publicKey,
+      feeVaultAuthority,
+      mint,
+      feeVault,
+      beneficiaryDstAcc,
+      tokenProgram: splToken.TOKEN_PROGRAM_ID,
+    })
+    .signers([beneficiaryKeypair])
+    .instruction();
+
+  const tx = new Transaction().add(claimFeesIx);
+
+  const signature = await sendAndConfirmTransaction(connection, tx, [
+    beneficiaryKeypair,
+  ]);
+  console.log(`Transaction signature ${signature}`);
+}
+
+async function main() {
+  const clusterUrl = getClusterUrlEnv();
+  const beneficiaryKeypairPath = prompt_(
+    "beneficiary-kp",
+    "Enter beneficiary keypair path: "
+  );
+  const mint = new PublicKey(prompt_("mint", "Enter fee mint public key: "));
+
+  const connection = new Connection(clusterUrl, "confirmed");
+  const fusionSwap = new Program<FusionSwap>(FUSION_IDL, { connection });
+
+  const beneficiaryKeypair = await loadKeypairFromFile(beneficiaryKeypairPath);
+
+  const feeVaultAuthority = findFeeVaultAuthorityAddress(
+    fusionSwap.programId,
+    beneficiaryKeypair.publicKey
+  );
+  console.log(`Fee vault authority: ${feeVaultAuthority.toString()}`);
+
+  await claimFees(connection, fusionSwap, beneficiaryKeypair, mint);
+}
+
+main();
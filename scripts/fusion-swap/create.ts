@@ -12,6 +12,7 @@ const fs = require("fs");
 import FUSION_IDL from "../../target/idl/fusion_swap.json";
 import { FusionSwap } from "../../target/types/fusion_swap";
 import {
+  auctionDuration,
   calculateOrderHash,
   defaultAuctionData,
   defaultExpirationTime,
@@ -44,7 +45,7 @@ async function create(
   integratorDstAcc: PublicKey = null,
   estimatedDstAmount: BN = minDstAmount,
   dutchAuctionData: AuctionData = defaultAuctionData,
-  cancellationAuctionDuration: number = defaultAuctionData.duration,
+  cancellationAuctionDuration: number = auctionDuration(defaultAuctionData),
   srcTokenProgram: PublicKey = splToken.TOKEN_PROGRAM_ID
 ): Promise<[PublicKey, PublicKey]> {
   const orderConfig: OrderConfig = {
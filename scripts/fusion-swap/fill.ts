@@ -17,6 +17,7 @@ import {
   calculateOrderHash,
   findEscrowAddress,
   findResolverAccessAddress,
+  findResolverBondAddress,
   getClusterUrlEnv,
   getTokenDecimals,
   loadKeypairFromFile,
@@ -48,6 +49,7 @@ async function fill(
   );
 
   const resolverAccess = findResolverAccessAddress(whitelistProgramId, taker);
+  const resolverBond = findResolverBondAddress(whitelistProgramId, taker);
 
   const takerSrcAta = await splToken.getAssociatedTokenAddress(
     orderConfig.srcMint,
@@ -73,7 +75,9 @@ async function fill(
     .fill(orderConfig, new BN(amount * Math.pow(10, srcMintDecimals)))
     .accountsPartial({
       taker,
+      resolverDelegate: null,
       resolverAccess,
+      resolverBond,
       maker,
       makerReceiver: orderConfig.receiver,
       srcMint: orderConfig.srcMint,
@@ -88,6 +92,13 @@ async function fill(
       srcTokenProgram: splToken.TOKEN_PROGRAM_ID,
       dstTokenProgram: splToken.TOKEN_PROGRAM_ID,
     })
+    .remainingAccounts(
+      (orderConfig.fee.integratorFeeShares ?? []).map((share) => ({
+        pubkey: share.recipient,
+        isWritable: true,
+        isSigner: false,
+      }))
+    )
     .signers([takerKeypair])
     .instruction();
 
@@ -115,6 +126,12 @@ async function main() {
     dstMint: new PublicKey(orderConfigJson.dstMint),
     receiver: new PublicKey(orderConfigJson.receiver),
   };
+  orderConfig.fee.integratorFeeShares = (
+    orderConfigJson.fee.integratorFeeShares ?? []
+  ).map((share) => ({
+    recipient: new PublicKey(share.recipient),
+    fee: share.fee,
+  }));
 
   const takerKeypairPath = prompt_("taker-kp", "Enter taker keypair path");
   const takerKeypair = await loadKeypairFromFile(takerKeypairPath);
@@ -156,6 +173,10 @@ async function main() {
     orderConfigJson.fee.maxCancellationPremium,
     "hex"
   );
+  orderConfig.fee.maxSrcCancellationPremium = new BN(
+    orderConfigJson.fee.maxSrcCancellationPremium ?? 0,
+    "hex"
+  );
 
   await fill(
     connection,
@@ -15,19 +15,32 @@ const prompt = require("prompt-sync")({ sigint: true });
 export const defaultFeeConfig: FeeConfig = {
   protocolFee: 0,
   integratorFee: 0,
+  integratorFeeShares: [],
   surplusPercentage: 0,
+  integratorSurplusPercentage: 0,
+  resolverSurplusPercentage: 0,
   maxCancellationPremium: new anchor.BN(0),
+  maxSrcCancellationPremium: new anchor.BN(0),
   protocolDstAcc: null,
   integratorDstAcc: null,
 };
 
 export const defaultAuctionData: AuctionData = {
-  startTime: 0xffffffff - 32000, // default auction start in the far far future and order use default formula
-  duration: 32000,
-  initialRateBump: 0,
-  pointsAndTimeDeltas: [],
+  piecewiseLinear: {
+    0: {
+      startTime: 0, // default auction without rate bump, order uses default formula
+      duration: 32000,
+      initialRateBump: 0,
+      pointsAndTimeDeltas: [],
+    },
+  },
 };
 
+// Duration of the auction regardless of the curve kind
+export function auctionDuration(auctionData: AuctionData): number {
+  return Object.values(auctionData)[0][0].duration;
+}
+
 export async function getTokenDecimals(
   connection: Connection,
   mint: PublicKey
@@ -93,6 +106,30 @@ export function findResolverAccessAddress(
   return resolverAccess;
 }
 
+export function findResolverBondAddress(
+  programId: PublicKey,
+  resolver: PublicKey
+): PublicKey {
+  const [resolverBond] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("resolver_bond"), resolver.toBuffer()],
+    programId
+  );
+
+  return resolverBond;
+}
+
+export function findFeeVaultAuthorityAddress(
+  programId: PublicKey,
+  beneficiary: PublicKey
+): PublicKey {
+  const [feeVaultAuthority] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("fee_vault"), beneficiary.toBuffer()],
+    programId
+  );
+
+  return feeVaultAuthority;
+}
+
 export function findWhitelistStateAddress(programId: PublicKey): PublicKey {
   const [whitelistState] = PublicKey.findProgramAddressSync(
     [anchor.utils.bytes.utf8.encode("whitelist_state")],
@@ -126,20 +163,27 @@ export function calculateOrderHash(orderConfig: OrderConfig): Uint8Array {
     fee: {
       protocolFee: orderConfig.fee.protocolFee,
       integratorFee: orderConfig.fee.integratorFee,
-      surplusPercentage: orderConfig.fee.surplusPercentage,
-      maxCancellationPremium: orderConfig.fee.maxCancellationPremium,
-    },
-    dutchAuctionData: {
-      startTime: orderConfig.dutchAuctionData.startTime,
-      duration: orderConfig.dutchAuctionData.duration,
-      initialRateBump: orderConfig.dutchAuctionData.initialRateBump,
-      pointsAndTimeDeltas: orderConfig.dutchAuctionData.pointsAndTimeDeltas.map(
-        (p) => ({
-          rateBump: p.rateBump,
-          timeDelta: p.timeDelta,
+      integratorFeeShares: (orderConfig.fee.integratorFeeShares ?? []).map(
+        (s) => ({
+          recipient: s.recipient.toBuffer(),
+          fee: s.fee,
         })
       ),
+      surplusPercentage: orderConfig.fee.surplusPercentage,
+      integratorSurplusPercentage:
+        orderConfig.fee.integratorSurplusPercentage ?? 0,
+      resolverSurplusPercentage: orderConfig.fee.resolverSurplusPercentage ?? 0,
+      maxCancellationPremium: orderConfig.fee.maxCancellationPremium,
+      maxSrcCancellationPremium:
+        orderConfig.fee.maxSrcCancellationPremium ?? new anchor.BN(0),
     },
+    // Borsh enum is encoded as the variant name mapped to the curve
+    dutchAuctionData: Object.fromEntries(
+      Object.entries(orderConfig.dutchAuctionData).map(([kind, curve]) => [
+        kind,
+        curve[0],
+      ])
+    ),
     cancellationAuctionDuration: orderConfig.cancellationAuctionDuration,
 
     // Accounts concatenated directly to OrderConfig
@@ -153,6 +197,24 @@ export function calculateOrderHash(orderConfig: OrderConfig): Uint8Array {
   return sha256(borsh.serialize(orderConfigSchema, values));
 }
 
+const pointsCurveSchema = {
+  struct: {
+    startTime: "u32",
+    duration: "u32",
+    initialRateBump: "u16",
+    pointsAndTimeDeltas: {
+      array: {
+        type: {
+          struct: {
+            rateBump: "u16",
+            timeDelta: "u16",
+          },
+        },
+      },
+    },
+  },
+};
+
 const orderConfigSchema = {
   struct: {
     id: "u32",
@@ -166,26 +228,52 @@ const orderConfigSchema = {
       struct: {
         protocolFee: "u16",
         integratorFee: "u16",
+        integratorFeeShares: {
+          array: {
+            type: {
+              struct: {
+                recipient: { array: { type: "u8", len: 32 } },
+                fee: "u16",
+              },
+            },
+          },
+        },
         surplusPercentage: "u8",
+        integratorSurplusPercentage: "u8",
+        resolverSurplusPercentage: "u8",
         maxCancellationPremium: "u64",
+        maxSrcCancellationPremium: "u64",
       },
     },
     dutchAuctionData: {
-      struct: {
-        startTime: "u32",
-        duration: "u32",
-        initialRateBump: "u16",
-        pointsAndTimeDeltas: {
-          array: {
-            type: {
+      enum: [
+        { struct: { piecewiseLinear: pointsCurveSchema } },
+        {
+          struct: {
+            exponentialDecay: {
               struct: {
-                rateBump: "u16",
-                timeDelta: "u16",
+                startTime: "u32",
+                duration: "u32",
+                initialRateBump: "u16",
+                halfLife: "u32",
               },
             },
           },
         },
-      },
+        { struct: { step: pointsCurveSchema } },
+        {
+          struct: {
+            linearWithPlateau: {
+              struct: {
+                startTime: "u32",
+                duration: "u32",
+                initialRateBump: "u16",
+                plateauDuration: "u32",
+              },
+            },
+          },
+        },
+      ],
     },
     cancellationAuctionDuration: "u32",
 
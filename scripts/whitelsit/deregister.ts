@@ -26,11 +26,14 @@ async function deregister(
 ): Promise<void> {
   const whitelistState = findWhitelistStateAddress(program.programId);
   const resolverAccess = findResolverAccessAddress(program.programId, user);
+  // The rent of the access is refunded to the account that paid it
+  const { payer } = await program.account.resolverAccess.fetch(resolverAccess);
 
   const registerIx = await program.methods
     .deregister(user)
     .accountsPartial({
       authority: authorityKeypair.publicKey,
+      payer,
       whitelistState,
       resolverAccess,
     })
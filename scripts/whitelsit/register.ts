@@ -5,7 +5,7 @@ import {
   Transaction,
   sendAndConfirmTransaction,
 } from "@solana/web3.js";
-import { Program } from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
 
 import WHITELIST_IDL from "../../target/idl/whitelist.json";
 import { Whitelist } from "../../target/types/whitelist";
@@ -22,24 +22,31 @@ async function register(
   connection: Connection,
   program: Program<Whitelist>,
   authorityKeypair: Keypair,
-  user: PublicKey
+  payerKeypair: Keypair,
+  user: PublicKey,
+  validFrom: BN,
+  validUntil: BN,
+  permissions: number,
+  label: string
 ): Promise<void> {
   const whitelistState = findWhitelistStateAddress(program.programId);
   const resolverAccess = findResolverAccessAddress(program.programId, user);
 
   const registerIx = await program.methods
-    .register(user)
+    .register(user, validFrom, validUntil, permissions, label)
     .accountsPartial({
       authority: authorityKeypair.publicKey,
+      payer: payerKeypair.publicKey,
       whitelistState,
       resolverAccess,
     })
-    .signers([authorityKeypair])
+    .signers([authorityKeypair, payerKeypair])
     .instruction();
 
   const tx = new Transaction().add(registerIx);
 
   const signature = await sendAndConfirmTransaction(connection, tx, [
+    payerKeypair,
     authorityKeypair,
   ]);
   console.log(`Transaction signature ${signature}`);
@@ -57,9 +64,39 @@ async function main() {
     "Enter authority keypair path: "
   );
   const authorityKeypair = await loadKeypairFromFile(authorityKeypairPath);
+  const payerKeypairPath = prompt_(
+    "payer-kp",
+    "Enter rent payer keypair path (empty for the authority): "
+  );
+  const payerKeypair = payerKeypairPath
+    ? await loadKeypairFromFile(payerKeypairPath)
+    : authorityKeypair;
   const user = new PublicKey(prompt_("user-key", "Enter user public key: "));
+  const validFrom = new BN(
+    prompt_("valid-from", "Enter access start unix timestamp: ")
+  );
+  const validUntil = new BN(
+    prompt_("valid-until", "Enter access end unix timestamp: ")
+  );
+  const permissions = Number(
+    prompt_(
+      "permissions",
+      "Enter permissions bitmask (1 - fill, 2 - cancel, 3 - both): "
+    )
+  );
+  const label = prompt_("label", "Enter resolver label: ");
 
-  await register(connection, whitelist, authorityKeypair, user);
+  await register(
+    connection,
+    whitelist,
+    authorityKeypair,
+    payerKeypair,
+    user,
+    validFrom,
+    validUntil,
+    permissions,
+    label
+  );
 }
 
 main();
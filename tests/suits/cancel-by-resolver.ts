@@ -96,6 +96,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -160,6 +161,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -227,6 +229,7 @@ describe("Cancel by Resolver", () => {
             .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
             .accountsPartial({
               resolver: state.bob.keypair.publicKey,
+              resolverDelegate: null,
               maker: state.alice.keypair.publicKey,
               makerReceiver: escrow.orderConfig.receiver,
               srcMint: escrow.orderConfig.srcMint,
@@ -310,6 +313,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -386,6 +390,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, resolverPremium)
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -462,6 +467,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, new anchor.BN(0))
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -526,6 +532,7 @@ describe("Cancel by Resolver", () => {
       .cancelByResolver(escrow.orderConfig, new anchor.BN(0))
       .accountsPartial({
         resolver: state.bob.keypair.publicKey,
+        resolverDelegate: null,
         maker: state.alice.keypair.publicKey,
         makerReceiver: escrow.orderConfig.receiver,
         srcMint: escrow.orderConfig.srcMint,
@@ -571,6 +578,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, new anchor.BN(0))
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -609,6 +617,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
         .accountsPartial({
           resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -648,6 +657,7 @@ describe("Cancel by Resolver", () => {
         .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
         .accountsPartial({
           resolver: state.charlie.keypair.publicKey,
+          resolverDelegate: null,
           maker: state.alice.keypair.publicKey,
           makerReceiver: escrow.orderConfig.receiver,
           srcMint: escrow.orderConfig.srcMint,
@@ -685,4 +695,341 @@ describe("Cancel by Resolver", () => {
       })
     ).to.be.rejectedWith("Error Code: InvalidCancellationFee");
   });
+
+  it("Resolver receives the cancellation premium in src tokens", async () => {
+    const escrow = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: state.orderConfig({
+        srcAmount: defaultSrcAmount,
+        fee: {
+          maxCancellationPremium: new anchor.BN(0),
+          maxSrcCancellationPremium: defaultMaxCancellationPremium,
+          protocolDstAcc: undefined,
+          integratorDstAcc: undefined,
+          protocolFee: undefined,
+          integratorFee: undefined,
+          surplusPercentage: undefined,
+        },
+        cancellationAuctionDuration: order.auctionDuration,
+      }),
+    });
+
+    const makerNativeBalanceBefore = (
+      await provider.connection.getAccountInfo(state.alice.keypair.publicKey)
+    ).lamports;
+
+    await setCurrentTime(
+      context,
+      state.defaultExpirationTime + order.auctionDuration + 1
+    );
+
+    const transactionPromise = () =>
+      program.methods
+        .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
+        .accountsPartial({
+          resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
+          maker: state.alice.keypair.publicKey,
+          makerReceiver: escrow.orderConfig.receiver,
+          srcMint: escrow.orderConfig.srcMint,
+          dstMint: escrow.orderConfig.dstMint,
+          escrow: escrow.escrow,
+          escrowSrcAta: escrow.ata,
+          resolverSrcAta: state.bob.atas[state.tokens[0].toString()].address,
+          protocolDstAcc: escrow.orderConfig.fee.protocolDstAcc,
+          integratorDstAcc: escrow.orderConfig.fee.integratorDstAcc,
+          srcTokenProgram: splToken.TOKEN_PROGRAM_ID,
+        })
+        .signers([payer, state.bob.keypair])
+        .rpc({ skipPreflight: true });
+
+    const results = await trackReceivedTokenAndTx(
+      provider.connection,
+      [
+        state.alice.atas[state.tokens[0].toString()].address,
+        state.bob.atas[state.tokens[0].toString()].address,
+      ],
+      transactionPromise
+    );
+
+    const resolverPremium = defaultMaxCancellationPremium.toNumber();
+
+    expect(
+      (await provider.connection.getAccountInfo(state.alice.keypair.publicKey))
+        .lamports
+    ).to.be.eq(makerNativeBalanceBefore + tokenAccountRent);
+
+    expect(results).to.be.deep.eq([
+      BigInt(defaultSrcAmount.toNumber() - resolverPremium),
+      BigInt(resolverPremium),
+    ]);
+  });
+
+  it("Resolver can't take the cancellation premium in src tokens without providing resolver-src-ata", async () => {
+    const escrow = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: state.orderConfig({
+        srcAmount: defaultSrcAmount,
+        fee: {
+          maxCancellationPremium: new anchor.BN(0),
+          maxSrcCancellationPremium: defaultMaxCancellationPremium,
+          protocolDstAcc: undefined,
+          integratorDstAcc: undefined,
+          protocolFee: undefined,
+          integratorFee: undefined,
+          surplusPercentage: undefined,
+        },
+        cancellationAuctionDuration: order.auctionDuration,
+      }),
+    });
+
+    await setCurrentTime(
+      context,
+      state.defaultExpirationTime + order.auctionDuration + 1
+    );
+
+    await expect(
+      program.methods
+        .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
+        .accountsPartial({
+          resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
+          maker: state.alice.keypair.publicKey,
+          makerReceiver: escrow.orderConfig.receiver,
+          srcMint: escrow.orderConfig.srcMint,
+          dstMint: escrow.orderConfig.dstMint,
+          escrow: escrow.escrow,
+          escrowSrcAta: escrow.ata,
+          protocolDstAcc: escrow.orderConfig.fee.protocolDstAcc,
+          integratorDstAcc: escrow.orderConfig.fee.integratorDstAcc,
+          srcTokenProgram: splToken.TOKEN_PROGRAM_ID,
+        })
+        .signers([payer, state.bob.keypair])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: MissingResolverSrcAta");
+  });
+
+  it("Maker can't create an escrow with both premiums in lamports and src tokens", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: state.orderConfig({
+          srcAmount: defaultSrcAmount,
+          fee: {
+            maxCancellationPremium: new anchor.BN(1),
+            maxSrcCancellationPremium: new anchor.BN(1),
+            protocolDstAcc: undefined,
+            integratorDstAcc: undefined,
+            protocolFee: undefined,
+            integratorFee: undefined,
+            surplusPercentage: undefined,
+          },
+          cancellationAuctionDuration: order.auctionDuration,
+        }),
+      })
+    ).to.be.rejectedWith("Error Code: InvalidCancellationFee");
+  });
+
+  it("Maker can't create an escrow if the src premium is greater than src amount", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: state.orderConfig({
+          srcAmount: defaultSrcAmount,
+          fee: {
+            maxCancellationPremium: new anchor.BN(0),
+            maxSrcCancellationPremium: defaultSrcAmount.addn(1),
+            protocolDstAcc: undefined,
+            integratorDstAcc: undefined,
+            protocolFee: undefined,
+            integratorFee: undefined,
+            surplusPercentage: undefined,
+          },
+          cancellationAuctionDuration: order.auctionDuration,
+        }),
+      })
+    ).to.be.rejectedWith("Error Code: InvalidCancellationFee");
+  });
+
+  it("Maker can't create an escrow with src premium if order was created with native src assets", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: state.orderConfig({
+          srcAmount: defaultSrcAmount,
+          srcMint: splToken.NATIVE_MINT,
+          srcAssetIsNative: true,
+          fee: {
+            maxCancellationPremium: new anchor.BN(0),
+            maxSrcCancellationPremium: new anchor.BN(1),
+            protocolDstAcc: undefined,
+            integratorDstAcc: undefined,
+            protocolFee: undefined,
+            integratorFee: undefined,
+            surplusPercentage: undefined,
+          },
+          cancellationAuctionDuration: order.auctionDuration,
+        }),
+      })
+    ).to.be.rejectedWith("Error Code: InvalidCancellationFee");
+  });
+
+  it("Resolver can cancel several orders in one batch, skipping closed ones", async () => {
+    const escrows = [];
+    for (let i = 0; i < 3; i++) {
+      escrows.push(
+        await state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider: banksClient,
+          orderConfig: state.orderConfig({
+            srcAmount: defaultSrcAmount,
+            fee: {
+              maxCancellationPremium: defaultMaxCancellationPremium,
+              protocolDstAcc: undefined,
+              integratorDstAcc: undefined,
+              protocolFee: undefined,
+              integratorFee: undefined,
+              surplusPercentage: undefined,
+            },
+            cancellationAuctionDuration: order.auctionDuration,
+          }),
+        })
+      );
+    }
+
+    await setCurrentTime(
+      context,
+      state.defaultExpirationTime + order.auctionDuration + 1
+    );
+
+    const cancelAccounts = (escrow) => ({
+      resolver: state.bob.keypair.publicKey,
+      resolverDelegate: null,
+      maker: state.alice.keypair.publicKey,
+      makerReceiver: escrow.orderConfig.receiver,
+      srcMint: escrow.orderConfig.srcMint,
+      dstMint: escrow.orderConfig.dstMint,
+      escrow: escrow.escrow,
+      escrowSrcAta: escrow.ata,
+      protocolDstAcc: escrow.orderConfig.fee.protocolDstAcc,
+      integratorDstAcc: escrow.orderConfig.fee.integratorDstAcc,
+      srcTokenProgram: splToken.TOKEN_PROGRAM_ID,
+    });
+
+    // The first order is already cancelled and must be skipped by the batch
+    await program.methods
+      .cancelByResolver(escrows[0].orderConfig, defaultRewardLimit)
+      .accountsPartial(cancelAccounts(escrows[0]))
+      .signers([payer, state.bob.keypair])
+      .rpc();
+
+    // Every order in the batch takes the same accounts as `cancel_by_resolver`
+    const remainingAccounts = [];
+    for (const escrow of escrows) {
+      const ix = await program.methods
+        .cancelByResolver(escrow.orderConfig, defaultRewardLimit)
+        .accountsPartial(cancelAccounts(escrow))
+        .instruction();
+      remainingAccounts.push(...ix.keys);
+    }
+
+    const makerNativeBalanceBefore = (
+      await provider.connection.getAccountInfo(state.alice.keypair.publicKey)
+    ).lamports;
+    const resolverNativeBalanceBefore = (
+      await provider.connection.getAccountInfo(state.bob.keypair.publicKey)
+    ).lamports;
+
+    const transactionPromise = () =>
+      program.methods
+        .cancelByResolverBatch(
+          escrows.map((escrow) => escrow.orderConfig),
+          defaultRewardLimit
+        )
+        .accountsPartial({
+          resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
+        })
+        .remainingAccounts(remainingAccounts)
+        .signers([payer, state.bob.keypair])
+        .rpc();
+
+    const results = await trackReceivedTokenAndTx(
+      provider.connection,
+      [
+        state.alice.atas[state.tokens[0].toString()].address,
+        state.bob.atas[state.tokens[0].toString()].address,
+      ],
+      transactionPromise
+    );
+
+    const resolverPremium = defaultMaxCancellationPremium.toNumber();
+
+    expect(
+      (await provider.connection.getAccountInfo(state.alice.keypair.publicKey))
+        .lamports
+    ).to.be.eq(
+      makerNativeBalanceBefore + 2 * (tokenAccountRent - resolverPremium)
+    );
+    expect(
+      (await provider.connection.getAccountInfo(state.bob.keypair.publicKey))
+        .lamports
+    ).to.be.eq(resolverNativeBalanceBefore + 2 * resolverPremium);
+
+    expect(results).to.be.deep.eq([
+      BigInt(2 * defaultSrcAmount.toNumber()),
+      BigInt(0),
+    ]);
+  });
+
+  it("Resolver can't cancel a batch with inconsistent number of accounts", async () => {
+    const escrow = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: state.orderConfig({
+        srcAmount: defaultSrcAmount,
+        fee: {
+          maxCancellationPremium: defaultMaxCancellationPremium,
+          protocolDstAcc: undefined,
+          integratorDstAcc: undefined,
+          protocolFee: undefined,
+          integratorFee: undefined,
+          surplusPercentage: undefined,
+        },
+        cancellationAuctionDuration: order.auctionDuration,
+      }),
+    });
+
+    await setCurrentTime(
+      context,
+      state.defaultExpirationTime + order.auctionDuration + 1
+    );
+
+    await expect(
+      program.methods
+        .cancelByResolverBatch([escrow.orderConfig], defaultRewardLimit)
+        .accountsPartial({
+          resolver: state.bob.keypair.publicKey,
+          resolverDelegate: null,
+        })
+        .remainingAccounts([
+          { pubkey: escrow.escrow, isWritable: false, isSigner: false },
+          { pubkey: escrow.ata, isWritable: true, isSigner: false },
+        ])
+        .signers([payer, state.bob.keypair])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: InconsistentBatchAccounts");
+  });
 });
@@ -73,7 +73,7 @@ describe("Dutch Auction", () => {
       payer,
       provider: banksClient,
       orderConfig: {
-        dutchAuctionData: auction,
+        dutchAuctionData: { piecewiseLinear: { 0: auction } },
       },
     });
   });
@@ -292,6 +292,155 @@ describe("Dutch Auction", () => {
     ]);
   });
 
+  it("should fill with the step rate between points of the step curve", async () => {
+    state.escrows[0] = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: {
+        dutchAuctionData: { step: { 0: auction } },
+      },
+    });
+
+    await setCurrentTime(
+      context,
+      auction.startTime +
+        auction.pointsAndTimeDeltas[0].timeDelta +
+        auction.pointsAndTimeDeltas[1].timeDelta / 4
+    );
+
+    const transactionPromise = () =>
+      program.methods
+        .fill(state.escrows[0].orderConfig, state.defaultSrcAmount)
+        .accountsPartial(state.buildAccountsDataForFill({}))
+        .signers([state.bob.keypair])
+        .rpc();
+
+    const results = await trackReceivedTokenAndTx(
+      provider.connection,
+      [
+        state.alice.atas[state.tokens[1].toString()].address,
+        state.bob.atas[state.tokens[0].toString()].address,
+        state.bob.atas[state.tokens[1].toString()].address,
+      ],
+      transactionPromise
+    );
+
+    const dstAmountWithRateBump = BigInt(
+      (state.defaultDstAmount.toNumber() *
+        (BASE_POINTS + auction.pointsAndTimeDeltas[0].rateBump)) /
+        BASE_POINTS
+    );
+    expect(results).to.be.deep.eq([
+      dstAmountWithRateBump,
+      BigInt(state.defaultSrcAmount.toNumber()),
+      -dstAmountWithRateBump,
+    ]);
+  });
+
+  it("should fill with initialRateBump during the plateau of the linear with plateau curve", async () => {
+    state.escrows[0] = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: {
+        dutchAuctionData: {
+          linearWithPlateau: {
+            0: {
+              startTime: auction.startTime,
+              duration: auction.duration,
+              initialRateBump: auction.initialRateBump,
+              plateauDuration: auction.duration / 2,
+            },
+          },
+        },
+      },
+    });
+
+    await setCurrentTime(context, auction.startTime + auction.duration / 4);
+
+    const transactionPromise = () =>
+      program.methods
+        .fill(state.escrows[0].orderConfig, state.defaultSrcAmount)
+        .accountsPartial(state.buildAccountsDataForFill({}))
+        .signers([state.bob.keypair])
+        .rpc();
+
+    const results = await trackReceivedTokenAndTx(
+      provider.connection,
+      [
+        state.alice.atas[state.tokens[1].toString()].address,
+        state.bob.atas[state.tokens[0].toString()].address,
+        state.bob.atas[state.tokens[1].toString()].address,
+      ],
+      transactionPromise
+    );
+
+    const dstAmountWithRateBump = BigInt(
+      (state.defaultDstAmount.toNumber() *
+        (BASE_POINTS + auction.initialRateBump)) /
+        BASE_POINTS
+    );
+    expect(results).to.be.deep.eq([
+      dstAmountWithRateBump,
+      BigInt(state.defaultSrcAmount.toNumber()),
+      -dstAmountWithRateBump,
+    ]);
+  });
+
+  it("should fill with the halved rate after one half-life of the exponential decay curve", async () => {
+    const halfLife = 8000;
+    state.escrows[0] = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: {
+        dutchAuctionData: {
+          exponentialDecay: {
+            0: {
+              startTime: auction.startTime,
+              duration: auction.duration,
+              initialRateBump: auction.initialRateBump,
+              halfLife,
+            },
+          },
+        },
+      },
+    });
+
+    await setCurrentTime(context, auction.startTime + halfLife);
+
+    const transactionPromise = () =>
+      program.methods
+        .fill(state.escrows[0].orderConfig, state.defaultSrcAmount)
+        .accountsPartial(state.buildAccountsDataForFill({}))
+        .signers([state.bob.keypair])
+        .rpc();
+
+    const results = await trackReceivedTokenAndTx(
+      provider.connection,
+      [
+        state.alice.atas[state.tokens[1].toString()].address,
+        state.bob.atas[state.tokens[0].toString()].address,
+        state.bob.atas[state.tokens[1].toString()].address,
+      ],
+      transactionPromise
+    );
+
+    const dstAmountWithRateBump = BigInt(
+      Math.ceil(
+        (state.defaultDstAmount.toNumber() *
+          (BASE_POINTS + auction.initialRateBump / 2)) /
+          BASE_POINTS
+      )
+    );
+    expect(results).to.be.deep.eq([
+      dstAmountWithRateBump,
+      BigInt(state.defaultSrcAmount.toNumber()),
+      -dstAmountWithRateBump,
+    ]);
+  });
+
   it("Execute the trade with surplus", async () => {
     state.escrows[0] = await state.createEscrow({
       escrowProgram: program,
@@ -307,7 +456,7 @@ describe("Dutch Auction", () => {
           integratorFee: undefined,
           maxCancellationPremium: undefined,
         },
-        dutchAuctionData: auction,
+        dutchAuctionData: { piecewiseLinear: { 0: auction } },
       },
     });
 
@@ -354,6 +503,114 @@ describe("Dutch Auction", () => {
     ]);
   });
 
+  it("Execute the trade with surplus split between maker, protocol, integrator and resolver", async () => {
+    state.escrows[0] = await state.createEscrow({
+      escrowProgram: program,
+      payer,
+      provider: banksClient,
+      orderConfig: {
+        fee: {
+          protocolDstAcc:
+            state.charlie.atas[state.tokens[1].toString()].address,
+          integratorDstAcc: state.dave.atas[state.tokens[1].toString()].address,
+          surplusPercentage: 20, // 20%
+          integratorSurplusPercentage: 30, // 30%
+          resolverSurplusPercentage: 10, // 10%
+          protocolFee: undefined,
+          integratorFee: undefined,
+          maxCancellationPremium: undefined,
+        },
+        dutchAuctionData: { piecewiseLinear: { 0: auction } },
+      },
+    });
+
+    const fillMethod = () =>
+      program.methods
+        .fill(state.escrows[0].orderConfig, state.defaultSrcAmount)
+        .accountsPartial(
+          state.buildAccountsDataForFill({
+            escrow: state.escrows[0].escrow,
+            escrowSrcAta: state.escrows[0].ata,
+            protocolDstAcc:
+              state.charlie.atas[state.tokens[1].toString()].address,
+            integratorDstAcc:
+              state.dave.atas[state.tokens[1].toString()].address,
+          })
+        )
+        .signers([state.bob.keypair]);
+
+    const { events } = await fillMethod().simulate();
+
+    const results = await trackReceivedTokenAndTx(
+      provider.connection,
+      [
+        state.alice.atas[state.tokens[1].toString()].address,
+        state.bob.atas[state.tokens[0].toString()].address,
+        state.bob.atas[state.tokens[1].toString()].address,
+        state.charlie.atas[state.tokens[1].toString()].address,
+        state.dave.atas[state.tokens[1].toString()].address,
+      ],
+      () => fillMethod().rpc()
+    );
+
+    const dstAmountWithRateBump = BigInt(
+      (state.defaultDstAmount.toNumber() *
+        (BASE_POINTS + auction.initialRateBump)) /
+        BASE_POINTS
+    );
+    const surplus =
+      dstAmountWithRateBump - BigInt(state.defaultDstAmount.toNumber());
+    const protocolSurplus = (surplus * 20n) / 100n;
+    const integratorSurplus = (surplus * 30n) / 100n;
+    const resolverSurplus = (surplus * 10n) / 100n;
+    const makerSurplus =
+      surplus - protocolSurplus - integratorSurplus - resolverSurplus;
+
+    expect(results).to.be.deep.eq([
+      BigInt(state.defaultDstAmount.toNumber()) + makerSurplus,
+      BigInt(state.defaultSrcAmount.toNumber()),
+      -(dstAmountWithRateBump - resolverSurplus),
+      protocolSurplus,
+      integratorSurplus,
+    ]);
+
+    expect(events.length).to.be.eq(1);
+    expect(events[0].name).to.be.eq("orderFilled");
+    const event = events[0].data;
+    expect(BigInt(event.dstAmount.toString())).to.be.eq(dstAmountWithRateBump);
+    expect(BigInt(event.makerSurplus.toString())).to.be.eq(makerSurplus);
+    expect(BigInt(event.protocolSurplus.toString())).to.be.eq(protocolSurplus);
+    expect(BigInt(event.integratorSurplus.toString())).to.be.eq(
+      integratorSurplus
+    );
+    expect(BigInt(event.resolverSurplus.toString())).to.be.eq(resolverSurplus);
+  });
+
+  it("Doesn't create escrow with surplus split above 100%", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: {
+          fee: {
+            protocolDstAcc:
+              state.charlie.atas[state.tokens[1].toString()].address,
+            integratorDstAcc:
+              state.dave.atas[state.tokens[1].toString()].address,
+            surplusPercentage: 50, // 50%
+            integratorSurplusPercentage: 40, // 40%
+            resolverSurplusPercentage: 11, // 11%
+            protocolFee: undefined,
+            integratorFee: undefined,
+            maxCancellationPremium: undefined,
+          },
+          dutchAuctionData: { piecewiseLinear: { 0: auction } },
+        },
+      })
+    ).to.be.rejectedWith("Error Code: InvalidSurplusSplit");
+  });
+
   it("Execute the trade with all fees", async () => {
     const auction = {
       startTime: Math.floor(new Date().getTime() / 1000),
@@ -376,7 +633,7 @@ describe("Dutch Auction", () => {
           surplusPercentage: 50, // 50%
           maxCancellationPremium: undefined,
         },
-        dutchAuctionData: auction,
+        dutchAuctionData: { piecewiseLinear: { 0: auction } },
       },
     });
 
@@ -433,4 +690,90 @@ describe("Dutch Auction", () => {
       integratorFee,
     ]);
   });
+
+  it("Doesn't create escrow with too many auction points", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: {
+          dutchAuctionData: {
+            piecewiseLinear: {
+              0: {
+                ...auction,
+                pointsAndTimeDeltas: Array(17).fill({
+                  rateBump: 0,
+                  timeDelta: 1,
+                }),
+              },
+            },
+          },
+        },
+      })
+    ).to.be.rejectedWith("Error Code: TooManyAuctionPoints");
+  });
+
+  it("Doesn't create escrow with increasing auction rate bumps", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: {
+          dutchAuctionData: {
+            step: {
+              0: {
+                ...auction,
+                pointsAndTimeDeltas: [
+                  { rateBump: 10000, timeDelta: 10000 },
+                  { rateBump: 20000, timeDelta: 10000 },
+                ],
+              },
+            },
+          },
+        },
+      })
+    ).to.be.rejectedWith("Error Code: IncreasingAuctionRateBump");
+  });
+
+  it("Doesn't create escrow with auction points beyond the duration", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: {
+          dutchAuctionData: {
+            piecewiseLinear: {
+              0: {
+                ...auction,
+                pointsAndTimeDeltas: [
+                  { rateBump: 20000, timeDelta: 20000 },
+                  { rateBump: 10000, timeDelta: 20000 },
+                ],
+              },
+            },
+          },
+        },
+      })
+    ).to.be.rejectedWith("Error Code: AuctionPointsExceedDuration");
+  });
+
+  it("Doesn't create escrow with auction start after expiration", async () => {
+    await expect(
+      state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider: banksClient,
+        orderConfig: {
+          dutchAuctionData: {
+            piecewiseLinear: {
+              0: { ...auction, startTime: state.defaultExpirationTime + 1 },
+            },
+          },
+        },
+      })
+    ).to.be.rejectedWith("Error Code: AuctionStartsAfterExpiration");
+  });
 });
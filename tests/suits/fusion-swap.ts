@@ -21,7 +21,10 @@ import {
   initializeLookupTable,
   sendV0Transaction,
 } from "../utils/lookupTables";
-import { calculateOrderHash } from "../../scripts/utils";
+import {
+  calculateOrderHash,
+  findFeeVaultAuthorityAddress,
+} from "../../scripts/utils";
 chai.use(chaiAsPromised);
 
 describe("Fusion Swap", () => {
@@ -775,6 +778,84 @@ describe("Fusion Swap", () => {
       ]);
     });
 
+    it("Execute the trade with integrator fee shares", async () => {
+      const escrow = await state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider,
+        orderConfig: {
+          fee: {
+            integratorDstAcc:
+              state.charlie.atas[state.tokens[1].toString()].address,
+            integratorFee: 10000, // 10%
+            integratorFeeShares: [
+              {
+                recipient: state.dave.atas[state.tokens[1].toString()].address,
+                fee: 5000, // 5%
+              },
+              {
+                recipient:
+                  state.charlie.atas[state.tokens[1].toString()].address,
+                fee: 2000, // 2%
+              },
+            ],
+            protocolDstAcc: undefined,
+            protocolFee: undefined,
+            surplusPercentage: undefined,
+            maxCancellationPremium: undefined,
+          },
+        },
+      });
+
+      const transactionPromise = () =>
+        program.methods
+          .fill(escrow.orderConfig, state.defaultSrcAmount)
+          .accountsPartial(
+            state.buildAccountsDataForFill({
+              escrow: escrow.escrow,
+              escrowSrcAta: escrow.ata,
+              integratorDstAcc:
+                state.charlie.atas[state.tokens[1].toString()].address,
+            })
+          )
+          .remainingAccounts(
+            escrow.orderConfig.fee.integratorFeeShares.map((share) => ({
+              pubkey: share.recipient,
+              isWritable: true,
+              isSigner: false,
+            }))
+          )
+          .signers([state.bob.keypair])
+          .rpc();
+
+      const results = await trackReceivedTokenAndTx(
+        provider.connection,
+        [
+          state.alice.atas[state.tokens[1].toString()].address,
+          state.bob.atas[state.tokens[0].toString()].address,
+          state.bob.atas[state.tokens[1].toString()].address,
+          state.charlie.atas[state.tokens[1].toString()].address,
+          state.dave.atas[state.tokens[1].toString()].address,
+        ],
+        transactionPromise
+      );
+      await expect(
+        splToken.getAccount(provider.connection, escrow.ata)
+      ).to.be.rejectedWith(splToken.TokenAccountNotFoundError);
+
+      const dstAmount = state.defaultDstAmount.toNumber();
+      const integratorFee = Math.floor((dstAmount * 10) / 100);
+      const firstShare = Math.floor((dstAmount * 5) / 100);
+      const secondShare = Math.floor((dstAmount * 2) / 100);
+      expect(results).to.be.deep.eq([
+        BigInt(dstAmount - integratorFee - firstShare - secondShare),
+        BigInt(state.defaultSrcAmount.toNumber()),
+        -BigInt(dstAmount),
+        BigInt(integratorFee + secondShare),
+        BigInt(firstShare),
+      ]);
+    });
+
     it("Execute the trade with native tokens (SOL) as destination + integrator fee", async () => {
       const makerNativeTokenBalanceBefore =
         await provider.connection.getBalance(state.alice.keypair.publicKey);
@@ -954,6 +1035,235 @@ describe("Fusion Swap", () => {
       ]);
     });
 
+    describe("Fee vaults", () => {
+      let feeVaultAuthority: anchor.web3.PublicKey;
+
+      before(async () => {
+        feeVaultAuthority = findFeeVaultAuthorityAddress(
+          program.programId,
+          state.charlie.keypair.publicKey
+        );
+      });
+
+      const createFeeVault = async (mint: anchor.web3.PublicKey) =>
+        (
+          await splToken.getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            payer,
+            mint,
+            feeVaultAuthority,
+            true
+          )
+        ).address;
+
+      const claimFees = (
+        beneficiary: anchor.web3.Keypair,
+        mint: anchor.web3.PublicKey,
+        feeVault: anchor.web3.PublicKey,
+        beneficiaryDstAcc: anchor.web3.PublicKey
+      ) =>
+        program.methods
+          .claimFees()
+          .accountsPartial({
+            beneficiary: beneficiary.publicKey,
+            mint,
+            feeVault,
+            beneficiaryDstAcc,
+            tokenProgram: splToken.TOKEN_PROGRAM_ID,
+          })
+          .signers([beneficiary])
+          .rpc();
+
+      it("Execute the trade with protocol fee credited to the fee vault and claim it", async () => {
+        const feeVault = await createFeeVault(state.tokens[1]);
+
+        const escrow = await state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider,
+          orderConfig: {
+            fee: {
+              protocolDstAcc: feeVault,
+              protocolFee: 10000, // 10%
+              integratorDstAcc: undefined,
+              integratorFee: undefined,
+              surplusPercentage: undefined,
+              maxCancellationPremium: undefined,
+            },
+          },
+        });
+
+        const fillResults = await trackReceivedTokenAndTx(
+          provider.connection,
+          [state.alice.atas[state.tokens[1].toString()].address, feeVault],
+          () =>
+            program.methods
+              .fill(escrow.orderConfig, state.defaultSrcAmount)
+              .accountsPartial(
+                state.buildAccountsDataForFill({
+                  escrow: escrow.escrow,
+                  escrowSrcAta: escrow.ata,
+                  protocolDstAcc: feeVault,
+                })
+              )
+              .signers([state.bob.keypair])
+              .rpc()
+        );
+        const fee = BigInt(state.defaultDstAmount.toNumber() / 10);
+        expect(fillResults).to.be.deep.eq([
+          BigInt(state.defaultDstAmount.toNumber()) - fee,
+          fee,
+        ]);
+
+        const claimResults = await trackReceivedTokenAndTx(
+          provider.connection,
+          [feeVault, state.charlie.atas[state.tokens[1].toString()].address],
+          () =>
+            claimFees(
+              state.charlie.keypair,
+              state.tokens[1],
+              feeVault,
+              state.charlie.atas[state.tokens[1].toString()].address
+            )
+        );
+        expect(claimResults).to.be.deep.eq([-fee, fee]);
+      });
+
+      it("Claim native fees credited to the wrapped SOL fee vault", async () => {
+        const feeVault = await createFeeVault(splToken.NATIVE_MINT);
+
+        const escrow = await state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider,
+          orderConfig: {
+            dstAssetIsNative: true,
+            dstMint: splToken.NATIVE_MINT,
+            fee: {
+              protocolDstAcc: feeVault,
+              protocolFee: 10000, // 10%
+              integratorDstAcc: undefined,
+              integratorFee: undefined,
+              surplusPercentage: undefined,
+              maxCancellationPremium: undefined,
+            },
+          },
+        });
+
+        await program.methods
+          .fill(escrow.orderConfig, state.defaultSrcAmount)
+          .accountsPartial(
+            state.buildAccountsDataForFill({
+              escrow: escrow.escrow,
+              escrowSrcAta: escrow.ata,
+              dstMint: splToken.NATIVE_MINT,
+              makerDstAta: null,
+              takerDstAta: null,
+              protocolDstAcc: feeVault,
+            })
+          )
+          .signers([state.bob.keypair])
+          .rpc();
+
+        const results = await trackReceivedTokenAndTx(
+          provider.connection,
+          [state.charlie.atas[splToken.NATIVE_MINT.toString()].address],
+          () =>
+            claimFees(
+              state.charlie.keypair,
+              splToken.NATIVE_MINT,
+              feeVault,
+              state.charlie.atas[splToken.NATIVE_MINT.toString()].address
+            )
+        );
+        expect(results).to.be.deep.eq([
+          BigInt(state.defaultDstAmount.toNumber() / 10),
+        ]);
+      });
+
+      it("Doesn't claim fees from the fee vault of another beneficiary", async () => {
+        const feeVault = await createFeeVault(state.tokens[1]);
+
+        await expect(
+          claimFees(
+            state.dave.keypair,
+            state.tokens[1],
+            feeVault,
+            state.dave.atas[state.tokens[1].toString()].address
+          )
+        ).to.be.rejectedWith("Error Code: ConstraintAssociated");
+      });
+
+      it("Doesn't claim fees from the empty fee vault", async () => {
+        const feeVault = await createFeeVault(state.tokens[1]);
+
+        await expect(
+          claimFees(
+            state.charlie.keypair,
+            state.tokens[1],
+            feeVault,
+            state.charlie.atas[state.tokens[1].toString()].address
+          )
+        ).to.be.rejectedWith("Error Code: NoFeesToClaim");
+      });
+    });
+
+    describe("Quote", () => {
+      it("Quote the trade with protocol fee", async () => {
+        const escrow = await state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider,
+          orderConfig: {
+            fee: {
+              protocolDstAcc:
+                state.charlie.atas[state.tokens[1].toString()].address,
+              protocolFee: 10000, // 10%
+              integratorDstAcc: undefined,
+              integratorFee: undefined,
+              surplusPercentage: undefined,
+              maxCancellationPremium: undefined,
+            },
+          },
+        });
+
+        const quote = await program.methods
+          .quote(escrow.orderConfig, state.defaultSrcAmount)
+          .accountsPartial(
+            state.buildAccountsDataForFill({
+              escrow: escrow.escrow,
+              escrowSrcAta: escrow.ata,
+              protocolDstAcc:
+                state.charlie.atas[state.tokens[1].toString()].address,
+            })
+          )
+          .view();
+
+        expect(quote.rateBump.toNumber()).to.be.eq(0);
+        expect(quote.dstAmount.toNumber()).to.be.eq(
+          state.defaultDstAmount.toNumber()
+        );
+        expect(quote.feeAmounts.makerAmount.toNumber()).to.be.eq(
+          (state.defaultDstAmount.toNumber() * 9) / 10
+        );
+        expect(quote.feeAmounts.protocolFee.toNumber()).to.be.eq(
+          state.defaultDstAmount.toNumber() / 10
+        );
+      });
+
+      it("Doesn't quote the trade with exchange amount more than escrow has", async () => {
+        await expect(
+          program.methods
+            .quote(
+              state.escrows[0].orderConfig,
+              state.defaultSrcAmount.muln(10)
+            )
+            .accountsPartial(state.buildAccountsDataForFill({}))
+            .view()
+        ).to.be.rejectedWith("Error Code: NotEnoughTokensInEscrow");
+      });
+    });
+
     it("Doesn't execute the trade with exchange amount more than escow has (src token)", async () => {
       await expect(
         program.methods
@@ -1592,6 +1902,167 @@ describe("Fusion Swap", () => {
       ).to.be.rejectedWith("Error Code: ConstraintSeeds");
     });
 
+    it("Doesn't execute the trade with the wrong integrator fee share recipient", async () => {
+      const escrow = await state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider,
+        orderConfig: {
+          fee: {
+            integratorFeeShares: [
+              {
+                recipient: state.dave.atas[state.tokens[1].toString()].address,
+                fee: 5000, // 5%
+              },
+            ],
+            integratorDstAcc: undefined,
+            integratorFee: undefined,
+            protocolDstAcc: undefined,
+            protocolFee: undefined,
+            surplusPercentage: undefined,
+            maxCancellationPremium: undefined,
+          },
+        },
+      });
+
+      await expect(
+        program.methods
+          .fill(escrow.orderConfig, state.defaultSrcAmount)
+          .accountsPartial(
+            state.buildAccountsDataForFill({
+              escrow: escrow.escrow,
+              escrowSrcAta: escrow.ata,
+            })
+          )
+          .remainingAccounts([
+            {
+              pubkey: state.charlie.atas[state.tokens[1].toString()].address,
+              isWritable: true,
+              isSigner: false,
+            },
+          ])
+          .signers([state.bob.keypair])
+          .rpc()
+      ).to.be.rejectedWith("Error Code: InconsistentIntegratorFeeShares");
+    });
+
+    it("Doesn't execute the trade without integrator fee share recipients", async () => {
+      const escrow = await state.createEscrow({
+        escrowProgram: program,
+        payer,
+        provider,
+        orderConfig: {
+          fee: {
+            integratorFeeShares: [
+              {
+                recipient: state.dave.atas[state.tokens[1].toString()].address,
+                fee: 5000, // 5%
+              },
+            ],
+            integratorDstAcc: undefined,
+            integratorFee: undefined,
+            protocolDstAcc: undefined,
+            protocolFee: undefined,
+            surplusPercentage: undefined,
+            maxCancellationPremium: undefined,
+          },
+        },
+      });
+
+      await expect(
+        program.methods
+          .fill(escrow.orderConfig, state.defaultSrcAmount)
+          .accountsPartial(
+            state.buildAccountsDataForFill({
+              escrow: escrow.escrow,
+              escrowSrcAta: escrow.ata,
+            })
+          )
+          .signers([state.bob.keypair])
+          .rpc()
+      ).to.be.rejectedWith("Error Code: InconsistentIntegratorFeeShares");
+    });
+
+    it("Doesn't create escrow with too many integrator fee shares", async () => {
+      await expect(
+        state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider,
+          orderConfig: {
+            fee: {
+              integratorFeeShares: Array(4).fill({
+                recipient: state.dave.atas[state.tokens[1].toString()].address,
+                fee: 1000, // 1%
+              }),
+              integratorDstAcc: undefined,
+              integratorFee: undefined,
+              protocolDstAcc: undefined,
+              protocolFee: undefined,
+              surplusPercentage: undefined,
+              maxCancellationPremium: undefined,
+            },
+          },
+        })
+      ).to.be.rejectedWith("Error Code: TooManyIntegratorFeeShares");
+    });
+
+    it("Doesn't create escrow with zero integrator fee share", async () => {
+      await expect(
+        state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider,
+          orderConfig: {
+            fee: {
+              integratorFeeShares: [
+                {
+                  recipient:
+                    state.dave.atas[state.tokens[1].toString()].address,
+                  fee: 0,
+                },
+              ],
+              integratorDstAcc: undefined,
+              integratorFee: undefined,
+              protocolDstAcc: undefined,
+              protocolFee: undefined,
+              surplusPercentage: undefined,
+              maxCancellationPremium: undefined,
+            },
+          },
+        })
+      ).to.be.rejectedWith("Error Code: InvalidIntegratorFeeShare");
+    });
+
+    it("Doesn't create escrow with total fee above 100%", async () => {
+      await expect(
+        state.createEscrow({
+          escrowProgram: program,
+          payer,
+          provider,
+          orderConfig: {
+            fee: {
+              protocolDstAcc:
+                state.charlie.atas[state.tokens[1].toString()].address,
+              protocolFee: 50000, // 50%
+              integratorDstAcc:
+                state.charlie.atas[state.tokens[1].toString()].address,
+              integratorFee: 30000, // 30%
+              integratorFeeShares: [
+                {
+                  recipient:
+                    state.dave.atas[state.tokens[1].toString()].address,
+                  fee: 20001, // 20.001%
+                },
+              ],
+              surplusPercentage: undefined,
+              maxCancellationPremium: undefined,
+            },
+          },
+        })
+      ).to.be.rejectedWith("Error Code: InvalidTotalFee");
+    });
+
     it("Execute the multiple trades", async () => {
       let transactionPromise = () =>
         program.methods
@@ -1,9 +1,17 @@
 import * as anchor from "@coral-xyz/anchor";
 import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
+import { NATIVE_MINT } from "@solana/spl-token";
 import { LAMPORTS_PER_SOL } from "@solana/web3.js";
 import chai, { expect } from "chai";
 import chaiAsPromised from "chai-as-promised";
-import { debugLog, initializeWhitelist } from "../utils/utils";
+import {
+  ACCESS_VALID_FROM,
+  ACCESS_VALID_UNTIL,
+  ALL_PERMISSIONS,
+  PERMISSION_CANCEL,
+  debugLog,
+  initializeWhitelist,
+} from "../utils/utils";
 import { Whitelist } from "../../target/types/whitelist";
 
 chai.use(chaiAsPromised);
@@ -20,6 +28,21 @@ describe("Whitelist", () => {
   let newAuthority: anchor.web3.Keypair;
   let whitelistPDA: anchor.web3.PublicKey;
 
+  const whitelistStatePDA = () =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist_state")],
+      program.programId
+    )[0];
+
+  const resolverPagePDA = (page: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("resolver_page"),
+        new anchor.BN(page).toArrayLike(Buffer, "le", 4),
+      ],
+      program.programId
+    )[0];
+
   before(async () => {
     userToWhitelist = anchor.web3.Keypair.generate();
     await provider.connection.requestAirdrop(
@@ -46,9 +69,16 @@ describe("Whitelist", () => {
   it("Can register and deregister a user from whitelist", async () => {
     // Register the user
     await program.methods
-      .register(userToWhitelist.publicKey)
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
       .accountsPartial({
         authority: payer.publicKey,
+        payer: payer.publicKey,
       })
       .signers([payer])
       .rpc();
@@ -74,6 +104,115 @@ describe("Whitelist", () => {
     ).to.be.rejectedWith("Account does not exist");
   });
 
+  it("Lists registered users in the resolver registry", async () => {
+    await program.methods
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        "resolver"
+      )
+      .accountsPartial({
+        authority: payer.publicKey,
+        payer: payer.publicKey,
+      })
+      .signers([payer])
+      .rpc();
+
+    const whitelistAccount = await program.account.resolverAccess.fetch(
+      whitelistPDA
+    );
+    expect(whitelistAccount.resolver).to.eql(userToWhitelist.publicKey);
+    expect(whitelistAccount.label).to.be.equal("resolver");
+
+    const page = await program.account.resolverPage.fetch(
+      resolverPagePDA(whitelistAccount.registryPage)
+    );
+    expect(page.resolvers).to.deep.include(userToWhitelist.publicKey);
+    const resolverCount = (
+      await program.account.whitelistState.fetch(whitelistStatePDA())
+    ).resolverCount;
+
+    await program.methods
+      .deregister(userToWhitelist.publicKey)
+      .accountsPartial({
+        authority: payer.publicKey,
+      })
+      .signers([payer])
+      .rpc();
+
+    const pageAfter = await program.account.resolverPage.fetch(
+      resolverPagePDA(whitelistAccount.registryPage)
+    );
+    expect(pageAfter.resolvers).to.not.deep.include(userToWhitelist.publicKey);
+    const whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.resolverCount).to.be.equal(resolverCount - 1);
+  });
+
+  it("Refunds the rent of the access to its payer", async () => {
+    const payerBalance = await provider.connection.getBalance(
+      newAuthority.publicKey
+    );
+
+    await program.methods
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
+      .accountsPartial({
+        authority: payer.publicKey,
+        payer: newAuthority.publicKey,
+      })
+      .signers([payer, newAuthority])
+      .rpc();
+
+    const whitelistAccount = await program.account.resolverAccess.fetch(
+      whitelistPDA
+    );
+    expect(whitelistAccount.payer).to.eql(newAuthority.publicKey);
+    expect(
+      await provider.connection.getBalance(newAuthority.publicKey)
+    ).to.be.lessThan(payerBalance);
+
+    // The payer is resolved from the resolver access
+    await program.methods
+      .deregister(userToWhitelist.publicKey)
+      .accountsPartial({
+        authority: payer.publicKey,
+      })
+      .signers([payer])
+      .rpc();
+
+    expect(
+      await provider.connection.getBalance(newAuthority.publicKey)
+    ).to.be.equal(payerBalance);
+  });
+
+  it("Cannot register a user with a too long label", async () => {
+    await expect(
+      program.methods
+        .register(
+          userToWhitelist.publicKey,
+          ACCESS_VALID_FROM,
+          ACCESS_VALID_UNTIL,
+          ALL_PERMISSIONS,
+          "a".repeat(33)
+        )
+        .accountsPartial({
+          authority: payer.publicKey,
+          payer: payer.publicKey,
+        })
+        .signers([payer])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: LabelTooLong");
+  });
+
   it("Stores the canonical bump in the whitelist account", async () => {
     // Get the canonical bump
     const [, canonicalBump] = anchor.web3.PublicKey.findProgramAddressSync(
@@ -83,9 +222,16 @@ describe("Whitelist", () => {
 
     // Register the user
     await program.methods
-      .register(userToWhitelist.publicKey)
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
       .accountsPartial({
         authority: payer.publicKey,
+        payer: payer.publicKey,
       })
       .signers([payer])
       .rpc();
@@ -109,9 +255,16 @@ describe("Whitelist", () => {
   it("Cannot register the same user twice", async () => {
     // First registration
     await program.methods
-      .register(userToWhitelist.publicKey)
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
       .accountsPartial({
         authority: payer.publicKey,
+        payer: payer.publicKey,
       })
       .signers([payer])
       .rpc();
@@ -119,9 +272,16 @@ describe("Whitelist", () => {
     // Second registration should fail
     await expect(
       program.methods
-        .register(userToWhitelist.publicKey)
+        .register(
+          userToWhitelist.publicKey,
+          ACCESS_VALID_FROM,
+          ACCESS_VALID_UNTIL,
+          ALL_PERMISSIONS,
+          ""
+        )
         .accountsPartial({
           authority: payer.publicKey,
+          payer: payer.publicKey,
         })
         .signers([payer])
         .rpc()
@@ -137,35 +297,161 @@ describe("Whitelist", () => {
       .rpc();
   });
 
-  it("Can set authority to new account", async () => {
-    // Set new authority
+  it("Can propose new authority", async () => {
     await program.methods
-      .setAuthority(newAuthority.publicKey)
+      .proposeAuthority(newAuthority.publicKey)
       .accountsPartial({
         currentAuthority: payer.publicKey,
       })
       .signers([payer])
       .rpc();
 
-    // Verify the new authority is set correctly
-    const [whitelistStatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("whitelist_state")],
-      program.programId
+    // Verify the authority is not changed until the transfer is accepted
+    const whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.authority.toString()).to.equal(
+      payer.publicKey.toString()
+    );
+    expect(whitelistState.pendingAuthority.toString()).to.equal(
+      newAuthority.publicKey.toString()
     );
+  });
+
+  it("Only proposed authority can accept the transfer", async () => {
+    await expect(
+      program.methods
+        .acceptAuthority()
+        .accountsPartial({
+          pendingAuthority: userToWhitelist.publicKey,
+        })
+        .signers([userToWhitelist])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
+
+  it("Can accept proposed authority", async () => {
+    await program.methods
+      .acceptAuthority()
+      .accountsPartial({
+        pendingAuthority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    // Verify the new authority is set correctly
     const whitelistState = await program.account.whitelistState.fetch(
-      whitelistStatePDA
+      whitelistStatePDA()
     );
     expect(whitelistState.authority.toString()).to.equal(
       newAuthority.publicKey.toString()
     );
+    expect(whitelistState.pendingAuthority).to.be.null;
+  });
+
+  it("New authority can manage roles", async () => {
+    // Roles are not transferred with the authority
+    await expect(
+      program.methods
+        .register(
+          userToWhitelist.publicKey,
+          ACCESS_VALID_FROM,
+          ACCESS_VALID_UNTIL,
+          ALL_PERMISSIONS,
+          ""
+        )
+        .accountsPartial({
+          authority: newAuthority.publicKey,
+          payer: payer.publicKey,
+        })
+        .signers([newAuthority])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+
+    await program.methods
+      .grantRole({ operator: {} }, newAuthority.publicKey)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+    await program.methods
+      .revokeRole({ operator: {} }, payer.publicKey)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    const whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.operators.map((key) => key.toString())).to.eql([
+      newAuthority.publicKey.toString(),
+    ]);
+  });
+
+  it("Previous authority cannot manage roles", async () => {
+    await expect(
+      program.methods
+        .grantRole({ operator: {} }, payer.publicKey)
+        .accountsPartial({
+          authority: payer.publicKey,
+        })
+        .signers([payer])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
+
+  it("Guardian cannot register users", async () => {
+    await program.methods
+      .grantRole({ guardian: {} }, userToWhitelist.publicKey)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    await expect(
+      program.methods
+        .register(
+          userToWhitelist.publicKey,
+          ACCESS_VALID_FROM,
+          ACCESS_VALID_UNTIL,
+          ALL_PERMISSIONS,
+          ""
+        )
+        .accountsPartial({
+          authority: userToWhitelist.publicKey,
+          payer: payer.publicKey,
+        })
+        .signers([userToWhitelist])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+
+    // Cleanup
+    await program.methods
+      .revokeRole({ guardian: {} }, userToWhitelist.publicKey)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
   });
 
   it("New authority can register and deregister users", async () => {
     // New authority should be able to register a user
     await program.methods
-      .register(userToWhitelist.publicKey)
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
       .accountsPartial({
         authority: newAuthority.publicKey,
+        payer: payer.publicKey,
       })
       .signers([newAuthority])
       .rpc();
@@ -194,9 +480,16 @@ describe("Whitelist", () => {
   it("Cannot register with wrong authority", async () => {
     await expect(
       program.methods
-        .register(userToWhitelist.publicKey)
+        .register(
+          userToWhitelist.publicKey,
+          ACCESS_VALID_FROM,
+          ACCESS_VALID_UNTIL,
+          ALL_PERMISSIONS,
+          ""
+        )
         .accountsPartial({
           authority: userToWhitelist.publicKey,
+          payer: payer.publicKey,
         })
         .signers([userToWhitelist])
         .rpc()
@@ -206,9 +499,16 @@ describe("Whitelist", () => {
   it("Cannot deregister with wrong authority", async () => {
     // First register the user
     await program.methods
-      .register(userToWhitelist.publicKey)
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
       .accountsPartial({
         authority: newAuthority.publicKey,
+        payer: payer.publicKey,
       })
       .signers([newAuthority])
       .rpc();
@@ -234,13 +534,20 @@ describe("Whitelist", () => {
       .rpc();
   });
 
-  it("Previous authority cannot register or deregister users", async () => {
-    // Previous authority should not be able to register a user
+  it("Revoked operator cannot register or deregister users", async () => {
+    // Previous authority is no longer an operator and cannot register a user
     await expect(
       program.methods
-        .register(userToWhitelist.publicKey)
+        .register(
+          userToWhitelist.publicKey,
+          ACCESS_VALID_FROM,
+          ACCESS_VALID_UNTIL,
+          ALL_PERMISSIONS,
+          ""
+        )
         .accountsPartial({
           authority: payer.publicKey,
+          payer: payer.publicKey,
         })
         .signers([payer])
         .rpc()
@@ -248,9 +555,16 @@ describe("Whitelist", () => {
 
     // Register user with new authority for deregister test
     await program.methods
-      .register(userToWhitelist.publicKey)
+      .register(
+        userToWhitelist.publicKey,
+        ACCESS_VALID_FROM,
+        ACCESS_VALID_UNTIL,
+        ALL_PERMISSIONS,
+        ""
+      )
       .accountsPartial({
         authority: newAuthority.publicKey,
+        payer: payer.publicKey,
       })
       .signers([newAuthority])
       .rpc();
@@ -267,7 +581,7 @@ describe("Whitelist", () => {
     ).to.be.rejectedWith("Error Code: Unauthorized");
   });
 
-  it("Non-authority cannot set authority", async () => {
+  it("Non-authority cannot propose authority", async () => {
     const randomUser = anchor.web3.Keypair.generate();
     await provider.connection.requestAirdrop(
       randomUser.publicKey,
@@ -276,7 +590,7 @@ describe("Whitelist", () => {
 
     await expect(
       program.methods
-        .setAuthority(newAuthority.publicKey)
+        .proposeAuthority(randomUser.publicKey)
         .accountsPartial({
           currentAuthority: randomUser.publicKey,
         })
@@ -284,4 +598,269 @@ describe("Whitelist", () => {
         .rpc()
     ).to.be.rejectedWith("Error Code: Unauthorized");
   });
+
+  it("Can cancel authority transfer", async () => {
+    await program.methods
+      .proposeAuthority(payer.publicKey)
+      .accountsPartial({
+        currentAuthority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    await program.methods
+      .cancelAuthorityTransfer()
+      .accountsPartial({
+        currentAuthority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    const whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.pendingAuthority).to.be.null;
+
+    // Cancelled proposal can not be accepted
+    await expect(
+      program.methods
+        .acceptAuthority()
+        .accountsPartial({
+          pendingAuthority: payer.publicKey,
+        })
+        .signers([payer])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
+
+  it("Cannot cancel without pending authority transfer", async () => {
+    await expect(
+      program.methods
+        .cancelAuthorityTransfer()
+        .accountsPartial({
+          currentAuthority: newAuthority.publicKey,
+        })
+        .signers([newAuthority])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: NoPendingAuthority");
+  });
+
+  it("Can renew user access", async () => {
+    // The user is left registered by the previous tests
+    const validUntil = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    await program.methods
+      .renew(userToWhitelist.publicKey, validUntil)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    const resolverAccess = await program.account.resolverAccess.fetch(
+      whitelistPDA
+    );
+    expect(resolverAccess.validUntil.toString()).to.equal(
+      validUntil.toString()
+    );
+  });
+
+  it("Cannot renew user access with wrong authority", async () => {
+    await expect(
+      program.methods
+        .renew(userToWhitelist.publicKey, ACCESS_VALID_UNTIL)
+        .accountsPartial({
+          authority: userToWhitelist.publicKey,
+        })
+        .signers([userToWhitelist])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
+
+  it("Can suspend and reinstate user", async () => {
+    await program.methods
+      .suspend(userToWhitelist.publicKey)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    let resolverAccess = await program.account.resolverAccess.fetch(
+      whitelistPDA
+    );
+    expect(resolverAccess.status).to.eql({ suspended: {} });
+
+    await program.methods
+      .reinstate(userToWhitelist.publicKey)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    resolverAccess = await program.account.resolverAccess.fetch(whitelistPDA);
+    expect(resolverAccess.status).to.eql({ active: {} });
+  });
+
+  it("Cannot suspend user without guardian or operator role", async () => {
+    await expect(
+      program.methods
+        .suspend(userToWhitelist.publicKey)
+        .accountsPartial({
+          authority: userToWhitelist.publicKey,
+        })
+        .signers([userToWhitelist])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
+
+  it("Can update user permissions", async () => {
+    await program.methods
+      .updatePermissions(userToWhitelist.publicKey, PERMISSION_CANCEL)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    const resolverAccess = await program.account.resolverAccess.fetch(
+      whitelistPDA
+    );
+    expect(resolverAccess.permissions).to.equal(PERMISSION_CANCEL);
+  });
+
+  it("Cannot update user permissions with unknown bits", async () => {
+    await expect(
+      program.methods
+        .updatePermissions(userToWhitelist.publicKey, ALL_PERMISSIONS + 1)
+        .accountsPartial({
+          authority: newAuthority.publicKey,
+        })
+        .signers([newAuthority])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: InvalidPermissions");
+  });
+
+  it("Resolver can add and remove a delegate", async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    const [delegatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("resolver_delegate"), delegate.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .addDelegate()
+      .accountsPartial({
+        resolver: userToWhitelist.publicKey,
+        delegate: delegate.publicKey,
+      })
+      .signers([userToWhitelist, delegate])
+      .rpc();
+
+    const resolverDelegate = await program.account.resolverDelegate.fetch(
+      delegatePDA
+    );
+    expect(resolverDelegate.resolver).to.eql(userToWhitelist.publicKey);
+
+    await program.methods
+      .removeDelegate(delegate.publicKey)
+      .accountsPartial({
+        resolver: userToWhitelist.publicKey,
+      })
+      .signers([userToWhitelist])
+      .rpc();
+
+    await expect(
+      program.account.resolverDelegate.fetch(delegatePDA)
+    ).to.be.rejectedWith("Account does not exist");
+  });
+
+  it("Unregistered user cannot add a delegate", async () => {
+    const delegate = anchor.web3.Keypair.generate();
+    await expect(
+      program.methods
+        .addDelegate()
+        .accountsPartial({
+          resolver: newAuthority.publicKey,
+          delegate: delegate.publicKey,
+        })
+        .signers([newAuthority, delegate])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: AccountNotInitialized");
+  });
+
+  it("Authority can set and clear the resolver root", async () => {
+    const root = Array(32).fill(1);
+    await program.methods
+      .setResolverRoot(root)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    let whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.resolverRoot).to.eql(root);
+
+    await program.methods
+      .setResolverRoot(null)
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.resolverRoot).to.be.null;
+  });
+
+  it("Non-authority cannot set the resolver root", async () => {
+    await expect(
+      program.methods
+        .setResolverRoot(Array(32).fill(1))
+        .accountsPartial({
+          authority: userToWhitelist.publicKey,
+        })
+        .signers([userToWhitelist])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
+
+  it("Authority can set the bond config", async () => {
+    // Fills keep working without a bond while the minimum bond is zero
+    await program.methods
+      .setBondConfig(new anchor.BN(0), new anchor.BN(86400))
+      .accountsPartial({
+        authority: newAuthority.publicKey,
+        bondMint: NATIVE_MINT,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    const whitelistState = await program.account.whitelistState.fetch(
+      whitelistStatePDA()
+    );
+    expect(whitelistState.bondMint.toString()).to.equal(
+      NATIVE_MINT.toString()
+    );
+    expect(whitelistState.minBond.toNumber()).to.equal(0);
+    expect(whitelistState.unbondingPeriod.toNumber()).to.equal(86400);
+  });
+
+  it("Non-authority cannot set the bond config", async () => {
+    await expect(
+      program.methods
+        .setBondConfig(new anchor.BN(1), new anchor.BN(86400))
+        .accountsPartial({
+          authority: userToWhitelist.publicKey,
+          bondMint: NATIVE_MINT,
+        })
+        .signers([userToWhitelist])
+        .rpc()
+    ).to.be.rejectedWith("Error Code: Unauthorized");
+  });
 });
@@ -108,7 +108,7 @@ export class TestState {
   defaultDstAmount = new anchor.BN(30);
   defaultExpirationTime = ~~(new Date().getTime() / 1000) + 86400; // now + 1 day
   auction = {
-    startTime: 0xffffffff - 32000, // default auction start in the far far future and order use default formula
+    startTime: 0, // default auction without rate bump, order uses default formula
     duration: 32000,
     initialRateBump: 0,
     pointsAndTimeDeltas: [],
@@ -240,6 +240,7 @@ export class TestState {
 
   buildAccountsDataForFill({
     taker = this.bob.keypair.publicKey,
+    resolverDelegate = null,
     maker = this.alice.keypair.publicKey,
     makerReceiver = this.alice.keypair.publicKey,
     srcMint = this.tokens[0],
@@ -256,6 +257,7 @@ export class TestState {
   }): any {
     return {
       taker,
+      resolverDelegate,
       maker,
       makerReceiver,
       srcMint,
@@ -379,7 +381,7 @@ export class TestState {
       srcAssetIsNative: false,
       dstAssetIsNative: false,
       receiver: this.alice.keypair.publicKey,
-      dutchAuctionData: this.auction,
+      dutchAuctionData: { piecewiseLinear: { 0: this.auction } },
       cancellationAuctionDuration: 0,
       srcMint: this.tokens[0],
       dstMint: this.tokens[1],
@@ -389,8 +391,12 @@ export class TestState {
         integratorDstAcc: null,
         protocolFee: 0,
         integratorFee: 0,
+        integratorFeeShares: [],
         surplusPercentage: 0,
+        integratorSurplusPercentage: 0,
+        resolverSurplusPercentage: 0,
         maxCancellationPremium: new anchor.BN(0),
+        maxSrcCancellationPremium: new anchor.BN(0),
         ...(fee ?? {}),
       },
     };
@@ -495,9 +501,16 @@ export async function createWhitelistedAccount(
   await initializeWhitelist(program, authority);
   // Register the user
   await program.methods
-    .register(user.publicKey)
+    .register(
+      user.publicKey,
+      ACCESS_VALID_FROM,
+      ACCESS_VALID_UNTIL,
+      ALL_PERMISSIONS,
+      ""
+    )
     .accountsPartial({
       authority: authority.publicKey,
+      payer: authority.publicKey,
     })
     .signers([authority])
     .rpc();
@@ -753,6 +766,14 @@ export async function waitForNewBlock(
   });
 }
 
+// Access period of the resolvers registered by the tests, valid at any time
+export const ACCESS_VALID_FROM = new anchor.BN(0);
+export const ACCESS_VALID_UNTIL = new anchor.BN("9223372036854775807");
+// Resolver permission bits allowing `fill` and `cancelByResolver`
+export const PERMISSION_FILL = 1 << 0;
+export const PERMISSION_CANCEL = 1 << 1;
+export const ALL_PERMISSIONS = PERMISSION_FILL | PERMISSION_CANCEL;
+
 // Anchor test fails with "Account does not exist <pubkey>" error when account does not exist
 export const ANCHOR_ACCOUNT_NOT_FOUND_ERROR_PREFIX = "Account does not exist";
 // Bankrun test fails with "Could not find <pubkey>" error when account does not exist
@@ -5,18 +5,56 @@ const FusionSwapIDL = require("../target/idl/fusion_swap.json");
 const escrowType = FusionSwapIDL.types.find((t) => t.name === "Escrow");
 export type Escrow = (typeof escrowType)["type"]["fields"];
 
-const auctionDataType = FusionSwapIDL.types.find(
-  (t) => t.name === "AuctionData"
-);
-export type AuctionData = (typeof auctionDataType)["type"]["fields"];
+export type PointAndTimeDelta = {
+  rateBump: number;
+  timeDelta: number;
+};
+
+export type PiecewiseLinearCurve = {
+  startTime: number;
+  duration: number;
+  initialRateBump: number;
+  pointsAndTimeDeltas: PointAndTimeDelta[];
+};
+
+export type ExponentialDecayCurve = {
+  startTime: number;
+  duration: number;
+  initialRateBump: number;
+  halfLife: number;
+};
+
+export type StepCurve = PiecewiseLinearCurve;
+
+export type LinearWithPlateauCurve = {
+  startTime: number;
+  duration: number;
+  initialRateBump: number;
+  plateauDuration: number;
+};
+
+export type AuctionData =
+  | { piecewiseLinear: { 0: PiecewiseLinearCurve } }
+  | { exponentialDecay: { 0: ExponentialDecayCurve } }
+  | { step: { 0: StepCurve } }
+  | { linearWithPlateau: { 0: LinearWithPlateauCurve } };
+
+export type IntegratorFeeShare = {
+  recipient: anchor.web3.PublicKey;
+  fee: number;
+};
 
 export type FeeConfig = {
   protocolDstAcc: anchor.web3.PublicKey | null;
   integratorDstAcc: anchor.web3.PublicKey | null;
   protocolFee: number;
   integratorFee: number;
+  integratorFeeShares?: IntegratorFeeShare[];
   surplusPercentage: number;
+  integratorSurplusPercentage?: number;
+  resolverSurplusPercentage?: number;
   maxCancellationPremium: anchor.BN;
+  maxSrcCancellationPremium?: anchor.BN;
 };
 
 export type OrderConfig = {